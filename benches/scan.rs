@@ -0,0 +1,76 @@
+//! 스캔 벤치마크.
+//!
+//! 합성 트리(기본 10만 개 파일)를 만들어 파일 시스템 `Walker`로 현재 스레드에서
+//! 순회하는 스캔(`jobs = 1`)과 여러 스레드로 나눠 순회하는 스캔을 비교합니다. 파일 수는
+//! `PS_ORGANIZER_BENCH_FILES` 환경 변수로 바꿀 수 있습니다.
+//!
+//! ```text
+//! cargo bench --bench scan
+//! ```
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use ps_organizer::events::NoopObserver;
+use ps_organizer::scanner::{ScanOptions, scan_directory_with};
+use std::env;
+use std::fs;
+use std::hint::black_box;
+use std::path::Path;
+use tempfile::TempDir;
+
+const DEFAULT_FILES: usize = 100_000;
+const FILES_PER_DIR: usize = 500;
+
+/// 문제 번호 구간별 폴더에 풀이 파일과 딸린 파일을 섞어 만듭니다.
+fn synthetic_tree(root: &Path, files: usize) {
+    for index in 0..files {
+        let number = 1000 + index;
+        let dir = root
+            .join(format!("{}", number / 10_000 * 10_000))
+            .join(format!("{}", number / FILES_PER_DIR * FILES_PER_DIR));
+        if index % FILES_PER_DIR == 0 {
+            fs::create_dir_all(&dir).unwrap();
+        }
+        let (name, contents) = match index % 4 {
+            0 => (format!("{number}.py"), format!("# BOJ {number}\n")),
+            1 => (format!("{number}.in"), String::new()),
+            _ => (
+                format!("{number}.cpp"),
+                format!("// https://www.acmicpc.net/problem/{number}\n"),
+            ),
+        };
+        fs::write(dir.join(name), contents).unwrap();
+    }
+}
+
+fn bench_scan(c: &mut Criterion) {
+    let files = env::var("PS_ORGANIZER_BENCH_FILES")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_FILES);
+    let temp = TempDir::new().unwrap();
+    synthetic_tree(temp.path(), files);
+
+    let mut group = c.benchmark_group(format!("scan_{files}"));
+    group.sample_size(10);
+    for header_lines in [0, 5] {
+        for jobs in [1, 0] {
+            let name = if jobs == 1 { "sequential" } else { "parallel" };
+            let id = BenchmarkId::new(name, format!("headers={header_lines}"));
+            let options = ScanOptions {
+                header_lines,
+                jobs,
+                ..ScanOptions::default()
+            };
+            group.bench_with_input(id, &options, |b, options| {
+                b.iter(|| {
+                    let report = scan_directory_with(temp.path(), options, &mut NoopObserver);
+                    black_box(report.entries.len())
+                });
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_scan);
+criterion_main!(benches);
@@ -0,0 +1,196 @@
+//! 재현 가능한 압축 파일 쓰기.
+//!
+//! 같은 파일 목록이면 언제 어디서 만들어도 바이트 단위로 같은 압축 파일이 나오도록
+//! 항목을 경로순으로 쓰고, 수정 시각(1980-01-01 00:00:00 UTC), 권한(`0644`),
+//! 소유자를 고정합니다.
+
+use flate2::{Compression, GzBuilder};
+use std::io::{self, Cursor, Write};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, DateTime, ZipWriter};
+
+/// 압축 파일 형식.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ArchiveFormat {
+    /// gzip으로 압축한 tar.
+    #[default]
+    #[value(name = "tar.gz")]
+    TarGz,
+    /// zip.
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// 파일 확장자 (점 제외).
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::TarGz => "tar.gz",
+            Self::Zip => "zip",
+        }
+    }
+}
+
+/// 압축 파일에 담을 파일 하나.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveFile {
+    /// 압축 파일 안의 경로 (`/`로 구분).
+    pub path: String,
+    /// 파일 내용.
+    pub contents: Vec<u8>,
+}
+
+/// 모든 항목에 쓰는 수정 시각 (1980-01-01 00:00:00 UTC, zip이 나타낼 수 있는 가장 이른 시각).
+const MTIME: u64 = 315_532_800;
+/// 모든 항목에 쓰는 권한.
+const MODE: u32 = 0o644;
+
+/// 파일 목록을 경로순으로 정렬해 압축 파일을 만듭니다.
+///
+/// # Errors
+///
+/// 압축 라이브러리가 항목을 쓰지 못하면 에러를 반환합니다.
+pub fn write_archive(format: ArchiveFormat, files: &[ArchiveFile]) -> io::Result<Vec<u8>> {
+    let mut sorted: Vec<&ArchiveFile> = files.iter().collect();
+    sorted.sort_by(|a, b| a.path.cmp(&b.path));
+    match format {
+        ArchiveFormat::TarGz => tar_gz(&sorted),
+        ArchiveFormat::Zip => zip(&sorted),
+    }
+}
+
+fn tar_gz(files: &[&ArchiveFile]) -> io::Result<Vec<u8>> {
+    // gzip 헤더의 시각은 0(없음)으로 둡니다.
+    let gzip = GzBuilder::new()
+        .mtime(0)
+        .write(Vec::new(), Compression::default());
+    let mut tar = tar::Builder::new(gzip);
+    for file in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Regular);
+        header.set_size(file.contents.len() as u64);
+        header.set_mode(MODE);
+        header.set_mtime(MTIME);
+        header.set_uid(0);
+        header.set_gid(0);
+        tar.append_data(&mut header, &file.path, file.contents.as_slice())?;
+    }
+    tar.into_inner()?.finish()
+}
+
+fn zip(files: &[&ArchiveFile]) -> io::Result<Vec<u8>> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    for file in files {
+        let options = SimpleFileOptions::default()
+            .compression_method(CompressionMethod::Deflated)
+            .last_modified_time(DateTime::default())
+            .unix_permissions(MODE)
+            .large_file(u32::try_from(file.contents.len()).is_err());
+        zip.start_file(file.path.as_str(), options)?;
+        zip.write_all(&file.contents)?;
+    }
+    Ok(zip.finish()?.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    fn file(path: &str, contents: &str) -> ArchiveFile {
+        ArchiveFile {
+            path: path.to_string(),
+            contents: contents.as_bytes().to_vec(),
+        }
+    }
+
+    /// tar.gz를 풀어 (경로, 권한, 수정 시각, 내용) 목록을 돌려줍니다.
+    fn read_tar_gz(data: &[u8]) -> Vec<(String, u32, u64, Vec<u8>)> {
+        let mut archive = tar::Archive::new(GzDecoder::new(data));
+        archive
+            .entries()
+            .unwrap()
+            .map(|entry| {
+                let mut entry = entry.unwrap();
+                let path = entry.path().unwrap().to_string_lossy().into_owned();
+                let mode = entry.header().mode().unwrap();
+                let mtime = entry.header().mtime().unwrap();
+                let mut contents = Vec::new();
+                entry.read_to_end(&mut contents).unwrap();
+                (path, mode, mtime, contents)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn archives_are_reproducible_and_sorted() {
+        let files = [
+            file("b/2000.py", "print(1)\n"),
+            file("a/1000.cpp", "int x;\n"),
+        ];
+        let reversed = [files[1].clone(), files[0].clone()];
+        for format in [ArchiveFormat::TarGz, ArchiveFormat::Zip] {
+            assert_eq!(
+                write_archive(format, &files).unwrap(),
+                write_archive(format, &reversed).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn tar_gz_entries_have_fixed_metadata() {
+        let gz = write_archive(
+            ArchiveFormat::TarGz,
+            &[
+                file("b/2000.py", "print(1)\n"),
+                file("a/1000.cpp", "int x;\n"),
+            ],
+        )
+        .unwrap();
+        // gzip 헤더의 수정 시각 칸은 비어 있습니다.
+        assert_eq!(&gz[4..8], &[0; 4]);
+        assert_eq!(
+            read_tar_gz(&gz),
+            [
+                ("a/1000.cpp".to_string(), MODE, MTIME, b"int x;\n".to_vec()),
+                ("b/2000.py".to_string(), MODE, MTIME, b"print(1)\n".to_vec()),
+            ]
+        );
+    }
+
+    #[test]
+    fn long_tar_paths_round_trip() {
+        let path = format!("{}/{}", "d".repeat(120), "f".repeat(150));
+        let gz = write_archive(ArchiveFormat::TarGz, &[file(&path, "x")]).unwrap();
+        let entries = read_tar_gz(&gz);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].0, path);
+    }
+
+    #[test]
+    fn zip_records_every_entry() {
+        let data = write_archive(
+            ArchiveFormat::Zip,
+            &[
+                file("b/2000.py", "print(1)\n"),
+                file("a/1000.cpp", "int x;\n"),
+            ],
+        )
+        .unwrap();
+        let mut zip = zip::ZipArchive::new(Cursor::new(data)).unwrap();
+        assert_eq!(zip.len(), 2);
+        for (i, (path, contents)) in [("a/1000.cpp", "int x;\n"), ("b/2000.py", "print(1)\n")]
+            .into_iter()
+            .enumerate()
+        {
+            let mut entry = zip.by_index(i).unwrap();
+            assert_eq!(entry.name(), path);
+            assert_eq!(entry.compression(), CompressionMethod::Deflated);
+            assert_eq!(entry.unix_mode().unwrap() & 0o777, MODE);
+            assert_eq!(entry.last_modified(), Some(DateTime::default()));
+            let mut read = String::new();
+            entry.read_to_string(&mut read).unwrap();
+            assert_eq!(read, contents);
+        }
+    }
+}
@@ -0,0 +1,534 @@
+//! 증분 스캔 캐시.
+//!
+//! 디렉토리마다 수정 시각, 장치/inode 번호, 하위 항목 목록을 대상 디렉토리의
+//! `.ps-organizer/scan-cache.json`에 저장합니다. 파일을 추가, 삭제, 이름 변경하면
+//! 그 파일이 든 디렉토리의 수정 시각이 바뀌므로, 다음 스캔은 수정 시각과 inode가
+//! 그대로인 디렉토리의 목록을 다시 읽지 않고 캐시를 씁니다.
+//!
+//! 캐시 형식이나 대상 경로가 맞지 않거나, 바뀌지 않았다는 디렉토리의 하위 폴더가
+//! 사라지는 등 캐시가 실제와 어긋나면 캐시를 버리고 전체를 다시 읽습니다.
+
+use crate::metadata::STATE_DIR;
+use crate::scanner::dir_error;
+use crate::types::Error;
+use crate::vfs::{FileSystem, Metadata};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::ffi::{OsStr, OsString};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub use crate::vfs::EntryKind;
+
+const FILE_NAME: &str = "scan-cache.json";
+
+/// 캐시 형식 버전. 형식이 바뀌면 올립니다.
+const VERSION: u32 = 1;
+
+/// 스캔 시작 직전 이 시간 안에 바뀐 디렉토리는 캐시에 남기지 않습니다.
+///
+/// 수정 시각의 정밀도가 낮은 파일 시스템에서는 같은 시각 안에 다시 바뀌어도
+/// 수정 시각이 그대로일 수 있기 때문입니다.
+const RACY_WINDOW: Duration = Duration::from_secs(2);
+
+/// 파일명. UTF-8이면 문자열로, 아니면 바이트 배열로 저장합니다.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+enum Name {
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+// 유닉스가 아닌 환경에서는 UTF-8이 아닌 이름을 저장하지 않으므로 `Option`을 돌려줍니다.
+#[cfg_attr(unix, allow(clippy::unnecessary_wraps))]
+impl Name {
+    fn encode(name: &OsStr) -> Option<Self> {
+        if let Some(text) = name.to_str() {
+            return Some(Self::Text(text.to_string()));
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+            Some(Self::Bytes(name.as_bytes().to_vec()))
+        }
+        #[cfg(not(unix))]
+        None
+    }
+
+    fn decode(&self) -> Option<OsString> {
+        match self {
+            Self::Text(text) => Some(OsString::from(text)),
+            #[cfg(unix)]
+            Self::Bytes(bytes) => {
+                use std::os::unix::ffi::OsStringExt;
+                Some(OsString::from_vec(bytes.clone()))
+            }
+            #[cfg(not(unix))]
+            Self::Bytes(_) => None,
+        }
+    }
+}
+
+/// 디렉토리의 수정 시각과 장치/inode 번호.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct Stamp {
+    secs: u64,
+    nanos: u32,
+    dev: u64,
+    ino: u64,
+}
+
+impl Stamp {
+    /// 믿을 수 있는 수정 시각이면 도장을 만듭니다.
+    fn new(metadata: &Metadata, started: SystemTime) -> Option<Self> {
+        let modified = metadata.modified?;
+        if modified + RACY_WINDOW >= started {
+            return None;
+        }
+        let since_epoch = modified.duration_since(UNIX_EPOCH).ok()?;
+        Some(Self {
+            secs: since_epoch.as_secs(),
+            nanos: since_epoch.subsec_nanos(),
+            dev: metadata.dev,
+            ino: metadata.ino,
+        })
+    }
+}
+
+/// 하위 항목.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Child {
+    name: Name,
+    kind: EntryKind,
+    /// 하위 디렉토리의 기록.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dir: Option<DirRecord>,
+}
+
+/// 디렉토리 하나의 기록.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct DirRecord {
+    /// 목록을 읽을 때의 도장. 믿을 수 없는 시각이면 `None`이며 다음에 다시 읽습니다.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    stamp: Option<Stamp>,
+    children: Vec<Child>,
+}
+
+/// 대상 디렉토리 전체의 스캔 캐시.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanCache {
+    version: u32,
+    /// 캐시를 만든 대상 디렉토리의 정규화된 경로.
+    root: String,
+    tree: DirRecord,
+}
+
+impl ScanCache {
+    /// 대상 디렉토리의 캐시 파일 경로.
+    pub fn path(root: &Path) -> PathBuf {
+        root.join(STATE_DIR).join(FILE_NAME)
+    }
+
+    /// 캐시를 읽습니다.
+    ///
+    /// 파일이 없거나, 형식이 잘못되었거나, 버전이나 대상 경로가 다르면 `None`을
+    /// 반환합니다.
+    pub fn load(fs: &dyn FileSystem, root: &Path) -> Option<Self> {
+        let text = fs.read_to_string(&Self::path(root)).ok()?;
+        let cache: Self = serde_json::from_str(&text).ok()?;
+        (cache.version == VERSION && cache.root == root_key(fs, root)).then_some(cache)
+    }
+
+    /// 캐시를 씁니다. 임시 파일에 먼저 쓴 뒤 이름을 바꿉니다.
+    ///
+    /// # Errors
+    ///
+    /// 디렉토리 생성이나 파일 쓰기에 실패하면 에러를 반환합니다.
+    pub fn save(&self, fs: &dyn FileSystem, root: &Path) -> Result<(), Error> {
+        let dir = root.join(STATE_DIR);
+        fs.create_dir_all(&dir)
+            .map_err(|source| Error::from_io(dir, source))?;
+
+        let path = Self::path(root);
+        let temp = path.with_extension("json.tmp");
+        let text = serde_json::to_string(self).map_err(|source| Error::Io {
+            path: path.clone(),
+            source: io::Error::other(source),
+        })?;
+        fs.write(&temp, text.as_bytes())
+            .map_err(|source| Error::from_io(temp.clone(), source))?;
+        fs.rename(&temp, &path)
+            .map_err(|source| Error::from_io(path, source))
+    }
+}
+
+fn root_key(fs: &dyn FileSystem, root: &Path) -> String {
+    fs.canonicalize(root)
+        .unwrap_or_else(|_| root.to_path_buf())
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// 캐시를 쓴 순회 결과.
+#[derive(Debug)]
+pub struct CachedWalk {
+    /// 디렉토리가 아닌 항목의 상대 경로와 종류.
+    pub files: Vec<(PathBuf, EntryKind)>,
+    /// 읽을 수 없어 건너뛴 경로의 에러 목록.
+    pub errors: Vec<Error>,
+    /// 캐시로 목록을 대신한 디렉토리 수.
+    pub reused: usize,
+    /// 목록을 새로 읽은 디렉토리 수.
+    pub read: usize,
+    /// 이번 순회로 갱신한 캐시.
+    pub cache: ScanCache,
+}
+
+/// 캐시가 실제 디렉토리와 어긋남.
+#[derive(Debug)]
+struct Stale;
+
+/// 이전 캐시를 참고해 대상 디렉토리를 순회합니다.
+///
+/// 이전 캐시가 실제와 어긋나면 캐시 없이 처음부터 다시 순회합니다. 대상 디렉토리의
+/// `.ps-organizer` 폴더는 순회하지 않습니다.
+pub fn walk(fs: &dyn FileSystem, root: &Path, previous: Option<&ScanCache>) -> CachedWalk {
+    walk_in(fs, root, previous, false)
+}
+
+/// [`walk`]와 같이 순회하되, `parallel`이면 하위 디렉토리를 현재 rayon 스레드 풀에
+/// 나눠 순회합니다. 결과의 캐시 기록은 순회 순서와 관계없이 같습니다.
+pub(crate) fn walk_in(
+    fs: &dyn FileSystem,
+    root: &Path,
+    previous: Option<&ScanCache>,
+    parallel: bool,
+) -> CachedWalk {
+    let visitor = Visitor {
+        fs,
+        root,
+        started: SystemTime::now(),
+        parallel,
+    };
+    if let Some(walk) = previous.and_then(|cache| visitor.walk(Some(&cache.tree)).ok()) {
+        return walk;
+    }
+    // 이전 기록 없이 순회하면 어긋날 캐시가 없어 `Stale`이 나지 않습니다. 그래도
+    // 나면 결과를 믿을 수 없으므로 빈 결과와 순회 에러로 알립니다.
+    visitor.walk(None).unwrap_or_else(|Stale| CachedWalk {
+        files: Vec::new(),
+        errors: vec![Error::Walk {
+            path: root.to_path_buf(),
+            source: io::Error::other("scan cache walk went out of sync"),
+        }],
+        reused: 0,
+        read: 0,
+        cache: ScanCache {
+            version: VERSION,
+            root: root_key(fs, root),
+            tree: DirRecord::default(),
+        },
+    })
+}
+
+struct Visitor<'a> {
+    fs: &'a dyn FileSystem,
+    root: &'a Path,
+    started: SystemTime,
+    parallel: bool,
+}
+
+/// 하위 트리 하나를 순회해 모은 결과.
+#[derive(Default)]
+struct Visited {
+    files: Vec<(PathBuf, EntryKind)>,
+    errors: Vec<Error>,
+    reused: usize,
+    read: usize,
+}
+
+impl Visited {
+    fn merge(&mut self, other: Self) {
+        self.files.extend(other.files);
+        self.errors.extend(other.errors);
+        self.reused += other.reused;
+        self.read += other.read;
+    }
+}
+
+impl Visitor<'_> {
+    fn walk(&self, previous: Option<&DirRecord>) -> Result<CachedWalk, Stale> {
+        let mut visited = Visited::default();
+        let tree = self
+            .visit(Path::new(""), previous, &mut visited)?
+            .unwrap_or_default();
+        Ok(CachedWalk {
+            files: visited.files,
+            errors: visited.errors,
+            reused: visited.reused,
+            read: visited.read,
+            cache: ScanCache {
+                version: VERSION,
+                root: root_key(self.fs, self.root),
+                tree,
+            },
+        })
+    }
+
+    /// 디렉토리 하나를 순회하고 새 기록을 반환합니다. 읽을 수 없으면 `None`입니다.
+    fn visit(
+        &self,
+        relative: &Path,
+        previous: Option<&DirRecord>,
+        out: &mut Visited,
+    ) -> Result<Option<DirRecord>, Stale> {
+        let path = self.root.join(relative);
+        // 대상 디렉토리 자체는 링크여도 따라가고, 그 아래 링크는 따라가지 않습니다.
+        let metadata = if relative.as_os_str().is_empty() {
+            self.fs.metadata(&path)
+        } else {
+            self.fs.symlink_metadata(&path)
+        };
+        let metadata = match metadata {
+            Ok(metadata) if metadata.is_dir() => metadata,
+            // 캐시로 대신한 목록에 있던 디렉토리가 없으면 캐시가 어긋난 것입니다.
+            Ok(_) if previous.is_some() => return Err(Stale),
+            Err(error) if previous.is_some() && error.kind() == io::ErrorKind::NotFound => {
+                return Err(Stale);
+            }
+            Ok(_) => return Ok(None),
+            Err(source) => {
+                out.errors.push(dir_error(path, source));
+                return Ok(None);
+            }
+        };
+        let stamp = Stamp::new(&metadata, self.started);
+
+        let cached = previous.filter(|record| stamp.is_some() && record.stamp == stamp);
+        let listing = if let Some(record) = cached {
+            out.reused += 1;
+            record
+                .children
+                .iter()
+                .map(|child| (child.name.clone(), child.kind, child.dir.as_ref()))
+                .collect()
+        } else {
+            out.read += 1;
+            match read_listing(self.fs, &path) {
+                Ok(listing) => listing,
+                Err(source) => {
+                    out.errors.push(dir_error(path, source));
+                    return Ok(None);
+                }
+            }
+        };
+        let reusing = cached.is_some();
+
+        let mut children = Vec::with_capacity(listing.len());
+        let mut subdirs = Vec::new();
+        for (name, kind, child_record) in listing {
+            let Some(os_name) = name.decode() else {
+                continue;
+            };
+            let child_path = relative.join(&os_name);
+            match kind {
+                EntryKind::Dir if relative.as_os_str().is_empty() && os_name == STATE_DIR => {}
+                EntryKind::Dir => {
+                    // 새로 읽은 목록이면 이전 캐시에서 같은 이름의 하위 기록을 찾습니다.
+                    let previous_child = if reusing {
+                        child_record
+                    } else {
+                        previous.and_then(|record| find_child(record, &name))
+                    };
+                    if reusing && previous_child.is_none() {
+                        return Err(Stale);
+                    }
+                    subdirs.push((children.len(), child_path, previous_child));
+                }
+                EntryKind::File | EntryKind::Symlink | EntryKind::Other => {
+                    out.files.push((child_path, kind));
+                }
+            }
+            children.push(Child {
+                name,
+                kind,
+                dir: None,
+            });
+        }
+
+        let visit_child = |(index, child_path, previous_child): (usize, PathBuf, _)| {
+            let mut visited = Visited::default();
+            let record = self.visit(&child_path, previous_child, &mut visited)?;
+            Ok((index, record, visited))
+        };
+        let visited: Vec<(usize, Option<DirRecord>, Visited)> = if self.parallel {
+            subdirs
+                .into_par_iter()
+                .map(visit_child)
+                .collect::<Result<_, Stale>>()?
+        } else {
+            subdirs
+                .into_iter()
+                .map(visit_child)
+                .collect::<Result<_, Stale>>()?
+        };
+        for (index, record, child) in visited {
+            children[index].dir = record;
+            out.merge(child);
+        }
+
+        Ok(Some(DirRecord { stamp, children }))
+    }
+}
+
+type Listing<'a> = Vec<(Name, EntryKind, Option<&'a DirRecord>)>;
+
+fn read_listing<'a>(fs: &dyn FileSystem, path: &Path) -> io::Result<Listing<'a>> {
+    Ok(fs
+        .read_dir(path)?
+        .into_iter()
+        .filter_map(|entry| Some((Name::encode(&entry.name)?, entry.kind, None)))
+        .collect())
+}
+
+fn find_child<'a>(record: &'a DirRecord, name: &Name) -> Option<&'a DirRecord> {
+    record
+        .children
+        .iter()
+        .find(|child| child.name == *name && child.kind == EntryKind::Dir)
+        .and_then(|child| child.dir.as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vfs::RealFs;
+    use std::fs::{self, File};
+    use tempfile::TempDir;
+
+    /// 디렉토리의 수정 시각을 고정된 과거 시각으로 돌려 캐시에 남도록 합니다.
+    fn age(path: &Path) {
+        let old = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        File::open(path).unwrap().set_modified(old).unwrap();
+    }
+
+    fn sorted_files(walk: &CachedWalk) -> Vec<PathBuf> {
+        let mut files: Vec<_> = walk.files.iter().map(|(path, _)| path.clone()).collect();
+        files.sort();
+        files
+    }
+
+    fn setup() -> TempDir {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(root.join("1000")).unwrap();
+        fs::create_dir_all(root.join(STATE_DIR)).unwrap();
+        File::create(root.join("1000/1000.cpp")).unwrap();
+        File::create(root.join("1001.cpp")).unwrap();
+        File::create(root.join(STATE_DIR).join("1002.cpp")).unwrap();
+        age(&root.join("1000"));
+        age(root);
+        temp
+    }
+
+    #[test]
+    fn walk_skips_state_dir() {
+        let temp = setup();
+
+        let walk = walk(&RealFs, temp.path(), None);
+
+        assert_eq!(
+            sorted_files(&walk),
+            vec![PathBuf::from("1000/1000.cpp"), PathBuf::from("1001.cpp")]
+        );
+        assert_eq!((walk.reused, walk.read), (0, 2));
+    }
+
+    #[test]
+    fn parallel_walk_matches_sequential() {
+        let temp = setup();
+        for dir in ["2000/a", "2000/b", "3000"] {
+            fs::create_dir_all(temp.path().join(dir)).unwrap();
+            File::create(temp.path().join(dir).join("1.cpp")).unwrap();
+        }
+        let previous = walk(&RealFs, temp.path(), None).cache;
+
+        let sequential = walk_in(&RealFs, temp.path(), Some(&previous), false);
+        let parallel = walk_in(&RealFs, temp.path(), Some(&previous), true);
+
+        assert_eq!(sorted_files(&parallel), sorted_files(&sequential));
+        assert_eq!(
+            (parallel.reused, parallel.read),
+            (sequential.reused, sequential.read)
+        );
+        assert_eq!(parallel.cache, sequential.cache);
+    }
+
+    #[test]
+    fn unchanged_dirs_are_reused() {
+        let temp = setup();
+        let first = walk(&RealFs, temp.path(), None);
+        first.cache.save(&RealFs, temp.path()).unwrap();
+        let cache = ScanCache::load(&RealFs, temp.path()).unwrap();
+
+        let second = walk(&RealFs, temp.path(), Some(&cache));
+
+        assert_eq!((second.reused, second.read), (2, 0));
+        assert_eq!(sorted_files(&second), sorted_files(&first));
+    }
+
+    #[test]
+    fn changed_dir_is_read_again() {
+        let temp = setup();
+        let cache = walk(&RealFs, temp.path(), None).cache;
+        File::create(temp.path().join("1000/1003.cpp")).unwrap();
+
+        let second = walk(&RealFs, temp.path(), Some(&cache));
+
+        assert_eq!((second.reused, second.read), (1, 1));
+        assert!(sorted_files(&second).contains(&PathBuf::from("1000/1003.cpp")));
+    }
+
+    #[test]
+    fn recent_changes_are_not_trusted() {
+        let temp = TempDir::new().unwrap();
+        File::create(temp.path().join("1000.cpp")).unwrap();
+
+        let cache = walk(&RealFs, temp.path(), None).cache;
+        let second = walk(&RealFs, temp.path(), Some(&cache));
+
+        assert_eq!((second.reused, second.read), (0, 1));
+    }
+
+    #[test]
+    fn inconsistent_cache_falls_back_to_full_walk() {
+        let temp = setup();
+        let cache = walk(&RealFs, temp.path(), None).cache;
+        // 부모의 수정 시각을 되돌려 캐시가 하위 폴더 삭제를 놓치게 합니다.
+        fs::remove_dir_all(temp.path().join("1000")).unwrap();
+        age(temp.path());
+
+        let second = walk(&RealFs, temp.path(), Some(&cache));
+
+        assert_eq!(second.reused, 0);
+        assert_eq!(sorted_files(&second), vec![PathBuf::from("1001.cpp")]);
+    }
+
+    #[test]
+    fn load_rejects_other_root_and_garbage() {
+        let temp = setup();
+        let other = setup();
+        walk(&RealFs, temp.path(), None)
+            .cache
+            .save(&RealFs, temp.path())
+            .unwrap();
+        fs::copy(ScanCache::path(temp.path()), ScanCache::path(other.path())).unwrap();
+
+        assert!(ScanCache::load(&RealFs, temp.path()).is_some());
+        assert!(ScanCache::load(&RealFs, other.path()).is_none());
+
+        fs::write(ScanCache::path(temp.path()), "{").unwrap();
+        assert!(ScanCache::load(&RealFs, temp.path()).is_none());
+    }
+}
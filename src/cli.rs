@@ -1,16 +1,59 @@
 //! 명령줄 인터페이스 정의.
 
-use clap::Parser;
+use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
+use ps_organizer::archive::ArchiveFormat;
+use ps_organizer::extension::ExtensionPolicy;
+use ps_organizer::i18n::Lang;
+use ps_organizer::import::{ConflictPolicy, DuplicatePolicy};
+use ps_organizer::keys::{DateSource, GroupBy};
+use ps_organizer::locate::ProblemRange;
+use ps_organizer::metadata::{self, Date, Status};
+use ps_organizer::scanner::SymlinkPolicy;
 use std::path::PathBuf;
 
+/// 심볼릭 링크 처리 방식 ([`SymlinkPolicy`]의 명령줄 값).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SymlinkArg {
+    /// 링크를 무시합니다.
+    Skip,
+    /// 링크를 따라가 실제 파일을 정리합니다.
+    Follow,
+    /// 링크 자체를 옮깁니다.
+    MoveLink,
+}
+
+impl From<SymlinkArg> for SymlinkPolicy {
+    fn from(arg: SymlinkArg) -> Self {
+        match arg {
+            SymlinkArg::Skip => Self::Skip,
+            SymlinkArg::Follow => Self::Follow,
+            SymlinkArg::MoveLink => Self::MoveLink,
+        }
+    }
+}
+
+/// 로그 출력 형식.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    /// 사람이 읽는 텍스트.
+    Text,
+    /// 한 줄에 하나씩 JSON 이벤트.
+    Json,
+}
+
 #[derive(Parser, Debug)]
+#[allow(clippy::struct_excessive_bools)]
 #[command(
     name = "ps_organizer",
     version,
     about = "PS 문제 소스코드 자동 정리 유틸리티"
 )]
 pub struct Cli {
-    /// 정리할 대상 디렉토리.
+    /// 정리 대신 실행할 하위 명령.
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// 정리할 대상 디렉토리. 하위 명령과 이름이 같으면 `./meta`처럼 지정.
     #[arg(default_value = ".")]
     pub target_dir: PathBuf,
 
@@ -22,7 +65,457 @@ pub struct Cli {
     #[arg(short = 'n', long)]
     pub dry_run: bool,
 
-    /// 상세 출력 모드.
-    #[arg(short, long)]
-    pub verbose: bool,
+    /// 계획을 폴더 트리 비교 형태로 출력 (dry-run 모드용).
+    #[arg(long, requires = "dry_run")]
+    pub tree: bool,
+
+    /// 실행 전에 계획을 대화형으로 검토.
+    #[arg(short, long, conflicts_with = "dry_run")]
+    pub interactive: bool,
+
+    /// 상세 출력 (`-v`: 파일 이동, `-vv`: 스캔과 계획까지).
+    #[arg(short, long, action = ArgAction::Count)]
+    pub verbose: u8,
+
+    /// 에러 외의 출력을 생략.
+    #[arg(short, long, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// 로그 출력 형식.
+    #[arg(long, value_enum, default_value_t = LogFormat::Text)]
+    pub log_format: LogFormat,
+
+    /// 파일의 처음 N줄 주석에서 문제 번호를 찾음 (예: `// https://www.acmicpc.net/problem/1000`, `# BOJ 1000`).
+    #[arg(long, value_name = "N", num_args = 0..=1, default_missing_value = "5", default_value_t = 0)]
+    pub headers: usize,
+
+    /// 상위 폴더를 정하는 기준 (태그, 난이도, 날짜는 메타데이터 사용).
+    #[arg(long, value_enum, default_value_t = GroupBy::Number)]
+    pub group_by: GroupBy,
+
+    /// 날짜 기준일 때 푼 날짜가 없는 파일의 날짜 출처.
+    #[arg(long, value_enum, default_value_t = DateSource::Mtime)]
+    pub date_source: DateSource,
+
+    #[command(flatten)]
+    pub scan: ScanArgs,
+
+    /// 이동할 때 확장자를 정식 확장자로 변경 (예: `1010.CXX` → `1010.cpp`).
+    #[arg(long)]
+    pub normalize_ext: bool,
+
+    /// 스캔에 쓸 작업 스레드 수 (0이면 CPU 수만큼, 1이면 스레드를 만들지 않음).
+    #[arg(short = 'j', long, value_name = "N", default_value_t = 0)]
+    pub jobs: usize,
+
+    /// 이동 후 빈 폴더를 삭제하지 않음.
+    #[arg(long)]
+    pub keep_empty: bool,
+
+    /// 이번 실행과 관계없이 비어 있으면 삭제할 폴더 (이름 또는 상대 경로). 여러 번 지정할 수 있음.
+    #[arg(long, value_name = "DIR", conflicts_with = "keep_empty")]
+    pub cleanup_allow: Vec<String>,
+
+    /// 비어 있어도 삭제하지 않을 폴더 (이름 또는 상대 경로, `.git`과 `.ps-organizer`는 항상 포함). 여러 번 지정할 수 있음.
+    #[arg(long, value_name = "DIR", conflicts_with = "keep_empty")]
+    pub cleanup_deny: Vec<String>,
+
+    /// 스캔 캐시를 읽거나 쓰지 않고 전체를 다시 스캔.
+    #[arg(long)]
+    pub no_cache: bool,
+
+    /// 출력 언어 (기본값: `LC_ALL`/`LC_MESSAGES`/`LANG`에서 감지).
+    #[arg(long, value_enum, global = true)]
+    pub lang: Option<Lang>,
+}
+
+/// 하위 명령.
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// 문제 메타데이터 관리.
+    Meta {
+        #[command(subcommand)]
+        action: MetaCommand,
+    },
+    /// 정리 규칙이 정할 위치에 템플릿으로 새 풀이 파일을 만들고 경로를 출력.
+    New(NewArgs),
+    /// 문제 번호(범위)에 속한 파일을 모두 출력 (변형, 딸린 파일 포함).
+    Find(FindArgs),
+    /// 문제 번호(범위)의 풀이 파일이 놓일 경로를 출력.
+    Path(PathArgs),
+    /// `_conflictN` 파일을 본 파일과 비교하고 유지, 삭제, 승격 중 하나로 처리.
+    Conflicts(ConflictsArgs),
+    /// 다른 디렉토리의 풀이를 대상 트리의 구조에 맞춰 가져오고, 임계값을 넘는 폴더를 다시 나눔.
+    Import(ImportArgs),
+    /// 조건에 맞는 풀이를 정리했을 때의 배치 그대로 압축 파일로 내보냄 (작업 트리는 바꾸지 않음).
+    Export(ExportArgs),
+    /// 그룹 폴더를 풀어 모든 풀이를 루트(또는 지정한 폴더 하나)로 모으고, 비게 된 폴더를 정리.
+    Flatten(FlattenArgs),
+}
+
+/// 파일을 수집하는 방식. 정리와 스캔하는 하위 명령이 함께 씁니다.
+#[derive(Args, Debug)]
+pub struct ScanArgs {
+    /// 확장자를 대소문자까지 정확히 일치할 때만 인식 (별칭 사용 안 함).
+    #[arg(long)]
+    pub strict_ext: bool,
+
+    /// 확장자 별칭 추가 (`FROM=TO`, 예: `hpp=cpp`). 여러 번 지정할 수 있음.
+    #[arg(long, value_name = "FROM=TO", value_parser = parse_alias, conflicts_with = "strict_ext")]
+    pub ext_alias: Vec<(String, String)>,
+
+    /// 심볼릭 링크 처리 방식 (`follow`: 링크를 따라가 실제 파일 정리, `move-link`: 링크 자체를 이동).
+    #[arg(long, value_enum, default_value_t = SymlinkArg::Skip)]
+    pub symlinks: SymlinkArg,
+}
+
+impl ScanArgs {
+    /// 명령줄 옵션으로 확장자 인식 규칙을 만듭니다.
+    pub fn extension_policy(&self) -> ExtensionPolicy {
+        let mut policy = if self.strict_ext {
+            ExtensionPolicy::strict()
+        } else {
+            ExtensionPolicy::default()
+        };
+        for (alias, ext) in &self.ext_alias {
+            policy
+                .aliases
+                .insert(alias.to_ascii_lowercase(), ext.to_ascii_lowercase());
+        }
+        policy
+    }
+}
+
+/// 하위 명령의 대상 디렉토리.
+#[derive(Args, Debug)]
+pub struct RootArgs {
+    /// 대상 디렉토리.
+    #[arg(short = 'C', long = "dir", default_value = ".")]
+    pub dir: PathBuf,
+}
+
+/// `meta` 하위 명령.
+#[derive(Subcommand, Debug)]
+pub enum MetaCommand {
+    /// 문제 메타데이터를 저장 (지정한 항목만 바꿈).
+    Set(MetaSetArgs),
+    /// 문제 메타데이터를 출력.
+    Get {
+        /// 문제 번호.
+        number: u32,
+        #[command(flatten)]
+        root: RootArgs,
+    },
+}
+
+/// `meta set` 인자.
+#[derive(Args, Debug)]
+pub struct MetaSetArgs {
+    /// 문제 번호.
+    pub number: u32,
+
+    /// 문제 제목.
+    #[arg(long)]
+    pub title: Option<String>,
+
+    /// 난이도 (예: `gold3`).
+    #[arg(long)]
+    pub tier: Option<String>,
+
+    /// 태그 (쉼표로 구분하거나 여러 번 지정, 기존 태그를 대체).
+    #[arg(long = "tag", value_delimiter = ',')]
+    pub tags: Option<Vec<String>>,
+
+    /// 풀이 상태.
+    #[arg(long, value_enum)]
+    pub status: Option<Status>,
+
+    /// 푼 날짜 (`YYYY-MM-DD`).
+    #[arg(long, value_parser = metadata::parse_date)]
+    pub solved: Option<Date>,
+
+    /// 기존 메타데이터를 모두 지운 뒤 저장.
+    #[arg(long)]
+    pub clear: bool,
+
+    #[command(flatten)]
+    pub root: RootArgs,
+}
+
+/// `new` 인자.
+#[derive(Args, Debug)]
+pub struct NewArgs {
+    /// 문제 번호.
+    pub number: u32,
+
+    /// 언어 확장자 (예: `cpp`, `py`).
+    #[arg(long, default_value = "cpp")]
+    pub ext: String,
+
+    /// 템플릿의 `{author}`에 채울 작성자 (기본값: `PS_ORGANIZER_AUTHOR` 환경 변수).
+    #[arg(long)]
+    pub author: Option<String>,
+
+    /// 이미 풀이가 있으면 다음 변형 이름으로 만듦 (예: `1000_2.cpp`).
+    #[arg(long)]
+    pub variant: bool,
+
+    /// 그룹핑 임계값.
+    #[arg(short, long, default_value_t = 20)]
+    pub threshold: usize,
+
+    /// 상위 폴더를 정하는 기준.
+    #[arg(long, value_enum, default_value_t = GroupBy::Number)]
+    pub group_by: GroupBy,
+
+    #[command(flatten)]
+    pub root: RootArgs,
+}
+
+/// `find` 인자.
+#[derive(Args, Debug)]
+pub struct FindArgs {
+    /// 문제 번호 또는 범위 (`1000`, `1000..1100`, `1000..=1100`, `1000..`).
+    pub range: ProblemRange,
+
+    /// 파일의 처음 N줄 주석에서도 문제 번호를 찾음.
+    #[arg(long, value_name = "N", num_args = 0..=1, default_missing_value = "5", default_value_t = 0)]
+    pub headers: usize,
+
+    #[command(flatten)]
+    pub scan: ScanArgs,
+
+    #[command(flatten)]
+    pub root: RootArgs,
+}
+
+/// `path` 인자.
+#[derive(Args, Debug)]
+pub struct PathArgs {
+    /// 문제 번호 또는 범위 (`1000`, `1000..1100`, `1000..=1100`).
+    pub range: ProblemRange,
+
+    /// 언어 확장자 (예: `cpp`, `py`).
+    #[arg(long, default_value = "cpp")]
+    pub ext: String,
+
+    /// 그룹핑 임계값.
+    #[arg(short, long, default_value_t = 20)]
+    pub threshold: usize,
+
+    /// 상위 폴더를 정하는 기준.
+    #[arg(long, value_enum, default_value_t = GroupBy::Number)]
+    pub group_by: GroupBy,
+
+    #[command(flatten)]
+    pub scan: ScanArgs,
+
+    #[command(flatten)]
+    pub root: RootArgs,
+}
+
+impl Cli {
+    /// 명령줄 옵션으로 확장자 인식 규칙을 만듭니다.
+    pub fn extension_policy(&self) -> ExtensionPolicy {
+        ExtensionPolicy {
+            normalize_on_move: self.normalize_ext,
+            ..self.scan.extension_policy()
+        }
+    }
+}
+
+/// `FROM=TO` 형식의 별칭을 파싱합니다.
+fn parse_alias(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((from, to)) if !from.is_empty() && !to.is_empty() => Ok((
+            from.trim_start_matches('.').to_string(),
+            to.trim_start_matches('.').to_string(),
+        )),
+        _ => Err(format!("expected FROM=TO, got `{value}`")),
+    }
+}
+
+/// `conflicts` 인자.
+#[derive(Args, Debug)]
+pub struct ConflictsArgs {
+    /// 묻지 않고 충돌 파일과 차이만 출력.
+    #[arg(long)]
+    pub list: bool,
+
+    /// 차이를 보여 줄 때 바뀐 줄 앞뒤로 함께 보여 줄 줄 수.
+    #[arg(long, value_name = "N", default_value_t = 3)]
+    pub context: usize,
+
+    #[command(flatten)]
+    pub root: RootArgs,
+}
+
+/// `import` 인자.
+#[derive(Args, Debug)]
+pub struct ImportArgs {
+    /// 가져올 원본 디렉토리.
+    pub source: PathBuf,
+
+    /// 복사하지 않고 원본에서 옮김.
+    #[arg(long = "move")]
+    pub move_files: bool,
+
+    /// 목적지 이름에 다른 파일이 있을 때 처리 방식 (`rename`: `_conflictN` 이름으로 가져옴, `skip`: 건너뜀).
+    #[arg(long, value_enum, default_value_t = ConflictPolicy::Rename)]
+    pub on_conflict: ConflictPolicy,
+
+    /// 같은 문제, 같은 언어의 풀이가 내용까지 같을 때 처리 방식 (`skip`: 건너뜀, `keep`: 이름 충돌처럼 처리).
+    #[arg(long, value_enum, default_value_t = DuplicatePolicy::Skip)]
+    pub on_duplicate: DuplicatePolicy,
+
+    /// 실제로 가져오지 않고 계획만 출력.
+    #[arg(short = 'n', long)]
+    pub dry_run: bool,
+
+    /// 상세 출력 (`-v`: 파일 이동과 가져오기, `-vv`: 스캔과 계획까지).
+    #[arg(short, long, action = ArgAction::Count)]
+    pub verbose: u8,
+
+    /// 에러 외의 출력을 생략.
+    #[arg(short, long, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// 로그 출력 형식.
+    #[arg(long, value_enum, default_value_t = LogFormat::Text)]
+    pub log_format: LogFormat,
+
+    /// 스캔과 중복 비교에 쓸 작업 스레드 수 (0이면 CPU 수만큼, 1이면 스레드를 만들지 않음).
+    #[arg(short = 'j', long, value_name = "N", default_value_t = 0)]
+    pub jobs: usize,
+
+    /// 그룹핑 임계값.
+    #[arg(short, long, default_value_t = 20)]
+    pub threshold: usize,
+
+    /// 상위 폴더를 정하는 기준.
+    #[arg(long, value_enum, default_value_t = GroupBy::Number)]
+    pub group_by: GroupBy,
+
+    #[command(flatten)]
+    pub root: RootArgs,
+}
+
+/// `export` 인자.
+#[derive(Args, Debug)]
+pub struct ExportArgs {
+    /// 압축 형식.
+    #[arg(long, value_enum, default_value_t = ArchiveFormat::TarGz)]
+    pub format: ArchiveFormat,
+
+    /// 저장할 파일 (`-`이면 표준 출력, 기본값: 대상 디렉토리 옆의 `<대상 디렉토리 이름>.<형식>`).
+    #[arg(short, long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    /// 문제 번호 또는 범위 (`1000`, `1000..1100`, `1000..=1100`, `1000..`).
+    #[arg(long)]
+    pub range: Option<ProblemRange>,
+
+    /// 언어 확장자 (예: `cpp`, `py`). 쉼표로 구분하거나 여러 번 지정할 수 있음.
+    #[arg(long, value_delimiter = ',')]
+    pub ext: Vec<String>,
+
+    /// 태그 중 하나라도 있는 문제만. 쉼표로 구분하거나 여러 번 지정할 수 있음.
+    #[arg(long, value_delimiter = ',')]
+    pub tag: Vec<String>,
+
+    /// 난이도 중 하나인 문제만 (예: `gold3`). 쉼표로 구분하거나 여러 번 지정할 수 있음.
+    #[arg(long, value_delimiter = ',')]
+    pub tier: Vec<String>,
+
+    /// 풀이 상태 중 하나인 문제만. 쉼표로 구분하거나 여러 번 지정할 수 있음.
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub status: Vec<Status>,
+
+    /// 담을 파일 목록을 압축 파일 안의 경로와 함께 출력 (`-v`), 계획까지 출력 (`-vv`).
+    #[arg(short, long, action = ArgAction::Count)]
+    pub verbose: u8,
+
+    /// 그룹핑 임계값.
+    #[arg(short, long, default_value_t = 20)]
+    pub threshold: usize,
+
+    /// 상위 폴더를 정하는 기준.
+    #[arg(long, value_enum, default_value_t = GroupBy::Number)]
+    pub group_by: GroupBy,
+
+    #[command(flatten)]
+    pub root: RootArgs,
+}
+
+/// `flatten` 인자.
+#[derive(Args, Debug)]
+pub struct FlattenArgs {
+    /// 풀이를 모을 폴더 (대상 디렉토리 기준, 기본값: 루트).
+    #[arg(long, value_name = "DIR")]
+    pub into: Option<PathBuf>,
+
+    /// 이름이 겹칠 때 처리 방식 (`rename`: `_conflictN` 이름으로 옮김, `skip`: 제자리에 둠).
+    #[arg(long, value_enum, default_value_t = ConflictPolicy::Rename)]
+    pub on_conflict: ConflictPolicy,
+
+    /// 실제 이동 없이 계획만 출력.
+    #[arg(short = 'n', long)]
+    pub dry_run: bool,
+
+    /// 상세 출력 (`-v`: 파일 이동, `-vv`: 계획까지).
+    #[arg(short, long, action = ArgAction::Count)]
+    pub verbose: u8,
+
+    /// 에러 외의 출력을 생략.
+    #[arg(short, long, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// 로그 출력 형식.
+    #[arg(long, value_enum, default_value_t = LogFormat::Text)]
+    pub log_format: LogFormat,
+
+    /// 이동 후 빈 폴더를 삭제하지 않음.
+    #[arg(long)]
+    pub keep_empty: bool,
+
+    #[command(flatten)]
+    pub root: RootArgs,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dot_slash_prefix_selects_directory_named_like_subcommand() {
+        let cli = Cli::try_parse_from(["ps-organizer", "./meta"]).unwrap();
+        assert!(cli.command.is_none());
+        assert_eq!(cli.target_dir, PathBuf::from("./meta"));
+
+        let cli = Cli::try_parse_from(["ps-organizer", "meta", "get", "1000"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Meta {
+                action: MetaCommand::Get { number: 1000, .. }
+            })
+        ));
+    }
+
+    #[test]
+    fn output_language_is_accepted_after_subcommand() {
+        let cli = Cli::try_parse_from([
+            "ps-organizer",
+            "path",
+            "1000",
+            "--ext",
+            "py",
+            "--lang",
+            "en",
+        ])
+        .unwrap();
+        assert_eq!(cli.lang, Some(Lang::En));
+        assert!(
+            matches!(cli.command, Some(Command::Path(PathArgs { ref ext, .. })) if ext == "py")
+        );
+    }
 }
@@ -0,0 +1,70 @@
+//! `conflicts` 하위 명령.
+
+use crate::cli::ConflictsArgs;
+use anyhow::Result;
+use ps_organizer::conflicts::{self, Resolution};
+use ps_organizer::organizer::Organizer;
+use ps_organizer::scanner::ScanOptions;
+use ps_organizer::t;
+use std::io;
+use std::process::ExitCode;
+
+/// 충돌 파일을 하나씩 보여 주고, 고른 방법으로 바로 처리합니다.
+///
+/// `q`를 입력하거나 입력이 끝나면 남은 파일은 그대로 둡니다.
+pub fn run(args: &ConflictsArgs) -> Result<ExitCode> {
+    let organizer = Organizer::builder(&args.root.dir).build()?;
+    let root = organizer.root();
+    let report = conflicts::find_conflicts(root, &ScanOptions::default());
+    for error in &report.errors {
+        eprintln!("{}", t!("main.warning", error = error));
+    }
+    if report.conflicts.is_empty() {
+        println!("{}", t!("conflicts.none"));
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let count = report.conflicts.len();
+    for (index, conflict) in report.conflicts.iter().enumerate() {
+        println!(
+            "{}",
+            t!(
+                "conflicts.header",
+                index = index + 1,
+                count = count,
+                path = conflict.path.display(),
+                main = conflict.main.display()
+            )
+        );
+        if args.list {
+            conflicts::describe_conflict(root, conflict, args.context, io::stdout().lock())?;
+            continue;
+        }
+
+        let choice = conflicts::prompt_resolution(
+            root,
+            conflict,
+            args.context,
+            io::stdin().lock(),
+            io::stdout().lock(),
+        )?;
+        let Some(resolution) = choice else {
+            break;
+        };
+        conflicts::resolve(root, conflict, resolution)?;
+        let path = conflict.path.display();
+        let message = match resolution {
+            Resolution::Keep => t!("conflicts.kept", path = path),
+            Resolution::Delete => t!("conflicts.deleted", path = path),
+            Resolution::Promote => {
+                t!(
+                    "conflicts.promoted",
+                    path = path,
+                    main = conflict.main.display()
+                )
+            }
+        };
+        println!("{message}");
+    }
+    Ok(ExitCode::SUCCESS)
+}
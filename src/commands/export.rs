@@ -0,0 +1,89 @@
+//! `export` 하위 명령.
+
+use crate::cli::ExportArgs;
+use anyhow::Result;
+use ps_organizer::events::{Event, Observer, TextObserver, Verbosity};
+use ps_organizer::export::ExportFilter;
+use ps_organizer::organizer::Organizer;
+use ps_organizer::t;
+use ps_organizer::types::Error;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::ExitCode;
+
+/// 조건에 맞는 풀이를 정리 후 배치로 담은 압축 파일을 씁니다.
+///
+/// 진행 상황은 표준 에러로 출력하므로, 압축 파일을 표준 출력으로 보낼 수 있습니다.
+/// 담을 파일이 없으면 파일을 쓰지 않고 실패로 끝납니다.
+pub fn run(args: &ExportArgs) -> Result<ExitCode> {
+    let verbosity = Verbosity::from_flags(false, args.verbose);
+    let mut observer = TextObserver::new(io::stderr(), verbosity);
+    let mut organizer = Organizer::builder(&args.root.dir)
+        .threshold(args.threshold)
+        .group_by(args.group_by)
+        .observer(move |event: &Event| observer.on_event(event))
+        .build()?;
+
+    // 기본 위치는 루트 밖에 두어, 다음 스캔에 압축 파일이 섞이지 않게 합니다.
+    let output = if let Some(output) = &args.output {
+        output.clone()
+    } else {
+        let root = organizer.root();
+        let (Some(parent), Some(name)) = (root.parent(), root.file_name()) else {
+            eprintln!("{}", t!("export.no_default_output"));
+            return Ok(ExitCode::FAILURE);
+        };
+        let mut file = parent.join(name);
+        file.as_mut_os_string().push(".");
+        file.as_mut_os_string().push(args.format.extension());
+        file
+    };
+
+    let scan = organizer.scan();
+    for error in &scan.errors {
+        eprintln!("{}", t!("main.warning", error = error));
+    }
+    let scan_status = scan
+        .errors
+        .first()
+        .map_or(ExitCode::SUCCESS, |e| ExitCode::from(e.exit_code()));
+
+    let filter = ExportFilter {
+        range: args.range.clone(),
+        languages: args.ext.clone(),
+        tags: args.tag.clone(),
+        tiers: args.tier.clone(),
+        statuses: args.status.clone(),
+    };
+    let files = organizer.plan_export(&scan, &filter);
+    if files.is_empty() {
+        eprintln!("{}", t!("export.empty"));
+        return Ok(ExitCode::FAILURE);
+    }
+    if verbosity >= Verbosity::Verbose {
+        for op in &files {
+            eprintln!(
+                "{}",
+                t!(
+                    "export.entry",
+                    from = op.from.display(),
+                    to = op.to.display()
+                )
+            );
+        }
+    }
+
+    let archive = organizer.export(&files, args.format)?;
+    let written = if output == Path::new("-") {
+        io::stdout().lock().write_all(&archive)
+    } else {
+        fs::write(&output, &archive)
+    };
+    written.map_err(|e| Error::from_io(output.clone(), e))?;
+    eprintln!(
+        "{}",
+        t!("export.done", count = files.len(), path = output.display())
+    );
+    Ok(scan_status)
+}
@@ -0,0 +1,31 @@
+//! `find` 하위 명령.
+
+use crate::cli::FindArgs;
+use anyhow::Result;
+use ps_organizer::locate::find_problem_files;
+use ps_organizer::organizer::Organizer;
+use ps_organizer::t;
+use std::process::ExitCode;
+
+/// 범위에 드는 문제의 파일 경로를 한 줄에 하나씩 출력합니다.
+pub fn run(args: &FindArgs) -> Result<ExitCode> {
+    let mut organizer = Organizer::builder(&args.root.dir)
+        .extension_policy(args.scan.extension_policy())
+        .symlinks(args.scan.symlinks.into())
+        .header_lines(args.headers)
+        .build()?;
+    let scan = organizer.scan();
+    for error in &scan.errors {
+        eprintln!("{}", t!("main.warning", error = error));
+    }
+
+    let files = find_problem_files(&scan, &args.range);
+    if files.is_empty() {
+        eprintln!("{}", t!("find.not_found", range = args.range));
+        return Ok(ExitCode::FAILURE);
+    }
+    for file in &files {
+        println!("{}", organizer.root().join(&file.path).display());
+    }
+    Ok(ExitCode::SUCCESS)
+}
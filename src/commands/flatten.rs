@@ -0,0 +1,74 @@
+//! `flatten` 하위 명령.
+
+use crate::cli::{FlattenArgs, LogFormat};
+use crate::output::{self, Console};
+use anyhow::Result;
+use ps_organizer::events::{Event, JsonObserver, Observer, TextObserver, Verbosity};
+use ps_organizer::organizer::{Options, Organizer};
+use ps_organizer::t;
+use std::io;
+use std::path::Path;
+use std::process::ExitCode;
+
+/// 스캔한 풀이를 모두 한 폴더로 모읍니다.
+///
+/// 정리 작업과 같은 이벤트를 같은 형식으로 기록합니다. 스캔 중 건너뛴 경로가 있으면
+/// 첫 에러 분류의 종료 코드를, dry-run에서 실패할 작업이 있으면 첫 실패의 종료
+/// 코드를 돌려줍니다.
+pub fn run(args: &FlattenArgs) -> Result<ExitCode> {
+    let verbosity = Verbosity::from_flags(args.quiet, args.verbose);
+    let console = Console {
+        verbosity,
+        human: args.log_format == LogFormat::Text,
+    };
+    let mut observer: Box<dyn Observer> = match args.log_format {
+        LogFormat::Text => Box::new(TextObserver::new(io::stdout(), verbosity)),
+        LogFormat::Json => Box::new(JsonObserver::new(io::stdout())),
+    };
+    let mut organizer = Organizer::builder(&args.root.dir)
+        .options(Options {
+            cleanup_empty_dirs: !args.keep_empty,
+            ..Options::default()
+        })
+        .observer(move |event: &Event| observer.on_event(event))
+        .build()?;
+
+    let scan = organizer.scan();
+    for error in &scan.errors {
+        eprintln!("{}", t!("main.warning", error = error));
+    }
+    let scan_status = scan
+        .errors
+        .first()
+        .map_or(ExitCode::SUCCESS, |e| ExitCode::from(e.exit_code()));
+
+    let plan = organizer.plan_flatten(
+        &scan,
+        args.into.as_deref().unwrap_or_else(|| Path::new("")),
+        args.on_conflict,
+    )?;
+    if args.dry_run {
+        if console.human {
+            output::print_plan(&plan.moves, verbosity >= Verbosity::Verbose);
+        }
+        let simulation = organizer.simulate(&plan);
+        output::print_simulation(&console, &simulation);
+        return Ok(simulation
+            .failures
+            .first()
+            .map_or(scan_status, |e| ExitCode::from(e.exit_code())));
+    }
+
+    if plan.moves.is_empty() {
+        console.say(Verbosity::Normal, &t!("exec.empty"));
+    } else {
+        console.say(
+            Verbosity::Normal,
+            &format!("{}\n", t!("exec.header", count = plan.moves.len())),
+        );
+    }
+    let report = organizer.execute(&plan)?;
+    output::print_summary(&console, &report);
+
+    Ok(scan_status)
+}
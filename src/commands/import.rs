@@ -0,0 +1,102 @@
+//! `import` 하위 명령.
+
+use crate::cli::{ImportArgs, LogFormat};
+use crate::output::{self, Console};
+use anyhow::Result;
+use ps_organizer::events::{Event, JsonObserver, Observer, TextObserver, Verbosity};
+use ps_organizer::import::{ImportOptions, TransferMode};
+use ps_organizer::organizer::Organizer;
+use ps_organizer::t;
+use std::io;
+use std::process::ExitCode;
+
+/// 원본 디렉토리의 풀이를 대상 트리로 가져옵니다.
+///
+/// 스캔 중 건너뛴 경로가 있으면 첫 에러 분류의 종료 코드를, dry-run에서 실패할
+/// 작업이 있으면 첫 실패의 종료 코드를 돌려줍니다.
+pub fn run(args: &ImportArgs) -> Result<ExitCode> {
+    let verbosity = Verbosity::from_flags(args.quiet, args.verbose);
+    let console = Console {
+        verbosity,
+        human: args.log_format == LogFormat::Text,
+    };
+    let mut observer: Box<dyn Observer> = match args.log_format {
+        LogFormat::Text => Box::new(TextObserver::new(io::stdout(), verbosity)),
+        LogFormat::Json => Box::new(JsonObserver::new(io::stdout())),
+    };
+    let mut organizer = Organizer::builder(&args.root.dir)
+        .threshold(args.threshold)
+        .group_by(args.group_by)
+        .jobs(args.jobs)
+        .observer(move |event: &Event| observer.on_event(event))
+        .build()?;
+
+    let scan = organizer.scan();
+    let incoming = organizer.scan_source(&args.source)?;
+    for error in scan.errors.iter().chain(&incoming.errors) {
+        eprintln!("{}", t!("main.warning", error = error));
+    }
+    let scan_status = scan
+        .errors
+        .iter()
+        .chain(&incoming.errors)
+        .next()
+        .map_or(ExitCode::SUCCESS, |e| ExitCode::from(e.exit_code()));
+
+    let options = ImportOptions {
+        mode: if args.move_files {
+            TransferMode::Move
+        } else {
+            TransferMode::Copy
+        },
+        conflicts: args.on_conflict,
+        duplicates: args.on_duplicate,
+    };
+    let plan = organizer.plan_import(&scan, &args.source, &incoming, &options)?;
+    for op in &plan.renamed {
+        console.say(
+            Verbosity::Normal,
+            &t!(
+                "event.conflict",
+                from = op.from.display(),
+                to = op.to.display()
+            ),
+        );
+    }
+
+    if args.dry_run {
+        if console.human {
+            output::print_import_plan(&plan, verbosity >= Verbosity::Verbose);
+        }
+        let simulation = organizer.simulate(&plan.rebalance);
+        output::print_simulation(&console, &simulation);
+        return Ok(simulation
+            .failures
+            .first()
+            .map_or(scan_status, |e| ExitCode::from(e.exit_code())));
+    }
+
+    if plan.transfers.is_empty() {
+        console.say(Verbosity::Normal, &t!("import.empty"));
+    }
+    let report = organizer.execute_import(&plan)?;
+    if !report.imported.is_empty() {
+        console.say(
+            Verbosity::Normal,
+            &t!("import.done", count = report.imported.len()),
+        );
+    }
+    output::print_summary(&console, &report);
+    if !plan.duplicates.is_empty() || !plan.skipped.is_empty() {
+        console.say(
+            Verbosity::Normal,
+            &t!(
+                "import.skipped",
+                duplicates = plan.duplicates.len(),
+                clashes = plan.skipped.len()
+            ),
+        );
+    }
+
+    Ok(scan_status)
+}
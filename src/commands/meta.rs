@@ -0,0 +1,58 @@
+//! `meta` 하위 명령.
+
+use crate::cli::{MetaCommand, MetaSetArgs};
+use anyhow::Result;
+use ps_organizer::metadata::MetadataStore;
+use ps_organizer::t;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::ExitCode;
+
+/// `meta` 하위 명령을 실행합니다.
+pub fn run(action: &MetaCommand) -> Result<ExitCode> {
+    match action {
+        MetaCommand::Set(args) => set(args),
+        MetaCommand::Get { number, root } => get(*number, &root.dir),
+    }
+}
+
+fn set(args: &MetaSetArgs) -> Result<ExitCode> {
+    let mut store = MetadataStore::load(&args.root.dir)?;
+    if args.clear {
+        store.remove(args.number);
+    }
+
+    let meta = store.entry(args.number);
+    if let Some(title) = &args.title {
+        meta.title = Some(title.clone());
+    }
+    if let Some(tier) = &args.tier {
+        meta.tier = Some(tier.clone());
+    }
+    if let Some(tags) = &args.tags {
+        meta.tags.clone_from(tags);
+    }
+    if let Some(status) = args.status {
+        meta.status = Some(status);
+    }
+    if let Some(solved) = args.solved {
+        meta.solved = Some(solved);
+    }
+
+    store.save(&args.root.dir)?;
+    println!("{}", t!("meta.saved", number = args.number));
+    Ok(ExitCode::SUCCESS)
+}
+
+/// 메타데이터를 저장소 파일과 같은 TOML 형식으로 출력합니다.
+fn get(number: u32, root: &Path) -> Result<ExitCode> {
+    let store = MetadataStore::load(root)?;
+    let Some(meta) = store.get(number).filter(|meta| !meta.is_empty()) else {
+        eprintln!("{}", t!("meta.not_found", number = number));
+        return Ok(ExitCode::FAILURE);
+    };
+
+    let document = BTreeMap::from([(number.to_string(), meta)]);
+    print!("{}", toml::to_string(&document)?);
+    Ok(ExitCode::SUCCESS)
+}
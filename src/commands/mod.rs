@@ -0,0 +1,10 @@
+//! 하위 명령 실행.
+
+pub mod conflicts;
+pub mod export;
+pub mod find;
+pub mod flatten;
+pub mod import;
+pub mod meta;
+pub mod new;
+pub mod path;
@@ -0,0 +1,78 @@
+//! `new` 하위 명령.
+
+use crate::cli::NewArgs;
+use anyhow::Result;
+use ps_organizer::metadata;
+use ps_organizer::organizer::Organizer;
+use ps_organizer::scaffold::{self, TemplateContext};
+use ps_organizer::t;
+use ps_organizer::types::Error;
+use std::env;
+use std::path::Path;
+use std::process::ExitCode;
+
+/// 작성자를 지정하지 않았을 때 읽는 환경 변수.
+const AUTHOR_ENV: &str = "PS_ORGANIZER_AUTHOR";
+
+/// 새 풀이 파일을 만들고, 만든 파일의 경로만 표준 출력에 씁니다.
+///
+/// 파일이 이미 있으면 덮어쓰지 않고 다음 변형 이름을 안내합니다.
+pub fn run(args: &NewArgs) -> Result<ExitCode> {
+    let Some(ext) = scaffold::solution_extension(&args.ext) else {
+        eprintln!("{}", t!("new.unsupported_lang", lang = args.ext));
+        return Ok(ExitCode::FAILURE);
+    };
+
+    let mut organizer = Organizer::builder(&args.root.dir)
+        .threshold(args.threshold)
+        .group_by(args.group_by)
+        .build()?;
+    let scan = organizer.scan();
+    for error in &scan.errors {
+        eprintln!("{}", t!("main.warning", error = error));
+    }
+
+    let root = organizer.root().to_path_buf();
+    let target = organizer.placement(&scan, args.number, &ext);
+    let folder = target.parent().unwrap_or_else(|| Path::new(""));
+    let path = if args.variant && root.join(&target).exists() {
+        scaffold::next_variant(&root, folder, args.number, &ext)
+    } else {
+        target.clone()
+    };
+
+    let mut context = TemplateContext::new(args.number, ext.as_str(), metadata::today());
+    context.author = args
+        .author
+        .clone()
+        .or_else(|| env::var(AUTHOR_ENV).ok())
+        .unwrap_or_default();
+    if let Some(title) = organizer
+        .metadata()
+        .get(args.number)
+        .and_then(|meta| meta.title.clone())
+    {
+        context.title = title;
+    }
+    let contents = scaffold::render(&scaffold::load_template(&root, &ext)?, &context);
+
+    match scaffold::create_file(&root.join(&path), &contents) {
+        Ok(()) => {
+            println!("{}", root.join(&path).display());
+            Ok(ExitCode::SUCCESS)
+        }
+        Err(error @ Error::DestinationExists { .. }) => {
+            let suggestion = scaffold::next_variant(&root, folder, args.number, &ext);
+            eprintln!(
+                "{}",
+                t!(
+                    "new.exists",
+                    path = path.display(),
+                    suggestion = suggestion.display()
+                )
+            );
+            Ok(ExitCode::from(error.exit_code()))
+        }
+        Err(error) => Err(error.into()),
+    }
+}
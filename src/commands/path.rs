@@ -0,0 +1,47 @@
+//! `path` 하위 명령.
+
+use crate::cli::PathArgs;
+use anyhow::Result;
+use ps_organizer::organizer::Organizer;
+use ps_organizer::{scaffold, t};
+use std::process::ExitCode;
+
+/// 한 번에 경로를 계산할 수 있는 최대 문제 수.
+const MAX_NUMBERS: u64 = 10_000;
+
+/// 범위의 문제마다 풀이 파일이 놓일 경로를 한 줄에 하나씩 출력합니다.
+///
+/// 이미 있는 풀이는 현재 경로를 출력합니다.
+pub fn run(args: &PathArgs) -> Result<ExitCode> {
+    let Some(ext) = scaffold::solution_extension(&args.ext) else {
+        eprintln!("{}", t!("new.unsupported_lang", lang = args.ext));
+        return Ok(ExitCode::FAILURE);
+    };
+    if args.range.len() > MAX_NUMBERS {
+        eprintln!(
+            "{}",
+            t!(
+                "path.range_too_large",
+                range = args.range,
+                max = MAX_NUMBERS
+            )
+        );
+        return Ok(ExitCode::FAILURE);
+    }
+
+    let mut organizer = Organizer::builder(&args.root.dir)
+        .threshold(args.threshold)
+        .group_by(args.group_by)
+        .extension_policy(args.scan.extension_policy())
+        .symlinks(args.scan.symlinks.into())
+        .build()?;
+    let scan = organizer.scan();
+    for error in &scan.errors {
+        eprintln!("{}", t!("main.warning", error = error));
+    }
+
+    for (_, path) in organizer.placements(&scan, &ext, &args.range) {
+        println!("{}", organizer.root().join(path).display());
+    }
+    Ok(ExitCode::SUCCESS)
+}
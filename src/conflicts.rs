@@ -0,0 +1,443 @@
+//! 충돌 파일 정리.
+//!
+//! 정리 중 이름이 겹쳐 `_conflictN`이 붙은 파일을 찾아 같은 폴더의 본 파일과
+//! 비교하고, 유지, 삭제, 승격 중 하나로 처리합니다.
+
+use crate::events::NoopObserver;
+use crate::executor::temp_path;
+use crate::planner::{conflict_base, conflict_number};
+use crate::scanner::{ScanOptions, scan_directory_in};
+use crate::t;
+use crate::types::Error;
+use crate::vfs::{FileSystem, RealFs};
+use std::io::{self, BufRead, Read, Write};
+use std::path::{Path, PathBuf};
+
+/// 비교할 두 파일의 줄 수를 곱한 값이 이보다 크면 줄 단위 정렬 없이 통째로 보여 줍니다.
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// 충돌 이름이 붙은 파일.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictFile {
+    /// 루트 디렉토리 기준 상대 경로.
+    pub path: PathBuf,
+    /// 같은 폴더에서 충돌 꼬리표를 뗀 본 파일 경로 (없을 수도 있음).
+    pub main: PathBuf,
+}
+
+/// 충돌 파일 검색 결과.
+#[derive(Debug, Default)]
+pub struct ConflictReport {
+    /// 경로 순으로 정렬한 충돌 파일 목록.
+    pub conflicts: Vec<ConflictFile>,
+    /// 스캔 중 발생한 에러 목록.
+    pub errors: Vec<Error>,
+}
+
+/// 스캐너가 수집하는 파일 가운데 충돌 이름이 붙은 파일을 모두 찾습니다.
+pub fn find_conflicts(root: &Path, options: &ScanOptions) -> ConflictReport {
+    find_conflicts_in(&RealFs, root, options)
+}
+
+/// 주어진 파일 시스템에서 [`find_conflicts`]와 같이 찾습니다.
+pub fn find_conflicts_in(
+    fs: &dyn FileSystem,
+    root: &Path,
+    options: &ScanOptions,
+) -> ConflictReport {
+    let scan = scan_directory_in(fs, root, options, &mut NoopObserver);
+    let conflicts = scan
+        .entries
+        .into_iter()
+        .filter(|entry| conflict_number(&entry.filename).is_some())
+        .map(|entry| ConflictFile {
+            main: entry
+                .current_path
+                .with_file_name(conflict_base(&entry.filename)),
+            path: entry.current_path,
+        })
+        .collect();
+    ConflictReport {
+        conflicts,
+        errors: scan.errors,
+    }
+}
+
+/// 줄 단위 비교 결과의 한 줄.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    /// 두 파일에 모두 있는 줄.
+    Same(String),
+    /// 본 파일에만 있는 줄.
+    Removed(String),
+    /// 충돌 파일에만 있는 줄.
+    Added(String),
+}
+
+/// 두 텍스트를 줄 단위로 비교합니다 (최장 공통 부분열 기준).
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    if old.len().saturating_mul(new.len()) > MAX_DIFF_CELLS {
+        return old
+            .iter()
+            .map(|line| DiffLine::Removed((*line).to_string()))
+            .chain(new.iter().map(|line| DiffLine::Added((*line).to_string())))
+            .collect();
+    }
+
+    // lcs[i][j]: old[i..]와 new[j..]의 최장 공통 부분열 길이.
+    let width = new.len() + 1;
+    let mut lcs = vec![0usize; (old.len() + 1) * width];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i * width + j] = if old[i] == new[j] {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut diff = Vec::new();
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            diff.push(DiffLine::Same(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lcs[(i + 1) * width + j] >= lcs[i * width + j + 1] {
+            diff.push(DiffLine::Removed(old[i].to_string()));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(new[j].to_string()));
+            j += 1;
+        }
+    }
+    diff.extend(
+        old[i..]
+            .iter()
+            .map(|line| DiffLine::Removed((*line).to_string())),
+    );
+    diff.extend(
+        new[j..]
+            .iter()
+            .map(|line| DiffLine::Added((*line).to_string())),
+    );
+    diff
+}
+
+/// 비교 결과를 `-`/`+`/` ` 접두어를 붙인 줄로 바꿉니다.
+///
+/// 바뀐 줄에서 `context`줄보다 멀리 떨어진 같은 줄은 `...` 한 줄로 줄입니다.
+pub fn render_diff(diff: &[DiffLine], context: usize) -> Vec<String> {
+    let changed: Vec<usize> = diff
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !matches!(line, DiffLine::Same(_)))
+        .map(|(index, _)| index)
+        .collect();
+    let near_change = |index: usize| changed.iter().any(|&c| c.abs_diff(index) <= context);
+
+    let mut lines = Vec::new();
+    let mut skipped = false;
+    for (index, line) in diff.iter().enumerate() {
+        let text = match line {
+            DiffLine::Same(_) if !near_change(index) => {
+                if !skipped {
+                    lines.push("...".to_string());
+                    skipped = true;
+                }
+                continue;
+            }
+            DiffLine::Same(text) => format!(" {text}"),
+            DiffLine::Removed(text) => format!("-{text}"),
+            DiffLine::Added(text) => format!("+{text}"),
+        };
+        skipped = false;
+        lines.push(text);
+    }
+    lines
+}
+
+/// 충돌 파일 처리 방법.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    /// 그대로 둡니다.
+    Keep,
+    /// 충돌 파일을 지웁니다.
+    Delete,
+    /// 충돌 파일을 본 파일 이름으로 바꾸고, 본 파일은 충돌 파일 이름으로 바꿉니다.
+    Promote,
+}
+
+/// 충돌 파일을 처리합니다.
+///
+/// # Errors
+///
+/// 파일을 지우거나 이름을 바꾸지 못하면 에러를 반환합니다.
+pub fn resolve(root: &Path, conflict: &ConflictFile, resolution: Resolution) -> Result<(), Error> {
+    resolve_in(&RealFs, root, conflict, resolution)
+}
+
+/// 주어진 파일 시스템에서 [`resolve`]와 같이 처리합니다.
+///
+/// # Errors
+///
+/// [`resolve`]와 같습니다.
+pub fn resolve_in(
+    fs: &dyn FileSystem,
+    root: &Path,
+    conflict: &ConflictFile,
+    resolution: Resolution,
+) -> Result<(), Error> {
+    let path = root.join(&conflict.path);
+    let main = root.join(&conflict.main);
+    let rename = |from: &Path, to: &Path| {
+        fs.rename(from, to)
+            .map_err(|source| Error::from_move(from.to_path_buf(), to.to_path_buf(), source))
+    };
+
+    match resolution {
+        Resolution::Keep => Ok(()),
+        Resolution::Delete => fs
+            .remove_file(&path)
+            .map_err(|source| Error::from_source(path, source)),
+        Resolution::Promote if !fs.exists(&main) => rename(&path, &main),
+        Resolution::Promote => {
+            let temp = (1..=usize::MAX)
+                .map(|count| temp_path(&main, count))
+                .find(|temp| !fs.exists(temp))
+                .unwrap_or_else(|| temp_path(&main, 0));
+            rename(&main, &temp)?;
+            rename(&path, &main)?;
+            rename(&temp, &path)
+        }
+    }
+}
+
+/// 충돌 파일과 본 파일의 차이를 출력합니다.
+///
+/// # Errors
+///
+/// 출력에 실패하면 에러를 반환합니다.
+pub fn describe_conflict(
+    root: &Path,
+    conflict: &ConflictFile,
+    context: usize,
+    output: impl Write,
+) -> io::Result<()> {
+    describe_conflict_in(&RealFs, root, conflict, context, output)
+}
+
+/// 주어진 파일 시스템에서 [`describe_conflict`]와 같이 출력합니다.
+///
+/// # Errors
+///
+/// 출력에 실패하면 에러를 반환합니다.
+pub fn describe_conflict_in(
+    fs: &dyn FileSystem,
+    root: &Path,
+    conflict: &ConflictFile,
+    context: usize,
+    mut output: impl Write,
+) -> io::Result<()> {
+    let read = |path: &Path| {
+        let mut bytes = Vec::new();
+        fs.open(&root.join(path))
+            .and_then(|mut file| file.read_to_end(&mut bytes))
+            .ok()
+            .map(|_| String::from_utf8_lossy(&bytes).into_owned())
+    };
+    let Some(main) = read(&conflict.main) else {
+        return writeln!(
+            output,
+            "{}",
+            t!("conflicts.no_main", main = conflict.main.display())
+        );
+    };
+    let contents = read(&conflict.path).unwrap_or_default();
+
+    let diff = diff_lines(&main, &contents);
+    if diff.iter().all(|line| matches!(line, DiffLine::Same(_))) {
+        return writeln!(output, "{}", t!("conflicts.identical"));
+    }
+    writeln!(output, "--- {}", conflict.main.display())?;
+    writeln!(output, "+++ {}", conflict.path.display())?;
+    for line in render_diff(&diff, context) {
+        writeln!(output, "{line}")?;
+    }
+    Ok(())
+}
+
+/// 충돌 파일 하나를 어떻게 처리할지 묻습니다.
+///
+/// 차이를 보여 준 뒤 `input`에서 한 줄씩 읽어 `k`(유지), `d`(삭제), `p`(승격)
+/// 가운데 하나를 받습니다. `q`를 입력하거나 입력이 끝나면 `None`을 반환합니다.
+///
+/// # Errors
+///
+/// 입출력에 실패하면 에러를 반환합니다.
+pub fn prompt_resolution(
+    root: &Path,
+    conflict: &ConflictFile,
+    context: usize,
+    mut input: impl BufRead,
+    mut output: impl Write,
+) -> io::Result<Option<Resolution>> {
+    describe_conflict(root, conflict, context, &mut output)?;
+
+    let mut line = String::new();
+    loop {
+        write!(output, "{}", t!("conflicts.prompt"))?;
+        output.flush()?;
+
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        match line.trim() {
+            "k" => return Ok(Some(Resolution::Keep)),
+            "d" => return Ok(Some(Resolution::Delete)),
+            "p" => return Ok(Some(Resolution::Promote)),
+            "q" => return Ok(None),
+            "" => {}
+            "?" => writeln!(output, "{}", t!("conflicts.help"))?,
+            choice => writeln!(output, "{}", t!("conflicts.unknown", choice = choice))?,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::{self, Lang};
+    use std::fs;
+    use std::io::Cursor;
+    use tempfile::TempDir;
+
+    fn conflict(path: &str, main: &str) -> ConflictFile {
+        ConflictFile {
+            path: PathBuf::from(path),
+            main: PathBuf::from(main),
+        }
+    }
+
+    #[test]
+    fn find_conflicts_pairs_with_main_file() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir(temp.path().join("1000")).unwrap();
+        for name in [
+            "1000/1000.cpp",
+            "1000/1000_conflict1.cpp",
+            "1000/1001_2_conflict1.py",
+            "etc/main_conflict2.cpp",
+        ] {
+            let path = temp.path().join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+
+        let report = find_conflicts(temp.path(), &ScanOptions::default());
+
+        assert_eq!(
+            report.conflicts,
+            vec![
+                conflict("1000/1000_conflict1.cpp", "1000/1000.cpp"),
+                conflict("1000/1001_2_conflict1.py", "1000/1001_2.py"),
+                conflict("etc/main_conflict2.cpp", "etc/main.cpp"),
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_lines_keeps_common_lines() {
+        let diff = diff_lines("a\nb\nc\n", "a\nx\nc\nd\n");
+
+        assert_eq!(
+            diff,
+            vec![
+                DiffLine::Same("a".to_string()),
+                DiffLine::Removed("b".to_string()),
+                DiffLine::Added("x".to_string()),
+                DiffLine::Same("c".to_string()),
+                DiffLine::Added("d".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn render_diff_collapses_distant_lines() {
+        let old = (1..=10)
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let new = old.replace("5\n", "five\n");
+
+        let lines = render_diff(&diff_lines(&old, &new), 1);
+
+        assert_eq!(lines, ["...", " 4", "-5", "+five", " 6", "..."]);
+    }
+
+    #[test]
+    fn promote_swaps_with_main_file() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("1000.cpp"), "main").unwrap();
+        fs::write(temp.path().join("1000_conflict1.cpp"), "other").unwrap();
+        let file = conflict("1000_conflict1.cpp", "1000.cpp");
+
+        resolve(temp.path(), &file, Resolution::Promote).unwrap();
+
+        let read = |name| fs::read_to_string(temp.path().join(name)).unwrap();
+        assert_eq!(read("1000.cpp"), "other");
+        assert_eq!(read("1000_conflict1.cpp"), "main");
+        assert_eq!(fs::read_dir(temp.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn promote_without_main_renames_and_delete_removes() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("1000_conflict1.cpp"), "a").unwrap();
+        fs::write(temp.path().join("1001_conflict1.cpp"), "b").unwrap();
+
+        resolve(
+            temp.path(),
+            &conflict("1000_conflict1.cpp", "1000.cpp"),
+            Resolution::Promote,
+        )
+        .unwrap();
+        resolve(
+            temp.path(),
+            &conflict("1001_conflict1.cpp", "1001.cpp"),
+            Resolution::Delete,
+        )
+        .unwrap();
+
+        let mut names: Vec<_> = fs::read_dir(temp.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["1000.cpp"]);
+    }
+
+    #[test]
+    fn prompt_retries_until_valid_choice() {
+        i18n::set_lang(Lang::Ko);
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("1000.cpp"), "a\n").unwrap();
+        fs::write(temp.path().join("1000_conflict1.cpp"), "b\n").unwrap();
+        let file = conflict("1000_conflict1.cpp", "1000.cpp");
+        let mut output = Vec::new();
+
+        let choice =
+            prompt_resolution(temp.path(), &file, 3, Cursor::new("x\np\n"), &mut output).unwrap();
+        let eof = prompt_resolution(temp.path(), &file, 3, Cursor::new(""), io::sink()).unwrap();
+
+        assert_eq!(choice, Some(Resolution::Promote));
+        assert_eq!(eof, None);
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("-a\n+b\n"));
+        assert!(output.contains("알 수 없는 선택: x"));
+    }
+}
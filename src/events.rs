@@ -0,0 +1,365 @@
+//! 구조화된 실행 이벤트와 관찰자.
+//!
+//! 라이브러리 함수는 화면에 직접 출력하지 않고 [`Observer`]에 [`Event`]를 전달합니다.
+//! 사람이 읽는 텍스트 로그는 [`TextObserver`], 도구 연동용 JSON Lines 로그는
+//! [`JsonObserver`]가 담당합니다.
+
+use crate::t;
+use serde::{Serialize, Serializer};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// 출력 상세 수준.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// 에러만 출력 (`-q`).
+    Quiet,
+    /// 요약과 충돌만 출력 (기본값).
+    Normal,
+    /// 파일 이동과 폴더 생성/삭제까지 출력 (`-v`).
+    Verbose,
+    /// 스캔과 계획 단계까지 모두 출력 (`-vv`).
+    Debug,
+}
+
+impl Verbosity {
+    /// `-q` 여부와 `-v` 반복 횟수로 상세 수준을 결정합니다.
+    pub const fn from_flags(quiet: bool, verbose: u8) -> Self {
+        match (quiet, verbose) {
+            (true, _) => Self::Quiet,
+            (false, 0) => Self::Normal,
+            (false, 1) => Self::Verbose,
+            (false, _) => Self::Debug,
+        }
+    }
+}
+
+fn display_path<S: Serializer>(path: &Path, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&path.to_string_lossy())
+}
+
+/// 정리 과정에서 발생하는 이벤트.
+///
+/// 가져오기 이벤트의 원본 경로는 원본 디렉토리 기준, 나머지 경로는 모두 대상
+/// 디렉토리 기준 상대 경로입니다.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    /// 스캔 중 소스 파일을 발견함.
+    ScanFound {
+        /// 발견한 파일 경로.
+        #[serde(serialize_with = "display_path")]
+        path: PathBuf,
+        /// 추출한 문제 번호.
+        problem_number: Option<u32>,
+    },
+    /// 스캔 캐시를 사용함.
+    ScanCached {
+        /// 캐시로 목록을 대신한 디렉토리 수.
+        reused: usize,
+        /// 목록을 새로 읽은 디렉토리 수.
+        read: usize,
+    },
+    /// 이동이 계획됨.
+    Planned {
+        /// 이동 전 경로.
+        #[serde(serialize_with = "display_path")]
+        from: PathBuf,
+        /// 이동 후 경로.
+        #[serde(serialize_with = "display_path")]
+        to: PathBuf,
+    },
+    /// 파일을 이동함.
+    Moved {
+        /// 이동 전 경로.
+        #[serde(serialize_with = "display_path")]
+        from: PathBuf,
+        /// 이동 후 경로.
+        #[serde(serialize_with = "display_path")]
+        to: PathBuf,
+    },
+    /// 폴더를 생성함.
+    DirCreated {
+        /// 생성한 폴더 경로.
+        #[serde(serialize_with = "display_path")]
+        path: PathBuf,
+    },
+    /// 빈 폴더를 삭제함.
+    DirRemoved {
+        /// 삭제한 폴더 경로.
+        #[serde(serialize_with = "display_path")]
+        path: PathBuf,
+    },
+    /// 옮긴 파일을 가리키던 링크를 새 위치로 고침.
+    LinkUpdated {
+        /// 링크 경로.
+        #[serde(serialize_with = "display_path")]
+        path: PathBuf,
+        /// 링크의 새 대상.
+        #[serde(serialize_with = "display_path")]
+        target: PathBuf,
+    },
+    /// 목적지 파일명이 겹쳐 이름을 바꿈.
+    Conflict {
+        /// 이동 전 경로.
+        #[serde(serialize_with = "display_path")]
+        from: PathBuf,
+        /// 충돌을 피해 바뀐 목적지 경로.
+        #[serde(serialize_with = "display_path")]
+        to: PathBuf,
+    },
+    /// 다른 디렉토리의 파일을 가져옴.
+    Imported {
+        /// 원본 경로.
+        #[serde(serialize_with = "display_path")]
+        from: PathBuf,
+        /// 가져온 경로.
+        #[serde(serialize_with = "display_path")]
+        to: PathBuf,
+    },
+    /// 내용이 같은 파일이 이미 있어 가져오지 않음.
+    Duplicate {
+        /// 원본 경로.
+        #[serde(serialize_with = "display_path")]
+        path: PathBuf,
+        /// 내용이 같은 기존 파일 경로.
+        #[serde(serialize_with = "display_path")]
+        existing: PathBuf,
+    },
+    /// 목적지 이름이 겹쳐 가져오지 않음.
+    ImportSkipped {
+        /// 원본 경로.
+        #[serde(serialize_with = "display_path")]
+        path: PathBuf,
+        /// 겹친 목적지 경로.
+        #[serde(serialize_with = "display_path")]
+        to: PathBuf,
+    },
+    /// 모을 폴더에서 이름이 겹쳐 옮기지 않고 제자리에 둠.
+    FlattenSkipped {
+        /// 현재 경로.
+        #[serde(serialize_with = "display_path")]
+        path: PathBuf,
+        /// 겹친 목적지 경로.
+        #[serde(serialize_with = "display_path")]
+        to: PathBuf,
+    },
+    /// 파일명과 머리 주석의 문제 번호가 다름. 파일명 번호를 사용합니다.
+    HeaderMismatch {
+        /// 파일 경로.
+        #[serde(serialize_with = "display_path")]
+        path: PathBuf,
+        /// 파일명에서 추출한 번호.
+        filename_number: u32,
+        /// 머리 주석에서 추출한 번호.
+        header_number: u32,
+    },
+}
+
+impl Event {
+    /// 텍스트 로그에서 이 이벤트를 보여줄 최소 상세 수준.
+    pub const fn verbosity(&self) -> Verbosity {
+        match self {
+            Self::ScanFound { .. } | Self::Planned { .. } => Verbosity::Debug,
+            Self::ScanCached { .. }
+            | Self::Moved { .. }
+            | Self::Imported { .. }
+            | Self::DirCreated { .. }
+            | Self::DirRemoved { .. }
+            | Self::LinkUpdated { .. } => Verbosity::Verbose,
+            Self::Conflict { .. }
+            | Self::Duplicate { .. }
+            | Self::ImportSkipped { .. }
+            | Self::FlattenSkipped { .. }
+            | Self::HeaderMismatch { .. } => Verbosity::Normal,
+        }
+    }
+
+    /// 현재 언어로 된 한 줄 설명을 반환합니다.
+    pub fn message(&self) -> String {
+        match self {
+            Self::ScanFound {
+                path,
+                problem_number: Some(number),
+            } => t!("event.scan_found", path = path.display(), number = number),
+            Self::ScanFound {
+                path,
+                problem_number: None,
+            } => t!("event.scan_found_unnumbered", path = path.display()),
+            Self::ScanCached { reused, read } => {
+                t!("event.scan_cached", reused = reused, read = read)
+            }
+            Self::Planned { from, to } => {
+                t!("event.planned", from = from.display(), to = to.display())
+            }
+            Self::Moved { from, to } => t!("exec.moved", from = from.display(), to = to.display()),
+            Self::DirCreated { path } => t!("exec.dir_created", path = path.display()),
+            Self::DirRemoved { path } => t!("exec.dir_removed", path = path.display()),
+            Self::LinkUpdated { path, target } => t!(
+                "event.link_updated",
+                path = path.display(),
+                target = target.display()
+            ),
+            Self::Conflict { from, to } => {
+                t!("event.conflict", from = from.display(), to = to.display())
+            }
+            Self::Imported { from, to } => {
+                t!("event.imported", from = from.display(), to = to.display())
+            }
+            Self::Duplicate { path, existing } => t!(
+                "event.duplicate",
+                path = path.display(),
+                existing = existing.display()
+            ),
+            Self::ImportSkipped { path, to } => {
+                t!(
+                    "event.import_skipped",
+                    path = path.display(),
+                    to = to.display()
+                )
+            }
+            Self::FlattenSkipped { path, to } => {
+                t!(
+                    "event.flatten_skipped",
+                    path = path.display(),
+                    to = to.display()
+                )
+            }
+            Self::HeaderMismatch {
+                path,
+                filename_number,
+                header_number,
+            } => t!(
+                "event.header_mismatch",
+                path = path.display(),
+                filename = filename_number,
+                header = header_number
+            ),
+        }
+    }
+}
+
+/// 이벤트를 전달받는 관찰자.
+pub trait Observer {
+    /// 이벤트가 발생할 때마다 호출됩니다.
+    fn on_event(&mut self, event: &Event);
+}
+
+impl<F: FnMut(&Event)> Observer for F {
+    fn on_event(&mut self, event: &Event) {
+        self(event);
+    }
+}
+
+/// 모든 이벤트를 무시하는 관찰자.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopObserver;
+
+impl Observer for NoopObserver {
+    fn on_event(&mut self, _event: &Event) {}
+}
+
+/// 상세 수준에 맞는 이벤트를 사람이 읽는 문장으로 기록하는 관찰자.
+#[derive(Debug)]
+pub struct TextObserver<W> {
+    out: W,
+    verbosity: Verbosity,
+}
+
+impl<W: Write> TextObserver<W> {
+    /// 새 텍스트 관찰자를 생성합니다.
+    pub const fn new(out: W, verbosity: Verbosity) -> Self {
+        Self { out, verbosity }
+    }
+}
+
+impl<W: Write> Observer for TextObserver<W> {
+    fn on_event(&mut self, event: &Event) {
+        if self.verbosity >= event.verbosity() {
+            // 로그 출력 실패로 정리 작업을 중단하지 않습니다.
+            let _ = writeln!(self.out, "{}", event.message());
+        }
+    }
+}
+
+/// 모든 이벤트를 한 줄에 하나씩 JSON 객체로 기록하는 관찰자.
+#[derive(Debug)]
+pub struct JsonObserver<W> {
+    out: W,
+}
+
+impl<W: Write> JsonObserver<W> {
+    /// 새 JSON Lines 관찰자를 생성합니다.
+    pub const fn new(out: W) -> Self {
+        Self { out }
+    }
+}
+
+impl<W: Write> Observer for JsonObserver<W> {
+    fn on_event(&mut self, event: &Event) {
+        if serde_json::to_writer(&mut self.out, event).is_ok() {
+            let _ = writeln!(self.out);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::{self, Lang};
+
+    #[test]
+    fn verbosity_from_flags() {
+        assert_eq!(Verbosity::from_flags(true, 2), Verbosity::Quiet);
+        assert_eq!(Verbosity::from_flags(false, 0), Verbosity::Normal);
+        assert_eq!(Verbosity::from_flags(false, 1), Verbosity::Verbose);
+        assert_eq!(Verbosity::from_flags(false, 5), Verbosity::Debug);
+    }
+
+    #[test]
+    fn json_observer_writes_one_object_per_line() {
+        let mut out = Vec::new();
+        let mut observer = JsonObserver::new(&mut out);
+
+        observer.on_event(&Event::Moved {
+            from: PathBuf::from("old/1001.cpp"),
+            to: PathBuf::from("1001.cpp"),
+        });
+        observer.on_event(&Event::DirRemoved {
+            path: PathBuf::from("old"),
+        });
+        observer.on_event(&Event::ImportSkipped {
+            path: PathBuf::from("1002.cpp"),
+            to: PathBuf::from("1002.cpp"),
+        });
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "{\"event\":\"moved\",\"from\":\"old/1001.cpp\",\"to\":\"1001.cpp\"}\n\
+             {\"event\":\"dir_removed\",\"path\":\"old\"}\n\
+             {\"event\":\"import_skipped\",\"path\":\"1002.cpp\",\"to\":\"1002.cpp\"}\n"
+        );
+    }
+
+    #[test]
+    fn text_observer_filters_by_verbosity() {
+        i18n::set_lang(Lang::Ko);
+        let moved = Event::Moved {
+            from: PathBuf::from("a.cpp"),
+            to: PathBuf::from("etc/a.cpp"),
+        };
+        let conflict = Event::Conflict {
+            from: PathBuf::from("old/1.cpp"),
+            to: PathBuf::from("1_conflict1.cpp"),
+        };
+
+        let mut out = Vec::new();
+        let mut observer = TextObserver::new(&mut out, Verbosity::Normal);
+        observer.on_event(&moved);
+        observer.on_event(&conflict);
+
+        let output = String::from_utf8(out).unwrap();
+        assert!(!output.contains("a.cpp"));
+        assert!(output.contains("1_conflict1.cpp"));
+    }
+}
@@ -1,123 +1,706 @@
 //! 이동 계획 실행 및 정리.
 
-use crate::types::MoveOperation;
-use anyhow::{Context, Result};
-use std::collections::HashSet;
-use std::fs;
-use std::path::Path;
-
-/// 이동 계획을 화면에 출력합니다 (dry-run 모드용).
-pub fn print_plan(moves: &[MoveOperation], verbose: bool) {
-    if moves.is_empty() {
-        println!("변경 사항 없음. 모든 파일이 이미 올바른 위치에 있습니다.");
-        return;
+use crate::events::{Event, Observer};
+use crate::metadata::STATE_DIR;
+use crate::types::{Error, MoveOperation};
+use crate::vfs::{EntryKind, FileSystem, RealFs};
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashSet};
+use std::ffi::OsString;
+#[cfg(unix)]
+use std::path::Component;
+use std::path::{Path, PathBuf};
+
+/// 실제로 수행할 이름 변경 한 단계.
+pub(crate) struct Step {
+    pub(crate) from: PathBuf,
+    pub(crate) to: PathBuf,
+    /// 이 단계가 끝나면 완료되는 원래 이동. 임시 이름으로 옮기는 단계는 `None`.
+    pub(crate) completes: Option<MoveOperation>,
+}
+
+/// 같은 폴더의 숨김 임시 이름.
+pub(crate) fn temp_path(path: &Path, count: usize) -> PathBuf {
+    let mut name = OsString::from(".");
+    name.push(path.file_name().unwrap_or_default());
+    name.push(format!(".ps-organizer-tmp{count}"));
+    path.with_file_name(name)
+}
+
+/// 이동 순서를 정합니다.
+///
+/// 다른 이동이 비워 줄 자리를 목적지로 하는 이동은 그 이동 뒤로 미룹니다.
+/// 서로 자리를 바꾸는 순환은 원본 하나를 임시 이름으로 옮겨 풉니다.
+pub(crate) fn schedule(moves: &[MoveOperation]) -> Vec<Step> {
+    let mut pending: Vec<(MoveOperation, MoveOperation)> =
+        moves.iter().map(|op| (op.clone(), op.clone())).collect();
+    let mut steps = Vec::with_capacity(moves.len());
+    let mut temp_count = 0;
+
+    while !pending.is_empty() {
+        let sources: HashSet<PathBuf> = pending.iter().map(|(op, _)| op.from.clone()).collect();
+        let (ready, blocked): (Vec<_>, Vec<_>) = pending
+            .into_iter()
+            .partition(|(op, _)| !sources.contains(&op.to));
+
+        if ready.is_empty() {
+            let mut blocked = blocked.into_iter();
+            let Some((op, original)) = blocked.next() else {
+                break;
+            };
+            temp_count += 1;
+            let temp = temp_path(&op.from, temp_count);
+            steps.push(Step {
+                from: op.from,
+                to: temp.clone(),
+                completes: None,
+            });
+            pending = blocked.collect();
+            pending.push((MoveOperation::new(temp, op.to), original));
+            continue;
+        }
+
+        steps.extend(ready.into_iter().map(|(op, original)| Step {
+            from: op.from,
+            to: op.to,
+            completes: Some(original),
+        }));
+        pending = blocked;
     }
 
-    println!("=== 이동 계획 ({} 개 파일) ===\n", moves.len());
+    steps
+}
 
-    for op in moves {
-        println!("  {} -> {}", op.from.display(), op.to.display());
+/// 두 경로가 같은 파일을 가리키는지 확인합니다.
+///
+/// inode 번호를 알 수 없는 환경에서는 링크를 모두 푼 경로를 비교합니다.
+pub(crate) fn is_same_file(fs: &dyn FileSystem, a: &Path, b: &Path) -> bool {
+    match (fs.symlink_metadata(a), fs.symlink_metadata(b)) {
+        (Ok(meta_a), Ok(meta_b)) if meta_a.ino != 0 => {
+            meta_a.dev == meta_b.dev && meta_a.ino == meta_b.ino
+        }
+        (Ok(_), Ok(_)) => match (fs.canonicalize(a), fs.canonicalize(b)) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false,
+        },
+        _ => false,
     }
+}
 
-    if verbose {
-        let folders: HashSet<_> = moves
-            .iter()
-            .filter_map(|m| m.to.parent())
-            .filter(|p| !p.as_os_str().is_empty())
-            .collect();
-
-        if !folders.is_empty() {
-            println!("\n생성될 폴더:");
-            for folder in folders {
-                println!("  {}/", folder.display());
+/// `.`과 `..`을 글자 그대로 풀어 경로를 정리합니다.
+#[cfg(unix)]
+fn normalize_lexically(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push("..");
+                }
             }
+            other => normalized.push(other),
         }
     }
+    normalized
+}
+
+/// `base` 디렉토리에서 `target`으로 가는 상대 경로를 구합니다.
+fn relative_path(target: &Path, base: &Path) -> PathBuf {
+    let target: Vec<_> = target.components().collect();
+    let base: Vec<_> = base.components().collect();
+    let common = target.iter().zip(&base).take_while(|(a, b)| a == b).count();
+
+    let mut relative: PathBuf = base[common..].iter().map(|_| "..").collect();
+    relative.extend(&target[common..]);
+    relative
+}
+
+/// 파일이나 링크 하나를 옮깁니다.
+///
+/// 상대 경로 심볼릭 링크는 옮긴 뒤에도 같은 대상을 가리키도록 링크를 새로
+/// 만듭니다. 절대 경로 링크와 일반 파일은 이름만 바꿉니다.
+fn move_entry(fs: &dyn FileSystem, from: &Path, to: &Path) -> std::io::Result<()> {
+    #[cfg(unix)]
+    if fs.symlink_metadata(from)?.is_symlink() {
+        let target = fs.read_link(from)?;
+        if target.is_relative() {
+            let from_dir = from.parent().unwrap_or(Path::new(""));
+            let to_dir = to.parent().unwrap_or(Path::new(""));
+            let absolute = normalize_lexically(&from_dir.join(&target));
+            let retargeted = relative_path(&absolute, &normalize_lexically(to_dir));
+            fs.symlink(&retargeted, to)?;
+            return fs.remove_file(from);
+        }
+    }
+
+    fs.rename(from, to)
+}
+
+/// 대상 디렉토리 안을 가리키는 심볼릭 링크.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Link {
+    /// 링크 경로 (대상 디렉토리 기준).
+    pub(crate) path: PathBuf,
+    /// 링크를 모두 풀어 얻은 실제 경로 (대상 디렉토리 기준).
+    pub(crate) target: PathBuf,
+    /// 실제 경로가 폴더인지 여부.
+    pub(crate) is_dir: bool,
+}
+
+impl Link {
+    /// `path`가 링크가 가리키는 폴더 안에 있거나 그 폴더를 품고 있어, 정리로
+    /// 지우면 링크가 깨지는지 확인합니다. 파일 링크는 폴더를 붙잡지 않습니다.
+    pub(crate) fn keeps_dir(&self, path: &Path) -> bool {
+        self.is_dir && (path.starts_with(&self.target) || self.target.starts_with(path))
+    }
+}
+
+/// 대상 디렉토리 안을 가리키는 링크를 모두 찾습니다.
+///
+/// 링크를 따라 내려가지 않으며, 깨진 링크와 대상 디렉토리 자체나 바깥을 가리키는
+/// 링크는 뺍니다.
+pub(crate) fn collect_links(fs: &dyn FileSystem, root: &Path) -> Vec<Link> {
+    let canonical_root = fs.canonicalize(root).unwrap_or_else(|_| root.to_path_buf());
+    let mut links = Vec::new();
+    let mut pending = vec![PathBuf::new()];
+    while let Some(relative) = pending.pop() {
+        let Ok(listing) = fs.read_dir(&root.join(&relative)) else {
+            continue;
+        };
+        for entry in listing {
+            let is_state_dir = relative.as_os_str().is_empty() && entry.name == STATE_DIR;
+            let path = relative.join(&entry.name);
+            match entry.kind {
+                EntryKind::Dir if !is_state_dir => pending.push(path),
+                EntryKind::Symlink => {
+                    let Ok(real) = fs.canonicalize(&root.join(&path)) else {
+                        continue;
+                    };
+                    let Ok(target) = real.strip_prefix(&canonical_root) else {
+                        continue;
+                    };
+                    if !target.as_os_str().is_empty() {
+                        links.push(Link {
+                            is_dir: fs.metadata(&real).is_ok_and(|m| m.is_dir()),
+                            target: target.to_path_buf(),
+                            path,
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    links.sort_by(|a, b| a.path.cmp(&b.path));
+    links
+}
+
+/// 옮긴 파일을 가리키던 링크가 새 위치를 가리키도록 다시 만듭니다.
+///
+/// 상대 경로 링크는 새 위치로 가는 상대 경로로, 절대 경로 링크는 새 절대 경로로
+/// 바꿉니다. 링크를 고칠 때마다 [`Event::LinkUpdated`]를 보고합니다.
+///
+/// # Errors
+///
+/// 링크를 다시 만들지 못하면 에러를 반환합니다.
+pub(crate) fn retarget_links_in(
+    fs: &dyn FileSystem,
+    root: &Path,
+    links: &[Link],
+    moves: &[MoveOperation],
+    observer: &mut dyn Observer,
+) -> Result<(), Error> {
+    for link in links.iter().filter(|link| !link.is_dir) {
+        let Some(op) = moves.iter().find(|op| op.from == link.target) else {
+            continue;
+        };
+        let path = root.join(&link.path);
+        let old = fs
+            .read_link(&path)
+            .map_err(|source| Error::from_io(path.clone(), source))?;
+        let target = if old.is_absolute() {
+            fs.canonicalize(root)
+                .map_err(|source| Error::from_io(root.to_path_buf(), source))?
+                .join(&op.to)
+        } else {
+            relative_path(&op.to, link.path.parent().unwrap_or(Path::new("")))
+        };
+        fs.remove_file(&path)
+            .and_then(|()| fs.symlink(&target, &path))
+            .map_err(|source| Error::from_io(path, source))?;
+        observer.on_event(&Event::LinkUpdated {
+            path: link.path.clone(),
+            target,
+        });
+    }
+    Ok(())
+}
+
+/// 대상 디렉토리 기준 상대 경로 `relative`의 부모 폴더가 없으면 만들고
+/// [`Event::DirCreated`]를 보고합니다.
+pub(crate) fn create_parent(
+    fs: &dyn FileSystem,
+    root: &Path,
+    relative: &Path,
+    observer: &mut dyn Observer,
+) -> Result<(), Error> {
+    let Some(parent) = relative.parent() else {
+        return Ok(());
+    };
+    let parent_abs = root.join(parent);
+    if !fs.exists(&parent_abs) {
+        fs.create_dir_all(&parent_abs)
+            .map_err(|source| Error::from_io(parent_abs, source))?;
+        observer.on_event(&Event::DirCreated {
+            path: parent.to_path_buf(),
+        });
+    }
+    Ok(())
 }
 
 /// 이동 계획을 실제로 실행합니다.
 ///
+/// 다른 파일이 비워 줄 자리로 가는 이동은 순서를 뒤로 미루고, 서로 자리를
+/// 바꾸는 이동은 임시 이름을 거쳐 실행합니다. 심볼릭 링크는 따라가지 않고
+/// 링크 자체를 옮깁니다. 폴더를 만들 때마다
+/// [`Event::DirCreated`]를, 파일을 옮길 때마다 [`Event::Moved`]를 보고합니다.
+///
+/// # Errors
+///
+/// 폴더 생성이나 파일 이동에 실패하면 에러를 반환합니다. 목적지에 이미 파일이
+/// 있으면 덮어쓰지 않고 [`Error::DestinationExists`]를 반환합니다.
+pub fn execute_moves(
+    root: &Path,
+    moves: &[MoveOperation],
+    observer: &mut dyn Observer,
+) -> Result<(), Error> {
+    execute_moves_in(&RealFs, root, moves, observer)
+}
+
+/// 주어진 파일 시스템에서 [`execute_moves`]와 같이 이동 계획을 실행합니다.
+///
 /// # Errors
 ///
 /// 폴더 생성이나 파일 이동에 실패하면 에러를 반환합니다.
-pub fn execute_moves(root: &Path, moves: &[MoveOperation], verbose: bool) -> Result<()> {
-    if moves.is_empty() {
-        println!("변경 사항 없음.");
-        return Ok(());
+pub fn execute_moves_in(
+    fs: &dyn FileSystem,
+    root: &Path,
+    moves: &[MoveOperation],
+    observer: &mut dyn Observer,
+) -> Result<(), Error> {
+    for step in schedule(moves) {
+        let from_abs = root.join(&step.from);
+        let to_abs = root.join(&step.to);
+
+        create_parent(fs, root, &step.to, observer)?;
+
+        // `rename`은 목적지 파일을 조용히 덮어쓰므로 미리 확인합니다. 대소문자를
+        // 구분하지 않는 파일 시스템에서 `1001.CPP` → `1001.cpp`처럼 같은 파일을
+        // 가리키는 경우는 덮어쓰기가 아니므로 허용합니다.
+        if fs.symlink_metadata(&to_abs).is_ok() && !is_same_file(fs, &from_abs, &to_abs) {
+            return Err(Error::DestinationExists { path: to_abs });
+        }
+
+        move_entry(fs, &from_abs, &to_abs)
+            .map_err(|source| Error::from_move(from_abs.clone(), to_abs.clone(), source))?;
+
+        if let Some(op) = step.completes {
+            observer.on_event(&Event::Moved {
+                from: op.from,
+                to: op.to,
+            });
+        }
     }
 
-    println!("=== 파일 이동 중 ({} 개) ===\n", moves.len());
+    Ok(())
+}
+
+/// 빈 폴더 정리 규칙.
+///
+/// 규칙에 `/`가 없으면 경로 구성 요소 가운데 이름이 같은 것이 있을 때, `/`가 있으면
+/// 루트 기준 상대 경로가 그 경로이거나 그 아래일 때 맞습니다 (예: `.git`,
+/// `archive/2023`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CleanupRules {
+    /// 이번 실행과 관계없이 비어 있으면 삭제할 폴더.
+    pub allow: Vec<String>,
+    /// 비어 있어도 삭제하지 않을 폴더. `allow`보다 우선합니다.
+    pub deny: Vec<String>,
+}
 
-    for op in moves {
-        let from_abs = root.join(&op.from);
-        let to_abs = root.join(&op.to);
+impl Default for CleanupRules {
+    fn default() -> Self {
+        Self {
+            allow: Vec::new(),
+            deny: vec![".git".to_string(), STATE_DIR.to_string()],
+        }
+    }
+}
 
-        if let Some(parent) = to_abs.parent()
-            && !parent.exists()
-        {
-            fs::create_dir_all(parent)
-                .with_context(|| format!("폴더 생성 실패: {}", parent.display()))?;
-            if verbose {
-                println!("  [폴더 생성] {}", parent.display());
-            }
+impl CleanupRules {
+    fn matches(rule: &str, path: &Path) -> bool {
+        let rule = rule.trim_matches('/');
+        if rule.is_empty() {
+            false
+        } else if rule.contains('/') {
+            path.starts_with(rule)
+        } else {
+            path.components().any(|c| c.as_os_str() == rule)
         }
+    }
 
-        fs::rename(&from_abs, &to_abs).with_context(|| {
-            format!(
-                "파일 이동 실패: {} -> {}",
-                from_abs.display(),
-                to_abs.display()
-            )
-        })?;
+    /// 상대 경로가 허용 목록에 드는지 확인합니다.
+    pub fn allows(&self, path: &Path) -> bool {
+        self.allow.iter().any(|rule| Self::matches(rule, path))
+    }
 
-        if verbose {
-            println!("  [이동] {} -> {}", op.from.display(), op.to.display());
+    /// 상대 경로가 거부 목록에 드는지 확인합니다.
+    pub fn denies(&self, path: &Path) -> bool {
+        self.deny.iter().any(|rule| Self::matches(rule, path))
+    }
+}
+
+/// 다른 파일 시스템이 연결된 폴더인지 확인합니다.
+///
+/// 폴더나 부모 폴더의 메타데이터를 읽을 수 없거나 부모 폴더가 없으면 알 수 없으므로
+/// 연결 지점으로 보고, 정리에서 지우지 않고 남깁니다. 장치 번호를 알 수 없는
+/// 환경에서는 둘 다 0이므로 `false`입니다.
+pub(crate) fn is_mount_point(fs: &dyn FileSystem, path: &Path) -> bool {
+    let Some(parent) = path.parent() else {
+        return true;
+    };
+    match (fs.symlink_metadata(path), fs.metadata(parent)) {
+        (Ok(dir), Ok(parent)) => dir.dev != parent.dev,
+        _ => true,
+    }
+}
+
+/// 대상 디렉토리 아래의 폴더를 모두 모읍니다. 심볼릭 링크는 따라가지 않습니다.
+fn collect_dirs(fs: &dyn FileSystem, root: &Path, relative: &Path, dirs: &mut Vec<PathBuf>) {
+    let Ok(listing) = fs.read_dir(&root.join(relative)) else {
+        return;
+    };
+    for entry in listing {
+        if entry.kind == EntryKind::Dir {
+            let dir = relative.join(entry.name);
+            collect_dirs(fs, root, &dir, dirs);
+            dirs.push(dir);
         }
     }
+}
+
+/// 정리할 때 살펴볼 폴더를 안쪽 폴더부터 반환합니다. 거부 목록에 맞는 폴더는 뺍니다.
+pub(crate) fn cleanup_candidates(
+    fs: &dyn FileSystem,
+    root: &Path,
+    moves: &[MoveOperation],
+    rules: &CleanupRules,
+) -> Vec<PathBuf> {
+    let mut candidates: BTreeSet<PathBuf> = moves
+        .iter()
+        .flat_map(|op| op.from.ancestors().skip(1))
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .map(Path::to_path_buf)
+        .collect();
+    if !rules.allow.is_empty() {
+        let mut dirs = Vec::new();
+        collect_dirs(fs, root, Path::new(""), &mut dirs);
+        candidates.extend(dirs.into_iter().filter(|dir| rules.allows(dir)));
+    }
+
+    let mut ordered: Vec<PathBuf> = candidates
+        .into_iter()
+        .filter(|dir| !rules.denies(dir))
+        .collect();
+    ordered.sort_by_key(|dir| Reverse(dir.components().count()));
+    ordered
+}
+
+/// 이번 이동으로 비게 된 폴더를 삭제합니다.
+///
+/// 이동한 파일의 원래 상위 폴더들과 [`CleanupRules::allow`]에 맞는 폴더만 살펴보며,
+/// 처음부터 비어 있던 폴더나 관계없는 폴더는 건드리지 않습니다.
+/// [`CleanupRules::deny`]에 맞는 폴더, 심볼릭 링크, 다른 파일 시스템이 연결된
+/// 폴더는 삭제하지 않습니다. 안쪽 폴더부터 삭제하므로 하위 폴더가 지워져 비게 된
+/// 상위 폴더도 함께 삭제됩니다.
+///
+/// 폴더를 삭제할 때마다 [`Event::DirRemoved`]를 보고합니다.
+///
+/// # Errors
+///
+/// 폴더 삭제에 실패하면 에러를 반환합니다.
+pub fn cleanup_affected_dirs(
+    root: &Path,
+    moves: &[MoveOperation],
+    rules: &CleanupRules,
+    observer: &mut dyn Observer,
+) -> Result<(), Error> {
+    cleanup_affected_dirs_in(&RealFs, root, moves, rules, observer)
+}
 
-    println!("\n완료: {} 개 파일 이동됨", moves.len());
+/// 주어진 파일 시스템에서 [`cleanup_affected_dirs`]와 같이 빈 폴더를 삭제합니다.
+///
+/// # Errors
+///
+/// 폴더 삭제에 실패하면 에러를 반환합니다.
+pub fn cleanup_affected_dirs_in(
+    fs: &dyn FileSystem,
+    root: &Path,
+    moves: &[MoveOperation],
+    rules: &CleanupRules,
+    observer: &mut dyn Observer,
+) -> Result<(), Error> {
+    for dir in cleanup_candidates(fs, root, moves, rules) {
+        let path = root.join(&dir);
+        if !fs.symlink_metadata(&path).is_ok_and(|m| m.is_dir()) || is_mount_point(fs, &path) {
+            continue;
+        }
+        let is_empty = fs.read_dir(&path).is_ok_and(|entries| entries.is_empty());
+        if is_empty {
+            fs.remove_dir(&path)
+                .map_err(|source| Error::from_io(path, source))?;
+            observer.on_event(&Event::DirRemoved { path: dir });
+        }
+    }
     Ok(())
 }
 
-/// 빈 디렉토리를 재귀적으로 삭제합니다.
+/// 대상 디렉토리 아래의 빈 디렉토리를 모두 재귀적으로 삭제합니다.
+///
+/// 이번 실행과 관계없는 폴더까지 지우므로, 보통은 [`cleanup_affected_dirs`]를
+/// 사용하세요. 디렉토리를 가리키는 심볼릭 링크는 따라가지 않습니다.
+///
+/// 폴더를 삭제할 때마다 [`Event::DirRemoved`]를 보고합니다.
+///
+/// # Errors
+///
+/// 디렉토리 읽기나 삭제에 실패하면 에러를 반환합니다.
+pub fn cleanup_empty_dirs(root: &Path, observer: &mut dyn Observer) -> Result<(), Error> {
+    cleanup_empty_dirs_in(&RealFs, root, observer)
+}
+
+/// 주어진 파일 시스템에서 [`cleanup_empty_dirs`]와 같이 빈 폴더를 모두 삭제합니다.
 ///
 /// # Errors
 ///
 /// 디렉토리 읽기나 삭제에 실패하면 에러를 반환합니다.
-pub fn cleanup_empty_dirs(root: &Path, verbose: bool) -> Result<()> {
-    cleanup_empty_dirs_recursive(root, root, verbose)
+pub fn cleanup_empty_dirs_in(
+    fs: &dyn FileSystem,
+    root: &Path,
+    observer: &mut dyn Observer,
+) -> Result<(), Error> {
+    cleanup_empty_dirs_recursive(fs, root, root, observer)
 }
 
-fn cleanup_empty_dirs_recursive(root: &Path, current: &Path, verbose: bool) -> Result<()> {
-    if !current.is_dir() {
+fn cleanup_empty_dirs_recursive(
+    fs: &dyn FileSystem,
+    root: &Path,
+    current: &Path,
+    observer: &mut dyn Observer,
+) -> Result<(), Error> {
+    if !fs.metadata(current).is_ok_and(|m| m.is_dir()) {
         return Ok(());
     }
 
-    let entries: Vec<_> = fs::read_dir(current)
-        .with_context(|| format!("디렉토리 읽기 실패: {}", current.display()))?
-        .filter_map(Result::ok)
-        .collect();
+    let entries = fs
+        .read_dir(current)
+        .map_err(|source| Error::from_io(current.to_path_buf(), source))?;
 
-    for entry in &entries {
-        let path = entry.path();
-        if path.is_dir() {
-            cleanup_empty_dirs_recursive(root, &path, verbose)?;
+    for entry in entries {
+        if entry.kind == EntryKind::Dir {
+            cleanup_empty_dirs_recursive(fs, root, &current.join(entry.name), observer)?;
         }
     }
 
     if current != root {
-        let is_empty = fs::read_dir(current)
-            .map(|mut entries| entries.next().is_none())
-            .unwrap_or(false);
+        let is_empty = fs.read_dir(current).is_ok_and(|entries| entries.is_empty());
 
         if is_empty {
-            fs::remove_dir(current)
-                .with_context(|| format!("빈 폴더 삭제 실패: {}", current.display()))?;
-            if verbose {
-                println!("  [삭제] 빈 폴더: {}", current.display());
-            }
+            fs.remove_dir(current)
+                .map_err(|source| Error::from_io(current.to_path_buf(), source))?;
+            observer.on_event(&Event::DirRemoved {
+                path: current.strip_prefix(root).unwrap_or(current).to_path_buf(),
+            });
         }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn mv(from: &str, to: &str) -> MoveOperation {
+        MoveOperation::new(PathBuf::from(from), PathBuf::from(to))
+    }
+
+    fn renames(steps: &[Step]) -> Vec<(PathBuf, PathBuf)> {
+        steps
+            .iter()
+            .map(|s| (s.from.clone(), s.to.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn schedule_vacates_destination_first() {
+        let moves = vec![
+            mv("backup/1001.cpp", "1001.cpp"),
+            mv("1001.cpp", "1001_conflict1.cpp"),
+        ];
+
+        let steps = schedule(&moves);
+
+        assert_eq!(
+            renames(&steps),
+            vec![
+                (
+                    PathBuf::from("1001.cpp"),
+                    PathBuf::from("1001_conflict1.cpp")
+                ),
+                (PathBuf::from("backup/1001.cpp"), PathBuf::from("1001.cpp")),
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn relative_path_walks_up_to_common_ancestor() {
+        assert_eq!(
+            relative_path(Path::new("/r/data/1001.cpp"), Path::new("/r/1000")),
+            PathBuf::from("../data/1001.cpp")
+        );
+        assert_eq!(
+            relative_path(Path::new("/r/1001.cpp"), Path::new("/r")),
+            PathBuf::from("1001.cpp")
+        );
+        assert_eq!(
+            normalize_lexically(Path::new("/r/old/../data/./1001.cpp")),
+            PathBuf::from("/r/data/1001.cpp")
+        );
+    }
+
+    #[test]
+    fn schedule_breaks_swap_with_temporary_name() {
+        let moves = vec![mv("a/1.cpp", "b/1.cpp"), mv("b/1.cpp", "a/1.cpp")];
+
+        let steps = schedule(&moves);
+
+        assert_eq!(
+            renames(&steps),
+            vec![
+                (
+                    PathBuf::from("a/1.cpp"),
+                    PathBuf::from("a/.1.cpp.ps-organizer-tmp1")
+                ),
+                (PathBuf::from("b/1.cpp"), PathBuf::from("a/1.cpp")),
+                (
+                    PathBuf::from("a/.1.cpp.ps-organizer-tmp1"),
+                    PathBuf::from("b/1.cpp")
+                ),
+            ]
+        );
+        let completed: Vec<_> = steps.into_iter().filter_map(|s| s.completes).collect();
+        assert_eq!(completed.len(), 2);
+        assert!(completed.contains(&moves[0]) && completed.contains(&moves[1]));
+    }
+
+    fn move_and_clean_up(
+        root: &Path,
+        files: &[&str],
+        moves: &[MoveOperation],
+        rules: &CleanupRules,
+    ) -> Vec<PathBuf> {
+        for file in files {
+            let path = root.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+        execute_moves(root, moves, &mut |_: &Event| {}).unwrap();
+        let mut removed = Vec::new();
+        cleanup_affected_dirs(root, moves, rules, &mut |event: &Event| {
+            if let Event::DirRemoved { path } = event {
+                removed.push(path.clone());
+            }
+        })
+        .unwrap();
+        removed
+    }
+
+    #[test]
+    fn unreadable_metadata_counts_as_mount_point() {
+        use crate::vfs::{MemoryFs, Op};
+
+        let fs = MemoryFs::new();
+        fs.create_dir_all(Path::new("/root/a/b")).unwrap();
+        assert!(!is_mount_point(&fs, Path::new("/root/a")));
+        assert!(is_mount_point(&fs, Path::new("/")));
+
+        fs.inject(
+            Op::Metadata,
+            "/root/a",
+            std::io::ErrorKind::PermissionDenied,
+        );
+        assert!(is_mount_point(&fs, Path::new("/root/a")));
+        // 부모 폴더를 읽지 못해도 연결 지점으로 봅니다.
+        assert!(is_mount_point(&fs, Path::new("/root/a/b")));
+    }
+
+    #[test]
+    fn cleanup_only_removes_dirs_emptied_by_moves() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(root.join("keep/inner")).unwrap();
+        fs::create_dir_all(root.join(".git/refs")).unwrap();
+
+        let removed = move_and_clean_up(
+            root,
+            &["old/nested/1001.cpp", "busy/1002.cpp", "busy/notes.txt"],
+            &[
+                mv("old/nested/1001.cpp", "1001.cpp"),
+                mv("busy/1002.cpp", "1002.cpp"),
+            ],
+            &CleanupRules::default(),
+        );
+
+        assert_eq!(removed, [PathBuf::from("old/nested"), PathBuf::from("old")]);
+        assert!(root.join("keep/inner").is_dir());
+        assert!(root.join(".git/refs").is_dir());
+        assert!(root.join("busy").is_dir());
+    }
+
+    #[test]
+    fn cleanup_rules_allow_and_deny() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(root.join("scratch/a")).unwrap();
+        fs::create_dir_all(root.join("other")).unwrap();
+        let rules = CleanupRules {
+            allow: vec!["scratch".to_string()],
+            deny: vec!["archive/2023".to_string()],
+        };
+
+        let removed = move_and_clean_up(
+            root,
+            &["archive/2023/1001.cpp", "archive/2024/1002.cpp"],
+            &[
+                mv("archive/2023/1001.cpp", "1001.cpp"),
+                mv("archive/2024/1002.cpp", "1002.cpp"),
+            ],
+            &rules,
+        );
+
+        assert_eq!(
+            removed,
+            [
+                PathBuf::from("archive/2024"),
+                PathBuf::from("scratch/a"),
+                PathBuf::from("scratch"),
+            ]
+        );
+        assert!(root.join("archive/2023").is_dir());
+        assert!(root.join("other").is_dir());
+    }
+}
@@ -0,0 +1,211 @@
+//! 정리된 배치 그대로 풀이 내보내기.
+//!
+//! [`Organizer::plan_export`]가 스캔한 풀이 중 조건에 맞는 파일이 정리 후 놓일
+//! 경로를 계산하고, [`Organizer::export`]가 그 경로로 압축 파일을 만듭니다. 작업
+//! 트리는 바꾸지 않습니다.
+//!
+//! [`Organizer::plan_export`]: crate::organizer::Organizer::plan_export
+//! [`Organizer::export`]: crate::organizer::Organizer::export
+
+use crate::archive::ArchiveFile;
+use crate::locate::ProblemRange;
+use crate::metadata::{MetadataStore, Status};
+use crate::types::{Error, FileEntry, MoveOperation};
+use crate::vfs::FileSystem;
+use std::io::Read;
+use std::path::Path;
+
+/// 내보낼 풀이를 고르는 조건.
+///
+/// 지정한 조건을 모두 만족하는 파일만 내보냅니다. 목록으로 받는 조건은 값 중 하나와
+/// 맞으면 만족하며, 비어 있으면 따지지 않습니다. 메타데이터 조건을 지정하면
+/// 메타데이터가 없는 파일은 제외합니다.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExportFilter {
+    /// 문제 번호 범위. 지정하면 번호가 없는 파일은 제외합니다.
+    pub range: Option<ProblemRange>,
+    /// 정식 확장자 (예: `cpp`, `py`).
+    pub languages: Vec<String>,
+    /// 태그. 대소문자를 구분하지 않습니다.
+    pub tags: Vec<String>,
+    /// 난이도 (예: `gold3`). 대소문자를 구분하지 않습니다.
+    pub tiers: Vec<String>,
+    /// 풀이 상태.
+    pub statuses: Vec<Status>,
+}
+
+impl ExportFilter {
+    /// 파일이 조건을 모두 만족하는지 확인합니다.
+    pub fn matches(&self, entry: &FileEntry, metadata: &MetadataStore) -> bool {
+        if let Some(range) = &self.range
+            && !entry.problem_number.is_some_and(|n| range.contains(n))
+        {
+            return false;
+        }
+        if !self.languages.is_empty()
+            && !self.languages.iter().any(|lang| {
+                lang.trim_start_matches('.')
+                    .eq_ignore_ascii_case(&entry.extension)
+            })
+        {
+            return false;
+        }
+        if self.tags.is_empty() && self.tiers.is_empty() && self.statuses.is_empty() {
+            return true;
+        }
+
+        let Some(meta) = entry.problem_number.and_then(|n| metadata.get(n)) else {
+            return false;
+        };
+        let tag_ok = self.tags.is_empty()
+            || self
+                .tags
+                .iter()
+                .any(|tag| meta.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)));
+        let tier_ok = self.tiers.is_empty()
+            || meta
+                .tier
+                .as_ref()
+                .is_some_and(|tier| self.tiers.iter().any(|t| t.eq_ignore_ascii_case(tier)));
+        let status_ok = self.statuses.is_empty()
+            || meta
+                .status
+                .is_some_and(|status| self.statuses.contains(&status));
+        tag_ok && tier_ok && status_ok
+    }
+}
+
+/// 상대 경로를 압축 파일 안의 경로(`/`로 구분)로 바꿉니다.
+///
+/// # Errors
+///
+/// 경로가 UTF-8이 아니면 [`Error::InvalidUtf8Name`]을 반환합니다.
+pub fn archive_path(path: &Path) -> Result<String, Error> {
+    let parts = path
+        .components()
+        .map(|part| part.as_os_str().to_str())
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| Error::InvalidUtf8Name {
+            path: path.to_path_buf(),
+        })?;
+    Ok(parts.join("/"))
+}
+
+/// 내보낼 파일을 읽어 압축 파일 항목으로 만듭니다.
+///
+/// `files`의 각 항목은 대상 디렉토리 기준 현재 경로와 압축 파일 안의 경로입니다.
+///
+/// # Errors
+///
+/// 파일을 읽지 못하거나 압축 파일 안의 경로가 UTF-8이 아니면 에러를 반환합니다.
+pub fn read_files_in(
+    fs: &dyn FileSystem,
+    root: &Path,
+    files: &[MoveOperation],
+) -> Result<Vec<ArchiveFile>, Error> {
+    files
+        .iter()
+        .map(|op| {
+            let source = root.join(&op.from);
+            let mut contents = Vec::new();
+            fs.open(&source)
+                .and_then(|mut file| file.read_to_end(&mut contents))
+                .map_err(|e| Error::from_source(source, e))?;
+            Ok(ArchiveFile {
+                path: archive_path(&op.to)?,
+                contents,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vfs::MemoryFs;
+    use std::path::PathBuf;
+
+    fn entry(path: &str, number: Option<u32>) -> FileEntry {
+        FileEntry::new(PathBuf::from(path), number)
+    }
+
+    #[test]
+    fn filter_combines_range_language_and_metadata() {
+        let mut metadata = MetadataStore::default();
+        let meta = metadata.entry(1000);
+        meta.tier = Some("gold3".to_string());
+        meta.tags = vec!["DP".to_string()];
+        meta.status = Some(Status::Solved);
+
+        let cpp = entry("1000.cpp", Some(1000));
+        let py = entry("1000.py", Some(1000));
+        let other = entry("2000.cpp", Some(2000));
+        let unnumbered = entry("main.cpp", None);
+
+        assert!(ExportFilter::default().matches(&unnumbered, &metadata));
+
+        let range = ExportFilter {
+            range: Some("1000..2000".parse().unwrap()),
+            ..ExportFilter::default()
+        };
+        assert!(range.matches(&cpp, &metadata));
+        assert!(!range.matches(&other, &metadata));
+        assert!(!range.matches(&unnumbered, &metadata));
+
+        let lang = ExportFilter {
+            languages: vec![".CPP".to_string()],
+            ..ExportFilter::default()
+        };
+        assert!(lang.matches(&cpp, &metadata));
+        assert!(!lang.matches(&py, &metadata));
+
+        let meta = ExportFilter {
+            tags: vec!["dp".to_string(), "greedy".to_string()],
+            tiers: vec!["Gold3".to_string()],
+            statuses: vec![Status::Solved],
+            ..ExportFilter::default()
+        };
+        assert!(meta.matches(&cpp, &metadata));
+        assert!(!meta.matches(&other, &metadata));
+
+        let wrong_status = ExportFilter {
+            statuses: vec![Status::Todo],
+            ..ExportFilter::default()
+        };
+        assert!(!wrong_status.matches(&cpp, &metadata));
+    }
+
+    #[test]
+    fn read_files_uses_archive_paths() {
+        let fs = MemoryFs::new();
+        fs.create_dir_all(Path::new("/r/old")).unwrap();
+        fs.write(Path::new("/r/old/1000.cpp"), b"int x;\n").unwrap();
+
+        let files = read_files_in(
+            &fs,
+            Path::new("/r"),
+            &[MoveOperation::new(
+                PathBuf::from("old/1000.cpp"),
+                PathBuf::from("1000/1000.cpp"),
+            )],
+        )
+        .unwrap();
+        assert_eq!(
+            files,
+            vec![ArchiveFile {
+                path: "1000/1000.cpp".to_string(),
+                contents: b"int x;\n".to_vec(),
+            }]
+        );
+
+        let missing = read_files_in(
+            &fs,
+            Path::new("/r"),
+            &[MoveOperation::new(
+                PathBuf::from("gone.cpp"),
+                PathBuf::from("gone.cpp"),
+            )],
+        );
+        assert!(matches!(missing, Err(Error::SourceVanished { .. })));
+    }
+}
@@ -0,0 +1,99 @@
+//! 확장자 정규화.
+//!
+//! Windows에서 넘어온 `1010.CPP`, 다른 관례를 쓰는 `1010.cxx` 같은 파일도 같은
+//! 언어로 인식하도록, 대소문자를 무시하고 별칭을 정식 확장자로 바꿉니다.
+
+use std::collections::BTreeMap;
+
+/// 기본 별칭 (별칭 → 정식 확장자).
+const DEFAULT_ALIASES: [(&str, &str); 4] =
+    [("cc", "cpp"), ("cxx", "cpp"), ("c++", "cpp"), ("py3", "py")];
+
+/// 확장자 인식 규칙.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtensionPolicy {
+    /// 대소문자를 무시할지 여부.
+    pub case_insensitive: bool,
+    /// 별칭에서 정식 확장자로의 매핑 (점 제외).
+    pub aliases: BTreeMap<String, String>,
+    /// 이동할 때 파일 확장자를 정식 확장자로 바꿀지 여부.
+    pub normalize_on_move: bool,
+}
+
+impl Default for ExtensionPolicy {
+    fn default() -> Self {
+        Self {
+            case_insensitive: true,
+            aliases: DEFAULT_ALIASES
+                .iter()
+                .map(|(alias, ext)| ((*alias).to_string(), (*ext).to_string()))
+                .collect(),
+            normalize_on_move: false,
+        }
+    }
+}
+
+impl ExtensionPolicy {
+    /// 대소문자를 구분하고 별칭을 쓰지 않는 엄격한 규칙 (이전 동작).
+    pub fn strict() -> Self {
+        Self {
+            case_insensitive: false,
+            aliases: BTreeMap::new(),
+            normalize_on_move: false,
+        }
+    }
+
+    /// 확장자를 정식 확장자로 바꿉니다.
+    ///
+    /// 결과가 `allowed`에 없으면 `None`을 반환합니다.
+    pub fn canonical(&self, ext: &str, allowed: &[impl AsRef<str>]) -> Option<String> {
+        let ext = if self.case_insensitive {
+            ext.to_ascii_lowercase()
+        } else {
+            ext.to_string()
+        };
+        let ext = self.aliases.get(&ext).cloned().unwrap_or(ext);
+
+        allowed
+            .iter()
+            .any(|allowed| allowed.as_ref() == ext)
+            .then_some(ext)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALLOWED: [&str; 3] = ["cpp", "c", "py"];
+
+    #[test]
+    fn default_ignores_case() {
+        let policy = ExtensionPolicy::default();
+        assert_eq!(policy.canonical("CPP", &ALLOWED), Some("cpp".to_string()));
+        assert_eq!(policy.canonical("Py", &ALLOWED), Some("py".to_string()));
+    }
+
+    #[test]
+    fn default_resolves_aliases() {
+        let policy = ExtensionPolicy::default();
+        assert_eq!(policy.canonical("cxx", &ALLOWED), Some("cpp".to_string()));
+        assert_eq!(policy.canonical("C++", &ALLOWED), Some("cpp".to_string()));
+        assert_eq!(policy.canonical("py3", &ALLOWED), Some("py".to_string()));
+    }
+
+    #[test]
+    fn alias_must_map_to_allowed_extension() {
+        let policy = ExtensionPolicy::default();
+        assert_eq!(policy.canonical("cc", &["py"]), None);
+        assert_eq!(policy.canonical("java", &ALLOWED), None);
+    }
+
+    #[test]
+    fn strict_keeps_old_behavior() {
+        let policy = ExtensionPolicy::strict();
+        assert_eq!(policy.canonical("cpp", &ALLOWED), Some("cpp".to_string()));
+        assert_eq!(policy.canonical("CPP", &ALLOWED), None);
+        assert_eq!(policy.canonical("cxx", &ALLOWED), None);
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! 문제 번호 분포를 분석하여 적절한 폴더 구조를 계산합니다.
 
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 const fn find_largest_unit(max_num: u32) -> u32 {
     if max_num == 0 {
@@ -20,8 +20,8 @@ const fn next_unit(current_unit: u32, threshold: u32) -> u32 {
     if next <= threshold { threshold } else { next }
 }
 
-fn group_by_unit(numbers: &[u32], unit: u32) -> HashMap<u32, Vec<u32>> {
-    let mut groups: HashMap<u32, Vec<u32>> = HashMap::new();
+fn group_by_unit(numbers: &[u32], unit: u32) -> BTreeMap<u32, Vec<u32>> {
+    let mut groups: BTreeMap<u32, Vec<u32>> = BTreeMap::new();
     for &num in numbers {
         let group_start = (num / unit) * unit;
         groups.entry(group_start).or_default().push(num);
@@ -50,11 +50,14 @@ fn format_folder_name(group_start: u32, width: usize) -> String {
 ///
 /// `threshold` 이하의 파일 수는 그룹핑하지 않고 평탄하게 유지합니다.
 /// 파일이 많으면 10, 100, 1000 등의 단위로 자동 그룹핑합니다.
+///
+/// 파일이 많은 구간부터 폴더로 나누며, 파일 수가 같으면 시작 번호가 작은 구간을
+/// 먼저 나눕니다. 같은 번호 목록에는 항상 같은 결과를 돌려줍니다.
 pub fn compute_structure(
     numbers: &[u32],
     threshold: usize,
     current_path: &str,
-) -> HashMap<u32, String> {
+) -> BTreeMap<u32, String> {
     if numbers.len() <= threshold {
         return numbers
             .iter()
@@ -76,7 +79,7 @@ fn compute_structure_with_unit(
     current_path: &str,
     unit: u32,
     threshold_u32: u32,
-) -> HashMap<u32, String> {
+) -> BTreeMap<u32, String> {
     if numbers.len() <= threshold {
         return numbers
             .iter()
@@ -86,8 +89,9 @@ fn compute_structure_with_unit(
 
     let groups = group_by_unit(numbers, unit);
 
+    // 안정 정렬이므로 파일 수가 같은 구간은 시작 번호 순서를 유지합니다.
     let mut sorted_groups: Vec<_> = groups.into_iter().collect();
-    sorted_groups.sort_by(|a, b| b.1.len().cmp(&a.1.len()));
+    sorted_groups.sort_by_key(|group| std::cmp::Reverse(group.1.len()));
 
     let mut remaining = numbers.len();
     let mut groups_to_split: Vec<u32> = Vec::new();
@@ -107,7 +111,7 @@ fn compute_structure_with_unit(
         .unwrap_or(0);
     let width = digit_count(max_end);
 
-    let mut result = HashMap::new();
+    let mut result = BTreeMap::new();
 
     for (group_start, group_nums) in sorted_groups {
         if groups_to_split.contains(&group_start) {
@@ -267,4 +271,17 @@ mod tests {
 
         assert_eq!(root_1000.len(), root_30000.len());
     }
+
+    #[test]
+    fn equal_groups_split_from_smallest_start() {
+        let mut numbers: Vec<u32> = (2000..2005).collect();
+        numbers.extend(1000..1005);
+        numbers.extend(3000..3005);
+
+        let result = compute_structure(&numbers, 10, "");
+
+        assert_eq!(result[&1000], "1000");
+        assert_eq!(result[&2000], "");
+        assert_eq!(result[&3000], "");
+    }
 }
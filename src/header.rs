@@ -0,0 +1,221 @@
+//! 소스 파일 머리 주석에서 문제 번호 추출.
+//!
+//! `// https://www.acmicpc.net/problem/1000`, `# BOJ 1000 A+B`처럼 파일 앞부분에
+//! 적힌 문제 주소나 표식에서 문제 번호와 출처를 찾습니다.
+
+use crate::vfs::{FileSystem, RealFs};
+use serde::Serialize;
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+/// 문제 출처.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Platform {
+    /// 백준 온라인 저지 (`acmicpc.net`, `boj.kr`).
+    Boj,
+    /// 프로그래머스 (`programmers.co.kr`).
+    Programmers,
+}
+
+impl Platform {
+    /// 문제 번호의 문제 주소.
+    pub fn problem_url(self, number: u32) -> String {
+        match self {
+            Self::Boj => format!("https://www.acmicpc.net/problem/{number}"),
+            Self::Programmers => {
+                format!("https://school.programmers.co.kr/learn/courses/30/lessons/{number}")
+            }
+        }
+    }
+}
+
+/// 머리 주석에서 찾은 문제 정보.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeaderInfo {
+    /// 문제 출처.
+    pub platform: Platform,
+    /// 문제 번호.
+    pub problem_number: u32,
+}
+
+/// 파일명과 머리 주석의 문제 번호가 다른 파일.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeaderMismatch {
+    /// 루트 디렉토리 기준 상대 경로.
+    pub path: PathBuf,
+    /// 파일명에서 추출한 번호 (정리에 쓰는 번호).
+    pub filename_number: u32,
+    /// 머리 주석에서 추출한 번호.
+    pub header_number: u32,
+}
+
+/// 문제 주소 앞부분과 출처. 주소 바로 뒤의 숫자를 문제 번호로 봅니다.
+const URL_PREFIXES: [(&str, Platform); 4] = [
+    ("acmicpc.net/problem/", Platform::Boj),
+    ("boj.kr/", Platform::Boj),
+    (
+        "programmers.co.kr/learn/courses/30/lessons/",
+        Platform::Programmers,
+    ),
+    ("programmers.co.kr/learn/challenges/", Platform::Programmers),
+];
+
+/// 문제 번호 앞에 붙는 표식 (예: `BOJ 1000`, `백준 1000번`).
+const TAGS: [(&str, Platform); 2] = [("boj", Platform::Boj), ("백준", Platform::Boj)];
+
+/// 표식과 번호 사이에 올 수 있는 문자.
+const TAG_SEPARATORS: [char; 5] = [' ', '#', '-', ':', '_'];
+
+fn leading_number(text: &str) -> Option<u32> {
+    let end = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    text[..end].parse().ok()
+}
+
+fn parse_url(line: &str) -> Option<HeaderInfo> {
+    URL_PREFIXES.iter().find_map(|(prefix, platform)| {
+        let start = line.find(prefix)? + prefix.len();
+        Some(HeaderInfo {
+            platform: *platform,
+            problem_number: leading_number(&line[start..])?,
+        })
+    })
+}
+
+fn parse_tag(line: &str) -> Option<HeaderInfo> {
+    TAGS.iter().find_map(|(tag, platform)| {
+        line.match_indices(tag).find_map(|(index, _)| {
+            let at_word_start = line[..index]
+                .chars()
+                .next_back()
+                .is_none_or(|c| !c.is_alphanumeric());
+            if !at_word_start {
+                return None;
+            }
+            let rest = line[index + tag.len()..].trim_start_matches(TAG_SEPARATORS);
+            Some(HeaderInfo {
+                platform: *platform,
+                problem_number: leading_number(rest)?,
+            })
+        })
+    })
+}
+
+/// 여러 줄의 텍스트에서 처음 나오는 문제 정보를 찾습니다.
+///
+/// 줄마다 문제 주소를 먼저 찾고, 없으면 `BOJ`/`백준` 표식을 찾습니다.
+/// 대소문자는 구분하지 않습니다.
+pub fn parse_header(text: &str) -> Option<HeaderInfo> {
+    text.lines().find_map(|line| {
+        let line = line.to_lowercase();
+        parse_url(&line).or_else(|| parse_tag(&line))
+    })
+}
+
+/// 파일의 처음 `lines`줄에서 문제 정보를 찾습니다.
+///
+/// UTF-8이 아닌 내용은 대체 문자로 바꿔 읽습니다.
+///
+/// # Errors
+///
+/// 파일을 열거나 읽을 수 없으면 에러를 반환합니다.
+pub fn read_header(path: &Path, lines: usize) -> io::Result<Option<HeaderInfo>> {
+    read_header_in(&RealFs, path, lines)
+}
+
+/// 주어진 파일 시스템에서 파일의 처음 `lines`줄을 읽어 문제 정보를 찾습니다.
+///
+/// # Errors
+///
+/// 파일을 열거나 읽을 수 없으면 에러를 반환합니다.
+pub fn read_header_in(
+    fs: &dyn FileSystem,
+    path: &Path,
+    lines: usize,
+) -> io::Result<Option<HeaderInfo>> {
+    let mut reader = BufReader::new(fs.open(path)?);
+    let mut text = String::new();
+    let mut line = Vec::new();
+    for _ in 0..lines {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            break;
+        }
+        text.push_str(&String::from_utf8_lossy(&line));
+    }
+    Ok(parse_header(&text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const fn boj(problem_number: u32) -> HeaderInfo {
+        HeaderInfo {
+            platform: Platform::Boj,
+            problem_number,
+        }
+    }
+
+    #[test]
+    fn parse_urls() {
+        assert_eq!(
+            parse_header("// https://www.acmicpc.net/problem/1000\n"),
+            Some(boj(1000))
+        );
+        assert_eq!(parse_header("# http://boj.kr/2557"), Some(boj(2557)));
+        assert_eq!(
+            parse_header("// https://school.programmers.co.kr/learn/courses/30/lessons/42576"),
+            Some(HeaderInfo {
+                platform: Platform::Programmers,
+                problem_number: 42576,
+            })
+        );
+    }
+
+    #[test]
+    fn parse_tags() {
+        assert_eq!(parse_header("# BOJ 1000 A+B"), Some(boj(1000)));
+        assert_eq!(parse_header("/* boj#1001 */"), Some(boj(1001)));
+        assert_eq!(parse_header("// 백준 1008번"), Some(boj(1008)));
+    }
+
+    #[test]
+    fn tag_needs_word_boundary_and_number() {
+        assert_eq!(parse_header("int xboj 1000;"), None);
+        assert_eq!(parse_header("// BOJ practice"), None);
+        assert_eq!(parse_header("int boj = 1000;"), None);
+    }
+
+    #[test]
+    fn first_match_wins() {
+        let text = "#include <cstdio>\n// BOJ 1000\n// https://www.acmicpc.net/problem/2000\n";
+        assert_eq!(parse_header(text), Some(boj(1000)));
+    }
+
+    #[test]
+    fn problem_url_round_trips() {
+        for platform in [Platform::Boj, Platform::Programmers] {
+            let url = platform.problem_url(1000);
+            assert_eq!(
+                parse_header(&url),
+                Some(HeaderInfo {
+                    platform,
+                    problem_number: 1000,
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn read_header_limits_lines() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("main.cpp");
+        std::fs::write(&path, "#include <cstdio>\n\n// BOJ 1000\n").unwrap();
+
+        assert_eq!(read_header(&path, 2).unwrap(), None);
+        assert_eq!(read_header(&path, 3).unwrap(), Some(boj(1000)));
+    }
+}
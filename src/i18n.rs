@@ -0,0 +1,559 @@
+//! 사용자 메시지 다국어 지원.
+//!
+//! 모든 출력 문구와 에러 문맥은 키로 메시지 카탈로그에서 찾습니다. 언어는
+//! `--lang` 옵션으로 지정하거나 `LC_ALL`, `LC_MESSAGES`, `LANG` 환경 변수에서
+//! 감지합니다. 메시지 안의 `{name}` 자리표시자는 [`t!`](crate::t) 매크로의
+//! 인자로 채웁니다.
+
+use std::env;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// 출력 언어.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Lang {
+    /// 한국어.
+    Ko,
+    /// 영어.
+    En,
+}
+
+impl Lang {
+    /// 모든 지원 언어.
+    pub const ALL: [Self; 2] = [Self::Ko, Self::En];
+
+    /// 로캘 문자열(예: `ko_KR.UTF-8`, `en_US`)에서 언어를 결정합니다.
+    ///
+    /// 빈 문자열이면 `None`, 한국어가 아닌 로캘은 영어로 취급합니다.
+    pub fn from_locale(locale: &str) -> Option<Self> {
+        if locale.is_empty() {
+            None
+        } else if locale.starts_with("ko") {
+            Some(Self::Ko)
+        } else {
+            Some(Self::En)
+        }
+    }
+
+    /// `LC_ALL`, `LC_MESSAGES`, `LANG` 순서로 환경 변수를 확인해 언어를 감지합니다.
+    ///
+    /// 아무것도 설정되어 있지 않으면 한국어를 사용합니다.
+    pub fn detect() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find_map(|value| Self::from_locale(&value))
+            .unwrap_or(Self::Ko)
+    }
+
+    const fn catalog(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::Ko => KO,
+            Self::En => EN,
+        }
+    }
+}
+
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+/// 출력 언어를 지정합니다.
+pub fn set_lang(lang: Lang) {
+    let value = match lang {
+        Lang::Ko => 1,
+        Lang::En => 2,
+    };
+    CURRENT.store(value, Ordering::Relaxed);
+}
+
+/// 현재 출력 언어를 반환합니다. 지정된 적이 없으면 환경 변수에서 감지합니다.
+pub fn current() -> Lang {
+    match CURRENT.load(Ordering::Relaxed) {
+        1 => Lang::Ko,
+        2 => Lang::En,
+        _ => {
+            let lang = Lang::detect();
+            set_lang(lang);
+            lang
+        }
+    }
+}
+
+/// 지정한 언어의 카탈로그에서 메시지를 찾습니다.
+///
+/// 키가 없으면 키 자체를 반환합니다.
+pub fn lookup(lang: Lang, key: &'static str) -> &'static str {
+    lang.catalog()
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map_or(key, |(_, text)| text)
+}
+
+/// 현재 언어로 메시지를 찾습니다.
+pub fn tr(key: &'static str) -> &'static str {
+    lookup(current(), key)
+}
+
+/// 현재 언어로 메시지를 찾고 `{name}` 자리표시자를 채웁니다.
+pub fn tr_args(key: &'static str, args: &[(&str, &dyn Display)]) -> String {
+    fill(tr(key), args)
+}
+
+fn fill(template: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut text = template.to_string();
+    for (name, value) in args {
+        text = text.replace(&format!("{{{name}}}"), &value.to_string());
+    }
+    text
+}
+
+/// 현재 언어의 메시지를 `String`으로 만듭니다.
+///
+/// ```
+/// use ps_organizer::t;
+///
+/// ps_organizer::i18n::set_lang(ps_organizer::i18n::Lang::En);
+/// assert_eq!(t!("main.nothing_to_do"), "Nothing to organize.");
+/// assert_eq!(t!("exec.done", count = 3), "Done: 3 file(s) moved");
+/// ```
+#[macro_export]
+macro_rules! t {
+    ($key:literal) => {
+        $crate::i18n::tr($key).to_string()
+    };
+    ($key:literal, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::tr_args(
+            $key,
+            &[$((stringify!($name), &$value as &dyn ::std::fmt::Display)),+],
+        )
+    };
+}
+
+const KO: &[(&str, &str)] = &[
+    ("main.target_dir", "대상 디렉토리: {path}"),
+    ("main.threshold", "임계값: {threshold}"),
+    ("main.dry_run", "Dry-run: {enabled}"),
+    ("main.scanned", "스캔된 파일: {count} 개"),
+    ("main.nothing_to_do", "정리할 파일이 없습니다."),
+    ("main.aborted", "취소되었습니다."),
+    (
+        "plan.empty",
+        "변경 사항 없음. 모든 파일이 이미 올바른 위치에 있습니다.",
+    ),
+    ("plan.header", "=== 이동 계획 ({count} 개 파일) ==="),
+    ("plan.folders", "생성될 폴더:"),
+    ("exec.empty", "변경 사항 없음."),
+    ("exec.header", "=== 파일 이동 중 ({count} 개) ==="),
+    ("exec.dir_created", "  [폴더 생성] {path}"),
+    ("exec.moved", "  [이동] {from} -> {to}"),
+    ("exec.done", "완료: {count} 개 파일 이동됨"),
+    ("exec.dir_removed", "  [삭제] 빈 폴더: {path}"),
+    ("event.scan_found", "  [스캔] {path} (문제 {number})"),
+    ("event.scan_found_unnumbered", "  [스캔] {path} (번호 없음)"),
+    ("event.planned", "  [계획] {from} -> {to}"),
+    ("event.conflict", "  [충돌] {from} -> {to}"),
+    (
+        "event.header_mismatch",
+        "  [번호 불일치] {path}: 파일명 {filename}, 머리 주석 {header} (파일명 번호 사용)",
+    ),
+    ("error.invalid_root", "대상 디렉토리가 아닙니다: {path}"),
+    ("error.permission_denied", "권한이 없습니다: {path}"),
+    ("error.source_vanished", "원본 파일이 사라졌습니다: {path}"),
+    (
+        "error.destination_exists",
+        "목적지에 이미 파일이 있습니다: {path}",
+    ),
+    (
+        "error.cross_device",
+        "다른 장치로는 이동할 수 없습니다: {from} -> {to}",
+    ),
+    ("error.invalid_utf8_name", "UTF-8이 아닌 파일명: {path}"),
+    ("error.walk", "디렉토리 순회 실패: {path} ({reason})"),
+    ("error.io", "입출력 실패: {path} ({reason})"),
+    (
+        "error.outside_root",
+        "대상 디렉토리 밖을 가리키는 링크를 건너뜀: {path} → {target}",
+    ),
+    (
+        "error.invalid_metadata",
+        "메타데이터 파일 형식 오류: {path} ({reason})",
+    ),
+    ("main.error", "에러: {error}"),
+    ("main.warning", "경고: {error}"),
+    (
+        "review.help",
+        "명령어:\n  \
+         a                  모든 이동 승인 후 실행\n  \
+         r <번호>...        해당 이동 거부 (제자리에 유지)\n  \
+         e <번호> <경로>    해당 이동의 목적지 수정\n  \
+         o <폴더>|*         폴더 펼치기\n  \
+         c <폴더>|*         폴더 접기\n  \
+         l                  계획 다시 보기\n  \
+         q                  취소\n  \
+         ?                  도움말",
+    ),
+    ("review.hint", "'?'를 입력하면 명령어를 볼 수 있습니다."),
+    ("review.folder", "{folder} ({count} 개)"),
+    ("review.invalid_index", "잘못된 번호: {arg}"),
+    ("review.reject_usage", "거부할 이동 번호를 입력하세요."),
+    ("review.edit_usage", "사용법: e <번호> <경로>"),
+    (
+        "review.edit_outside",
+        "대상 디렉토리 안의 상대 경로만 지정할 수 있습니다.",
+    ),
+    (
+        "review.edit_taken",
+        "이미 다른 파일이 사용 중인 목적지입니다: {path}",
+    ),
+    ("review.unknown", "알 수 없는 명령어: {command}"),
+    ("tree.count", "({count} 개)"),
+    ("tree.count_changed", "({before} → {after} 개)"),
+    ("tree.unchanged", "({count} 개, 변경 없음)"),
+    (
+        "tree.summary",
+        "이동 {moved} 개 / 새 폴더 {new} 개 / 비는 폴더 {removed} 개 / 이름 변경 {renamed} 개",
+    ),
+    ("meta.saved", "{number}번 문제 메타데이터를 저장했습니다."),
+    ("meta.not_found", "{number}번 문제 메타데이터가 없습니다."),
+    (
+        "new.exists",
+        "{path} 파일이 이미 있습니다. 다음 이름을 쓰세요: {suggestion} (`--variant`)",
+    ),
+    ("new.unsupported_lang", "지원하지 않는 언어입니다: {lang}"),
+    ("find.not_found", "{range}번 문제의 파일이 없습니다."),
+    (
+        "path.range_too_large",
+        "범위가 너무 큽니다: {range} (최대 {max}개)",
+    ),
+    (
+        "event.scan_cached",
+        "스캔 캐시: 디렉토리 {reused}개 재사용, {read}개 다시 읽음",
+    ),
+    ("conflicts.none", "충돌 파일이 없습니다."),
+    (
+        "conflicts.header",
+        "[{index}/{count}] {path} (본 파일: {main})",
+    ),
+    (
+        "conflicts.no_main",
+        "본 파일 {main}이(가) 없습니다. 승격하면 이 파일이 본 파일이 됩니다.",
+    ),
+    ("conflicts.identical", "본 파일과 내용이 같습니다."),
+    (
+        "conflicts.prompt",
+        "유지(k) / 삭제(d) / 승격(p) / 종료(q) > ",
+    ),
+    (
+        "conflicts.help",
+        "k: 그대로 둠, d: 충돌 파일 삭제, p: 충돌 파일을 본 파일로 바꾸고 본 파일은 충돌 이름으로 바꿈, q: 종료",
+    ),
+    ("conflicts.unknown", "알 수 없는 선택: {choice}"),
+    ("conflicts.kept", "유지: {path}"),
+    ("conflicts.deleted", "삭제: {path}"),
+    ("conflicts.promoted", "승격: {path} -> {main}"),
+    ("exec.dirs_removed", "빈 폴더 {count} 개 삭제됨"),
+    ("simulate.ok", "검증 완료: 실행해도 실패할 작업이 없습니다."),
+    ("simulate.failure", "  [실패 예상] {error}"),
+    (
+        "simulate.failed",
+        "검증 실패: 작업 {count} 개가 실패할 것입니다.",
+    ),
+    ("event.imported", "  [가져옴] {from} -> {to}"),
+    ("event.duplicate", "  [중복] {path} = {existing}"),
+    (
+        "event.import_skipped",
+        "  [건너뜀] {path}: {to}에 다른 파일이 있음",
+    ),
+    (
+        "event.flatten_skipped",
+        "  [건너뜀] {path}: {to}에 다른 파일이 있어 제자리에 둠",
+    ),
+    ("import.header", "=== 가져오기 계획 ({count} 개) ==="),
+    ("import.empty", "가져올 파일 없음."),
+    ("import.done", "가져오기 완료: {count} 개 파일"),
+    (
+        "import.skipped",
+        "건너뜀: 중복 {duplicates} 개, 이름 충돌 {clashes} 개",
+    ),
+    ("export.entry", "  {from} -> {to}"),
+    ("export.empty", "내보낼 파일 없음."),
+    ("export.done", "{count} 개 파일을 {path}에 내보냄"),
+    (
+        "export.no_default_output",
+        "대상 디렉토리에 상위 디렉토리가 없음. -o로 저장할 파일을 지정하세요.",
+    ),
+    ("event.link_updated", "  [링크] {path} -> {target}"),
+    (
+        "main.cache_save_failed",
+        "경고: 스캔 캐시를 저장하지 못했습니다: {error}",
+    ),
+];
+
+const EN: &[(&str, &str)] = &[
+    ("main.target_dir", "Target directory: {path}"),
+    ("main.threshold", "Threshold: {threshold}"),
+    ("main.dry_run", "Dry-run: {enabled}"),
+    ("main.scanned", "Scanned files: {count}"),
+    ("main.nothing_to_do", "Nothing to organize."),
+    ("main.aborted", "Cancelled."),
+    (
+        "plan.empty",
+        "No changes. All files are already in the right place.",
+    ),
+    ("plan.header", "=== Move plan ({count} file(s)) ==="),
+    ("plan.folders", "Folders to create:"),
+    ("exec.empty", "No changes."),
+    ("exec.header", "=== Moving files ({count}) ==="),
+    ("exec.dir_created", "  [mkdir] {path}"),
+    ("exec.moved", "  [move] {from} -> {to}"),
+    ("exec.done", "Done: {count} file(s) moved"),
+    ("exec.dir_removed", "  [rmdir] empty folder: {path}"),
+    ("event.scan_found", "  [scan] {path} (problem {number})"),
+    ("event.scan_found_unnumbered", "  [scan] {path} (no number)"),
+    ("event.planned", "  [plan] {from} -> {to}"),
+    ("event.conflict", "  [conflict] {from} -> {to}"),
+    (
+        "event.header_mismatch",
+        "  [mismatch] {path}: filename {filename}, header {header} (using filename)",
+    ),
+    ("error.invalid_root", "not a directory: {path}"),
+    ("error.permission_denied", "permission denied: {path}"),
+    ("error.source_vanished", "source file vanished: {path}"),
+    (
+        "error.destination_exists",
+        "destination already exists: {path}",
+    ),
+    (
+        "error.cross_device",
+        "cannot move across devices: {from} -> {to}",
+    ),
+    (
+        "error.invalid_utf8_name",
+        "file name is not valid UTF-8: {path}",
+    ),
+    ("error.walk", "failed to walk directory: {path} ({reason})"),
+    ("error.io", "I/O error: {path} ({reason})"),
+    (
+        "error.outside_root",
+        "skipped link pointing outside the target directory: {path} -> {target}",
+    ),
+    (
+        "error.invalid_metadata",
+        "invalid metadata file: {path} ({reason})",
+    ),
+    ("main.error", "error: {error}"),
+    ("main.warning", "warning: {error}"),
+    (
+        "review.help",
+        "Commands:\n  \
+         a                  accept all moves and run\n  \
+         r <num>...         reject moves (keep files in place)\n  \
+         e <num> <path>     edit a move's destination\n  \
+         o <folder>|*       expand folder\n  \
+         c <folder>|*       collapse folder\n  \
+         l                  show the plan again\n  \
+         q                  cancel\n  \
+         ?                  help",
+    ),
+    ("review.hint", "Type '?' to list commands."),
+    ("review.folder", "{folder} ({count})"),
+    ("review.invalid_index", "Invalid number: {arg}"),
+    (
+        "review.reject_usage",
+        "Enter the numbers of the moves to reject.",
+    ),
+    ("review.edit_usage", "Usage: e <num> <path>"),
+    (
+        "review.edit_outside",
+        "Only relative paths inside the target directory are allowed.",
+    ),
+    (
+        "review.edit_taken",
+        "Destination is already used by another file: {path}",
+    ),
+    ("review.unknown", "Unknown command: {command}"),
+    ("tree.count", "({count})"),
+    ("tree.count_changed", "({before} → {after})"),
+    ("tree.unchanged", "({count}, unchanged)"),
+    (
+        "tree.summary",
+        "{moved} moved / {new} new folder(s) / {removed} emptied / {renamed} renamed",
+    ),
+    ("meta.saved", "Saved metadata for problem {number}."),
+    ("meta.not_found", "No metadata for problem {number}."),
+    (
+        "new.exists",
+        "{path} already exists. Use the next name instead: {suggestion} (`--variant`)",
+    ),
+    ("new.unsupported_lang", "Unsupported language: {lang}"),
+    ("find.not_found", "No files for problem {range}."),
+    (
+        "path.range_too_large",
+        "Range is too large: {range} (at most {max} problems)",
+    ),
+    (
+        "event.scan_cached",
+        "Scan cache: reused {reused} directories, re-read {read}",
+    ),
+    ("conflicts.none", "No conflict files found."),
+    (
+        "conflicts.header",
+        "[{index}/{count}] {path} (main file: {main})",
+    ),
+    (
+        "conflicts.no_main",
+        "Main file {main} does not exist. Promoting makes this file the main file.",
+    ),
+    ("conflicts.identical", "Identical to the main file."),
+    (
+        "conflicts.prompt",
+        "keep (k) / delete (d) / promote (p) / quit (q) > ",
+    ),
+    (
+        "conflicts.help",
+        "k: leave as is, d: delete the conflict file, p: make the conflict file the main file and give the main file the conflict name, q: quit",
+    ),
+    ("conflicts.unknown", "Unknown choice: {choice}"),
+    ("conflicts.kept", "Kept: {path}"),
+    ("conflicts.deleted", "Deleted: {path}"),
+    ("conflicts.promoted", "Promoted: {path} -> {main}"),
+    ("exec.dirs_removed", "Removed {count} empty folder(s)"),
+    ("simulate.ok", "Check passed: no operation would fail."),
+    ("simulate.failure", "  [would fail] {error}"),
+    (
+        "simulate.failed",
+        "Check failed: {count} operation(s) would fail.",
+    ),
+    ("event.imported", "  [import] {from} -> {to}"),
+    ("event.duplicate", "  [duplicate] {path} = {existing}"),
+    ("event.import_skipped", "  [skip] {path}: {to} is taken"),
+    (
+        "event.flatten_skipped",
+        "  [skip] {path}: {to} is taken, left in place",
+    ),
+    ("import.header", "=== Import plan ({count}) ==="),
+    ("import.empty", "Nothing to import."),
+    ("import.done", "Imported {count} file(s)"),
+    (
+        "import.skipped",
+        "Skipped: {duplicates} duplicate(s), {clashes} name clash(es)",
+    ),
+    ("export.entry", "  {from} -> {to}"),
+    ("export.empty", "Nothing to export."),
+    ("export.done", "Exported {count} file(s) to {path}"),
+    (
+        "export.no_default_output",
+        "The target directory has no parent. Choose the output file with -o.",
+    ),
+    ("event.link_updated", "  [link] {path} -> {target}"),
+    (
+        "main.cache_save_failed",
+        "warning: failed to save the scan cache: {error}",
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+    use std::fs;
+    use std::path::Path;
+
+    fn keys(lang: Lang) -> BTreeSet<&'static str> {
+        lang.catalog().iter().map(|(key, _)| *key).collect()
+    }
+
+    fn placeholders(text: &str) -> BTreeSet<&str> {
+        text.split('{')
+            .skip(1)
+            .filter_map(|rest| rest.split_once('}').map(|(name, _)| name))
+            .collect()
+    }
+
+    fn used_keys(dir: &Path, found: &mut BTreeSet<String>) {
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                used_keys(&path, found);
+            } else if path.extension().is_some_and(|ext| ext == "rs") {
+                let source = fs::read_to_string(&path).unwrap();
+                let pattern = "t!(\"";
+                for (index, _) in source.match_indices(pattern) {
+                    let standalone = source[..index]
+                        .chars()
+                        .next_back()
+                        .is_none_or(|c| !c.is_alphanumeric() && c != '_');
+                    let rest = &source[index + pattern.len()..];
+                    if let (true, Some((key, _))) = (standalone, rest.split_once('"')) {
+                        found.insert(key.to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn every_catalog_has_every_key() {
+        let all: BTreeSet<_> = Lang::ALL.iter().flat_map(|&lang| keys(lang)).collect();
+        for lang in Lang::ALL {
+            assert_eq!(keys(lang), all, "{lang:?} catalog is missing keys");
+        }
+    }
+
+    #[test]
+    fn catalogs_have_no_duplicate_keys() {
+        for lang in Lang::ALL {
+            assert_eq!(keys(lang).len(), lang.catalog().len(), "{lang:?}");
+        }
+    }
+
+    #[test]
+    fn every_used_key_exists() {
+        let mut used = BTreeSet::new();
+        used_keys(
+            &Path::new(env!("CARGO_MANIFEST_DIR")).join("src"),
+            &mut used,
+        );
+        assert!(!used.is_empty());
+
+        for lang in Lang::ALL {
+            for key in &used {
+                assert!(
+                    lang.catalog().iter().any(|(k, _)| k == key),
+                    "{lang:?} catalog is missing `{key}`"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn placeholders_match_across_catalogs() {
+        for (key, text) in KO {
+            assert_eq!(
+                placeholders(text),
+                placeholders(lookup(Lang::En, key)),
+                "placeholders differ for `{key}`"
+            );
+        }
+    }
+
+    #[test]
+    fn from_locale_parses_common_values() {
+        assert_eq!(Lang::from_locale("ko_KR.UTF-8"), Some(Lang::Ko));
+        assert_eq!(Lang::from_locale("en_US.UTF-8"), Some(Lang::En));
+        assert_eq!(Lang::from_locale("C"), Some(Lang::En));
+        assert_eq!(Lang::from_locale(""), None);
+    }
+
+    #[test]
+    fn fill_replaces_placeholders() {
+        assert_eq!(
+            fill("{from} -> {to}", &[("from", &"a"), ("to", &1)]),
+            "a -> 1"
+        );
+    }
+
+    #[test]
+    fn lookup_falls_back_to_key() {
+        assert_eq!(lookup(Lang::En, "no.such.key"), "no.such.key");
+    }
+}
@@ -0,0 +1,347 @@
+//! 다른 디렉토리의 풀이 가져오기.
+//!
+//! [`Organizer::plan_import`]가 원본 디렉토리의 파일이 대상 트리에서 놓일 자리를
+//! 정하고, [`Organizer::execute_import`]가 대상 트리를 다시 나눈 뒤 파일을
+//! 복사하거나 옮깁니다.
+//!
+//! [`Organizer::plan_import`]: crate::organizer::Organizer::plan_import
+//! [`Organizer::execute_import`]: crate::organizer::Organizer::execute_import
+
+use crate::events::{Event, Observer};
+use crate::executor::create_parent;
+use crate::organizer::Plan;
+use crate::planner::{conflict_base, resolve_conflict};
+use crate::types::{Error, FileEntry, MoveOperation};
+use crate::vfs::FileSystem;
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// 원본 파일을 가져오는 방식.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TransferMode {
+    /// 원본을 그대로 두고 복사합니다.
+    #[default]
+    Copy,
+    /// 원본에서 옮깁니다. 다른 장치로 옮길 때는 복사한 뒤 원본을 지웁니다.
+    Move,
+}
+
+/// 목적지 이름에 이미 다른 파일이 있을 때의 처리 방식.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ConflictPolicy {
+    /// 정리할 때와 같이 `_conflictN` 이름으로 가져옵니다.
+    #[default]
+    Rename,
+    /// 가져오지 않고 건너뜁니다.
+    Skip,
+}
+
+/// 내용이 같은 풀이가 이미 있을 때의 처리 방식.
+///
+/// 같은 문제 번호와 같은 언어의 파일(번호가 없으면 충돌 꼬리표를 뗀 이름이 같은
+/// 파일) 가운데 바이트 단위로 내용이 같은 것이 있으면 중복으로 봅니다.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum DuplicatePolicy {
+    /// 가져오지 않고 건너뜁니다.
+    #[default]
+    Skip,
+    /// 이름이 겹친 파일처럼 [`ConflictPolicy`]에 따라 가져옵니다.
+    Keep,
+}
+
+/// 가져오기 옵션.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportOptions {
+    /// 복사할지 옮길지 여부.
+    pub mode: TransferMode,
+    /// 이름이 겹칠 때의 처리 방식.
+    pub conflicts: ConflictPolicy,
+    /// 중복 파일의 처리 방식.
+    pub duplicates: DuplicatePolicy,
+}
+
+/// 가져오기 계획.
+///
+/// `transfers`, `renamed`, `skipped`, `duplicates`의 `from`은 원본 디렉토리 기준,
+/// `to`는 대상 디렉토리 기준 상대 경로입니다.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportPlan {
+    /// 정규화된 원본 디렉토리 경로.
+    pub source: PathBuf,
+    /// 가져오는 방식.
+    pub mode: TransferMode,
+    /// 가져올 파일 목록.
+    pub transfers: Vec<MoveOperation>,
+    /// `transfers` 가운데 이름이 겹쳐 `_conflictN` 이름을 받은 파일 목록.
+    pub renamed: Vec<MoveOperation>,
+    /// 이름이 겹쳐 건너뛴 파일 목록. `to`는 겹친 목적지입니다.
+    pub skipped: Vec<MoveOperation>,
+    /// 내용이 같은 파일이 있어 건너뛴 파일 목록. `to`는 기존 파일이며, 원본 안에서
+    /// 먼저 가져오기로 한 파일과 같으면 원본 디렉토리 기준 경로입니다.
+    pub duplicates: Vec<MoveOperation>,
+    /// 가져온 파일까지 세어 임계값을 넘는 폴더를 다시 나누는 대상 트리의 이동 계획.
+    pub rebalance: Plan,
+}
+
+/// 중복 여부를 가릴 때 같은 풀이로 볼 기준.
+pub(crate) fn duplicate_key(entry: &FileEntry) -> (Option<u32>, String, OsString) {
+    match entry.problem_number {
+        Some(number) => (Some(number), entry.extension.clone(), OsString::new()),
+        None => (
+            None,
+            entry.extension.clone(),
+            conflict_base(&entry.filename),
+        ),
+    }
+}
+
+/// 파일 내용의 해시. 읽을 수 없으면 `None`.
+pub(crate) fn content_hash(fs: &dyn FileSystem, path: &Path) -> Option<u64> {
+    let mut data = Vec::new();
+    fs.open(path).ok()?.read_to_end(&mut data).ok()?;
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+/// 두 파일의 내용이 바이트 단위로 같은지 확인합니다. 읽을 수 없으면 `false`.
+pub(crate) fn same_contents(fs: &dyn FileSystem, a: &Path, b: &Path) -> bool {
+    let read = |path: &Path| -> io::Result<Vec<u8>> {
+        let mut data = Vec::new();
+        fs.open(path)?.read_to_end(&mut data)?;
+        Ok(data)
+    };
+    match (fs.metadata(a), fs.metadata(b)) {
+        (Ok(meta_a), Ok(meta_b)) if meta_a.len == meta_b.len => {
+            matches!((read(a), read(b)), (Ok(a), Ok(b)) if a == b)
+        }
+        _ => false,
+    }
+}
+
+/// 가져올 파일마다 비어 있는 목적지 이름을 정해 계획에 더합니다.
+///
+/// `wanted`는 원본 경로와 원하는 목적지의 쌍입니다. 원본 경로의 깊이와 사전순으로
+/// 이름을 정하며, `is_taken`이 `true`인 경로와 앞서 정한 경로는 겹친 것으로 봅니다.
+/// 겹치면 `policy`에 따라 `_conflictN` 이름을 붙이거나, [`Event::ImportSkipped`]를
+/// 보고하고 건너뜁니다.
+pub(crate) fn assign_names(
+    plan: &mut ImportPlan,
+    mut wanted: Vec<MoveOperation>,
+    is_taken: impl Fn(&Path) -> bool,
+    policy: ConflictPolicy,
+    observer: &mut dyn Observer,
+) {
+    wanted.sort_by_cached_key(|op| (op.from.components().count(), op.from.clone()));
+    let mut assigned: HashSet<PathBuf> = HashSet::new();
+    let taken =
+        |path: &Path, assigned: &HashSet<PathBuf>| assigned.contains(path) || is_taken(path);
+
+    for op in wanted {
+        let mut target = op.to.clone();
+        if taken(&target, &assigned) {
+            if policy == ConflictPolicy::Skip {
+                observer.on_event(&Event::ImportSkipped {
+                    path: op.from.clone(),
+                    to: op.to.clone(),
+                });
+                plan.skipped.push(op);
+                continue;
+            }
+            let folder = op.to.parent().unwrap_or_else(|| Path::new(""));
+            let base = conflict_base(op.to.file_name().unwrap_or_default());
+            let mut conflict_num = 0;
+            while taken(&target, &assigned) {
+                conflict_num += 1;
+                target = folder.join(resolve_conflict(&base, conflict_num));
+            }
+            plan.renamed
+                .push(MoveOperation::new(op.from.clone(), target.clone()));
+        }
+        assigned.insert(target.clone());
+        plan.transfers.push(MoveOperation::new(op.from, target));
+    }
+
+    for list in [&mut plan.transfers, &mut plan.renamed, &mut plan.skipped] {
+        list.sort_by(|a, b| a.from.cmp(&b.from));
+    }
+}
+
+/// 가져올 파일을 원본 디렉토리에서 대상 디렉토리로 복사하거나 옮깁니다.
+///
+/// 폴더를 만들 때마다 [`Event::DirCreated`]를, 파일을 가져올 때마다
+/// [`Event::Imported`]를 보고합니다. 원본의 빈 폴더는 지우지 않습니다.
+///
+/// # Errors
+///
+/// 목적지에 이미 파일이 있으면 덮어쓰지 않고 [`Error::DestinationExists`]를,
+/// 폴더 생성이나 복사, 이동에 실패하면 해당 에러를 반환합니다.
+pub fn transfer_files_in(
+    fs: &dyn FileSystem,
+    source: &Path,
+    root: &Path,
+    transfers: &[MoveOperation],
+    mode: TransferMode,
+    observer: &mut dyn Observer,
+) -> Result<(), Error> {
+    for op in transfers {
+        let from_abs = source.join(&op.from);
+        let to_abs = root.join(&op.to);
+        create_parent(fs, root, &op.to, observer)?;
+        if fs.symlink_metadata(&to_abs).is_ok() {
+            return Err(Error::DestinationExists { path: to_abs });
+        }
+
+        let result = match mode {
+            TransferMode::Copy => fs.copy(&from_abs, &to_abs),
+            TransferMode::Move => match fs.rename(&from_abs, &to_abs) {
+                Err(error) if error.kind() == io::ErrorKind::CrossesDevices => fs
+                    .copy(&from_abs, &to_abs)
+                    .and_then(|()| fs.remove_file(&from_abs)),
+                result => result,
+            },
+        };
+        result.map_err(|source| Error::from_move(from_abs.clone(), to_abs.clone(), source))?;
+
+        observer.on_event(&Event::Imported {
+            from: op.from.clone(),
+            to: op.to.clone(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::NoopObserver;
+    use crate::vfs::{MemoryFs, Op, memory_with};
+
+    fn transfer(from: &str, to: &str) -> MoveOperation {
+        MoveOperation::new(PathBuf::from(from), PathBuf::from(to))
+    }
+
+    #[test]
+    fn same_contents_compares_bytes() {
+        let fs = memory_with(&[("/a/1.cpp", "x"), ("/b/1.cpp", "x"), ("/b/2.cpp", "y")]);
+
+        assert!(same_contents(
+            &fs,
+            Path::new("/a/1.cpp"),
+            Path::new("/b/1.cpp")
+        ));
+        assert!(!same_contents(
+            &fs,
+            Path::new("/a/1.cpp"),
+            Path::new("/b/2.cpp")
+        ));
+        assert!(!same_contents(
+            &fs,
+            Path::new("/a/1.cpp"),
+            Path::new("/b/3.cpp")
+        ));
+    }
+
+    #[test]
+    fn duplicate_key_ignores_variant_names() {
+        let numbered = FileEntry::new(PathBuf::from("1000_2.CPP"), Some(1000));
+        let plain = FileEntry::new(PathBuf::from("1000.cpp"), Some(1000));
+        let named = FileEntry::new(PathBuf::from("main_conflict1.py"), None);
+
+        assert_eq!(duplicate_key(&numbered), duplicate_key(&plain));
+        assert_eq!(
+            duplicate_key(&named),
+            duplicate_key(&FileEntry::new(PathBuf::from("main.py"), None))
+        );
+    }
+
+    #[test]
+    fn copy_keeps_source_and_move_removes_it() {
+        let fs = memory_with(&[("/src/1.cpp", "one"), ("/src/2.cpp", "two"), ("/dst/x", "")]);
+        let mut events = Vec::new();
+
+        transfer_files_in(
+            &fs,
+            Path::new("/src"),
+            Path::new("/dst"),
+            &[transfer("1.cpp", "1000/1.cpp")],
+            TransferMode::Copy,
+            &mut |event: &Event| events.push(event.clone()),
+        )
+        .unwrap();
+        transfer_files_in(
+            &fs,
+            Path::new("/src"),
+            Path::new("/dst"),
+            &[transfer("2.cpp", "2.cpp")],
+            TransferMode::Move,
+            &mut NoopObserver,
+        )
+        .unwrap();
+
+        assert!(fs.exists(Path::new("/src/1.cpp")));
+        assert_eq!(
+            fs.read_to_string(Path::new("/dst/1000/1.cpp")).unwrap(),
+            "one"
+        );
+        assert!(!fs.exists(Path::new("/src/2.cpp")));
+        assert_eq!(fs.read_to_string(Path::new("/dst/2.cpp")).unwrap(), "two");
+        assert_eq!(
+            events,
+            [
+                Event::DirCreated {
+                    path: PathBuf::from("1000"),
+                },
+                Event::Imported {
+                    from: PathBuf::from("1.cpp"),
+                    to: PathBuf::from("1000/1.cpp"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn move_across_devices_copies_then_removes() {
+        let fs = memory_with(&[("/src/1.cpp", "one"), ("/dst/x", "")]);
+        fs.set_device("/src", 2).unwrap();
+
+        transfer_files_in(
+            &fs,
+            Path::new("/src"),
+            Path::new("/dst"),
+            &[transfer("1.cpp", "1.cpp")],
+            TransferMode::Move,
+            &mut NoopObserver,
+        )
+        .unwrap();
+
+        assert!(!fs.exists(Path::new("/src/1.cpp")));
+        assert_eq!(fs.read_to_string(Path::new("/dst/1.cpp")).unwrap(), "one");
+    }
+
+    #[test]
+    fn transfer_refuses_to_overwrite_and_reports_faults() {
+        let fs = memory_with(&[("/src/1.cpp", "new"), ("/dst/1.cpp", "old")]);
+        let transfers = [transfer("1.cpp", "1.cpp")];
+        let run = |fs: &MemoryFs| {
+            transfer_files_in(
+                fs,
+                Path::new("/src"),
+                Path::new("/dst"),
+                &transfers,
+                TransferMode::Copy,
+                &mut NoopObserver,
+            )
+        };
+
+        assert!(matches!(run(&fs), Err(Error::DestinationExists { .. })));
+        assert_eq!(fs.read_to_string(Path::new("/dst/1.cpp")).unwrap(), "old");
+
+        fs.remove_file(Path::new("/dst/1.cpp")).unwrap();
+        fs.inject(Op::Copy, "/src", io::ErrorKind::PermissionDenied);
+        assert!(matches!(run(&fs), Err(Error::PermissionDenied { .. })));
+    }
+}
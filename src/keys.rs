@@ -0,0 +1,264 @@
+//! 그룹 키 추출.
+//!
+//! 번호 범위 대신 태그, 난이도, 날짜 같은 값으로 상위 폴더를 정합니다
+//! (예: `dp/1000.cpp`, `gold3/1000.cpp`, `2024/03/1000.cpp`). 각 키 폴더 안에서는
+//! 번호 범위 그룹핑이 그대로 적용됩니다.
+
+use crate::metadata::{Date, MetadataStore, date_from_days, parse_date};
+use crate::types::FileEntry;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::UNIX_EPOCH;
+
+/// 파일이 들어갈 상위 폴더를 정하는 키 추출기.
+pub trait KeyExtractor {
+    /// 상위 폴더 경로 (`/`로 구분). `None`이면 번호 범위 그룹핑만 적용합니다.
+    fn key(&self, entry: &FileEntry) -> Option<String>;
+}
+
+impl<F> KeyExtractor for F
+where
+    F: Fn(&FileEntry) -> Option<String>,
+{
+    fn key(&self, entry: &FileEntry) -> Option<String> {
+        self(entry)
+    }
+}
+
+/// 키 없이 번호 범위로만 그룹핑합니다.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NumberKey;
+
+impl KeyExtractor for NumberKey {
+    fn key(&self, _entry: &FileEntry) -> Option<String> {
+        None
+    }
+}
+
+/// 그룹 기준.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum GroupBy {
+    /// 번호 범위 (기존 동작).
+    #[default]
+    Number,
+    /// 메타데이터의 첫 번째 태그.
+    Tag,
+    /// 메타데이터의 난이도.
+    Tier,
+    /// 푼 날짜 (`연/월`).
+    Date,
+}
+
+/// 날짜 기준 그룹핑에서 메타데이터에 푼 날짜가 없을 때 쓸 날짜.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum DateSource {
+    /// 파일 수정 시각.
+    #[default]
+    Mtime,
+    /// 현재 경로로 처음 커밋된 날짜. 커밋되지 않은 파일은 수정 시각을 씁니다.
+    Git,
+}
+
+/// 메타데이터 필드로 키를 정합니다.
+pub struct MetadataKey<'a> {
+    store: &'a MetadataStore,
+    group_by: GroupBy,
+}
+
+impl KeyExtractor for MetadataKey<'_> {
+    fn key(&self, entry: &FileEntry) -> Option<String> {
+        let meta = self.store.get(entry.problem_number?)?;
+        match self.group_by {
+            GroupBy::Tag => meta.tags.first().cloned(),
+            GroupBy::Tier => meta.tier.clone(),
+            GroupBy::Number | GroupBy::Date => None,
+        }
+    }
+}
+
+/// 날짜로 키를 정합니다.
+///
+/// 메타데이터의 푼 날짜를 우선하고, 없으면 미리 수집한 파일 날짜를 씁니다.
+pub struct DateKey<'a> {
+    store: &'a MetadataStore,
+    dates: HashMap<PathBuf, Date>,
+}
+
+impl KeyExtractor for DateKey<'_> {
+    fn key(&self, entry: &FileEntry) -> Option<String> {
+        let solved = entry
+            .problem_number
+            .and_then(|number| self.store.get(number))
+            .and_then(|meta| meta.solved);
+        let date = solved.or_else(|| self.dates.get(&entry.current_path).copied())?;
+        Some(format!("{:04}/{:02}", date.year, date.month))
+    }
+}
+
+/// 그룹 기준에 맞는 키 추출기를 만듭니다.
+///
+/// 날짜 기준이면 `entries`의 파일 날짜를 이 시점에 한 번 수집합니다.
+pub fn extractor<'a>(
+    group_by: GroupBy,
+    date_source: DateSource,
+    root: &Path,
+    entries: &[FileEntry],
+    store: &'a MetadataStore,
+) -> Box<dyn KeyExtractor + 'a> {
+    match group_by {
+        GroupBy::Number => Box::new(NumberKey),
+        GroupBy::Tag | GroupBy::Tier => Box::new(MetadataKey { store, group_by }),
+        GroupBy::Date => {
+            let mut dates = match date_source {
+                DateSource::Mtime => HashMap::new(),
+                DateSource::Git => git_dates(root),
+            };
+            for entry in entries {
+                if !dates.contains_key(&entry.current_path)
+                    && let Some(date) = mtime_date(&root.join(&entry.current_path))
+                {
+                    dates.insert(entry.current_path.clone(), date);
+                }
+            }
+            Box::new(DateKey { store, dates })
+        }
+    }
+}
+
+/// 키를 안전한 상대 폴더 경로로 바꿉니다.
+///
+/// 각 구간의 앞뒤 공백을 지우고 `\`는 `-`로 바꿉니다. 빈 구간과 `.`, `..`
+/// 구간은 버리며, 남는 구간이 없으면 `None`을 반환합니다.
+pub fn sanitize_key(key: &str) -> Option<String> {
+    let segments: Vec<String> = key
+        .split('/')
+        .map(|segment| segment.trim().replace('\\', "-"))
+        .filter(|segment| !segment.is_empty() && segment != "." && segment != "..")
+        .collect();
+    (!segments.is_empty()).then(|| segments.join("/"))
+}
+
+fn mtime_date(path: &Path) -> Option<Date> {
+    let modified = path.metadata().ok()?.modified().ok()?;
+    let secs = modified.duration_since(UNIX_EPOCH).ok()?.as_secs();
+    Some(date_from_days(i64::try_from(secs / 86_400).ok()?))
+}
+
+/// 파일별로 현재 경로로 처음 커밋된 날짜를 수집합니다.
+///
+/// git이 없거나 저장소가 아니면 빈 목록을 반환합니다.
+fn git_dates(root: &Path) -> HashMap<PathBuf, Date> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args([
+            "-c",
+            "core.quotePath=false",
+            "log",
+            "--relative",
+            "--format=%x01%cs",
+            "--name-only",
+        ])
+        .output();
+    let Ok(output) = output else {
+        return HashMap::new();
+    };
+    if !output.status.success() {
+        return HashMap::new();
+    }
+    parse_git_log(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// `git log --format=%x01%cs --name-only` 출력을 파싱합니다.
+///
+/// 로그는 최신 커밋부터 나오므로, 경로마다 마지막으로 나온 날짜를 남깁니다.
+fn parse_git_log(log: &str) -> HashMap<PathBuf, Date> {
+    let mut dates = HashMap::new();
+    let mut current = None;
+    for line in log.lines() {
+        if let Some(date) = line.strip_prefix('\u{1}') {
+            current = parse_date(date).ok();
+        } else if !line.is_empty()
+            && let Some(date) = current
+        {
+            dates.insert(PathBuf::from(line), date);
+        }
+    }
+    dates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitize_key_drops_unsafe_segments() {
+        assert_eq!(sanitize_key("dp"), Some("dp".to_string()));
+        assert_eq!(sanitize_key("2024/03"), Some("2024/03".to_string()));
+        assert_eq!(sanitize_key("../etc"), Some("etc".to_string()));
+        assert_eq!(sanitize_key("a\\b"), Some("a-b".to_string()));
+        assert_eq!(sanitize_key(" / . "), None);
+    }
+
+    #[test]
+    fn parse_git_log_keeps_oldest_date() {
+        let log = "\u{1}2024-05-02\n\n1000.cpp\n\u{1}2024-03-01\n\n1000.cpp\n1001.cpp\n";
+
+        let dates = parse_git_log(log);
+
+        let date = dates[Path::new("1000.cpp")];
+        assert_eq!((date.year, date.month, date.day), (2024, 3, 1));
+        assert_eq!(dates.len(), 2);
+    }
+
+    #[test]
+    fn metadata_key_reads_first_tag_and_tier() {
+        let mut store = MetadataStore::default();
+        let meta = store.entry(1000);
+        meta.tags = vec!["dp".to_string(), "math".to_string()];
+        meta.tier = Some("gold3".to_string());
+        let entry = FileEntry::new(PathBuf::from("1000.cpp"), Some(1000));
+
+        let tag = extractor(GroupBy::Tag, DateSource::Mtime, Path::new("."), &[], &store);
+        let tier = extractor(
+            GroupBy::Tier,
+            DateSource::Mtime,
+            Path::new("."),
+            &[],
+            &store,
+        );
+
+        assert_eq!(tag.key(&entry), Some("dp".to_string()));
+        assert_eq!(tier.key(&entry), Some("gold3".to_string()));
+        assert_eq!(
+            tag.key(&FileEntry::new(PathBuf::from("1001.cpp"), Some(1001))),
+            None
+        );
+    }
+
+    #[test]
+    fn date_key_prefers_solved_date() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::write(temp.path().join("1000.cpp"), "").unwrap();
+        std::fs::write(temp.path().join("1001.cpp"), "").unwrap();
+        let entries = vec![
+            FileEntry::new(PathBuf::from("1000.cpp"), Some(1000)),
+            FileEntry::new(PathBuf::from("1001.cpp"), Some(1001)),
+        ];
+        let mut store = MetadataStore::default();
+        store.entry(1000).solved = Some(parse_date("2024-03-01").unwrap());
+
+        let keys = extractor(
+            GroupBy::Date,
+            DateSource::Mtime,
+            temp.path(),
+            &entries,
+            &store,
+        );
+
+        assert_eq!(keys.key(&entries[0]), Some("2024/03".to_string()));
+        let mtime = keys.key(&entries[1]).unwrap();
+        assert_eq!(mtime.len(), "2024/03".len());
+    }
+}
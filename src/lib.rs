@@ -1,10 +1,28 @@
 //! PS 문제 소스코드 자동 정리 라이브러리.
 //!
 //! `.cpp`, `.c`, `.py` 파일을 문제 번호 기반으로 자동 그룹핑합니다.
+//! 라이브러리로 사용할 때는 [`organizer::Organizer`]에서 시작하세요.
 
-pub mod cli;
+pub mod archive;
+pub mod cache;
+pub mod conflicts;
+pub mod events;
 pub mod executor;
+pub mod export;
+pub mod extension;
 pub mod grouper;
+pub mod header;
+pub mod i18n;
+pub mod import;
+pub mod keys;
+pub mod locate;
+pub mod metadata;
+pub mod organizer;
 pub mod planner;
+pub mod review;
+pub mod scaffold;
 pub mod scanner;
+pub mod simulate;
+pub mod tree;
 pub mod types;
+pub mod vfs;
@@ -0,0 +1,275 @@
+//! 문제 번호로 파일 찾기.
+//!
+//! 정리된 트리에서 문제에 속한 파일을 모두 찾습니다. 풀이 파일(`1000.cpp`)뿐 아니라
+//! 변형(`1000_2.cpp`, `1000-dp.py`)과 같은 번호로 시작하는 딸린 파일(`1000.in`,
+//! `1000_input.txt`)도 포함합니다.
+
+use crate::scanner::ScanReport;
+use std::collections::BTreeSet;
+use std::ffi::OsStr;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// 문제 번호 뒤에 올 수 있는 구분 문자.
+const NUMBER_SEPARATORS: [u8; 3] = [b'.', b'_', b'-'];
+
+/// 문제 번호 범위.
+///
+/// `1000`, `1000..1100` (끝 제외), `1000..=1100` (끝 포함), `1000..`, `..1100`
+/// 형식을 파싱하며, 양 끝을 포함하는 범위로 저장합니다.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProblemRange(RangeInclusive<u32>);
+
+impl ProblemRange {
+    /// 번호 하나만 담은 범위.
+    pub const fn single(number: u32) -> Self {
+        Self(number..=number)
+    }
+
+    /// 첫 번호.
+    pub const fn start(&self) -> u32 {
+        *self.0.start()
+    }
+
+    /// 마지막 번호.
+    pub const fn end(&self) -> u32 {
+        *self.0.end()
+    }
+
+    /// 번호가 범위에 들어가는지 확인합니다.
+    pub fn contains(&self, number: u32) -> bool {
+        self.0.contains(&number)
+    }
+
+    /// 범위의 번호 개수.
+    pub fn len(&self) -> u64 {
+        if self.is_empty() {
+            0
+        } else {
+            u64::from(self.end() - self.start()) + 1
+        }
+    }
+
+    /// 범위가 비었는지 확인합니다.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// 범위의 번호를 차례로 순회합니다.
+    pub fn iter(&self) -> RangeInclusive<u32> {
+        self.0.clone()
+    }
+}
+
+impl IntoIterator for &ProblemRange {
+    type Item = u32;
+    type IntoIter = RangeInclusive<u32>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl FromStr for ProblemRange {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let number = |text: &str| {
+            text.trim()
+                .parse::<u32>()
+                .map_err(|_| format!("expected N, A..B or A..=B, got `{value}`"))
+        };
+        let Some((start, end)) = value.split_once("..") else {
+            return number(value).map(Self::single);
+        };
+
+        let start = if start.trim().is_empty() {
+            0
+        } else {
+            number(start)?
+        };
+        let range = if let Some(end) = end.strip_prefix('=') {
+            start..=number(end)?
+        } else if end.trim().is_empty() {
+            start..=u32::MAX
+        } else {
+            let end = number(end)?;
+            if end == 0 {
+                return Err(format!("empty range `{value}`"));
+            }
+            start..=end - 1
+        };
+        if range.is_empty() {
+            return Err(format!("empty range `{value}`"));
+        }
+        Ok(Self(range))
+    }
+}
+
+impl fmt::Display for ProblemRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.start() == self.end() {
+            write!(f, "{}", self.start())
+        } else {
+            write!(f, "{}..={}", self.start(), self.end())
+        }
+    }
+}
+
+/// 파일이 속한 문제 번호를 파일명 앞부분에서 찾습니다.
+///
+/// 파일명이 숫자로 시작하고 그 뒤가 끝이거나 `.`, `_`, `-`이면 그 숫자를 문제
+/// 번호로 봅니다 (`1000_2.cpp`, `1000.in` → 1000). `1000abc.cpp`처럼 숫자 뒤에
+/// 다른 문자가 오면 `None`을 반환합니다.
+pub fn related_number(filename: &OsStr) -> Option<u32> {
+    let bytes = filename.as_encoded_bytes();
+    let end = bytes
+        .iter()
+        .position(|b| !b.is_ascii_digit())
+        .unwrap_or(bytes.len());
+    if end == 0
+        || bytes
+            .get(end)
+            .is_some_and(|b| !NUMBER_SEPARATORS.contains(b))
+    {
+        return None;
+    }
+    std::str::from_utf8(&bytes[..end]).ok()?.parse().ok()
+}
+
+/// 찾은 파일.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ProblemFile {
+    /// 문제 번호.
+    pub number: u32,
+    /// 루트 디렉토리 기준 상대 경로.
+    pub path: PathBuf,
+}
+
+/// 스캔 결과에서 번호가 `range`에 드는 문제의 파일을 모두 찾습니다.
+///
+/// 스캐너가 수집한 풀이 파일(머리 주석으로 번호를 찾은 파일 포함)과 딸린 파일을
+/// 모으므로, 스캔에 쓴 확장자와 링크 처리 방식을 그대로 따릅니다. 문제 번호,
+/// 경로 순으로 정렬해 반환합니다.
+pub fn find_problem_files(scan: &ScanReport, range: &ProblemRange) -> Vec<ProblemFile> {
+    let solutions = scan.entries.iter().filter_map(|entry| {
+        let number = entry
+            .problem_number
+            .or_else(|| related_number(&entry.filename))?;
+        Some(ProblemFile {
+            number,
+            path: entry.current_path.clone(),
+        })
+    });
+    let companions = scan.companions.iter().filter_map(|path| {
+        Some(ProblemFile {
+            number: related_number(path.file_name()?)?,
+            path: path.clone(),
+        })
+    });
+    let files: BTreeSet<ProblemFile> = solutions
+        .chain(companions)
+        .filter(|file| range.contains(file.number))
+        .collect();
+    files.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::NoopObserver;
+    use crate::metadata::STATE_DIR;
+    use crate::scanner::{ScanOptions, scan_directory_with};
+    use std::fs;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn find(root: &Path, range: &ProblemRange, options: &ScanOptions) -> Vec<ProblemFile> {
+        find_problem_files(
+            &scan_directory_with(root, options, &mut NoopObserver),
+            range,
+        )
+    }
+
+    fn range(value: &str) -> ProblemRange {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_ranges() {
+        assert_eq!(range("1000"), ProblemRange::single(1000));
+        assert_eq!(range("1000..1100"), ProblemRange(1000..=1099));
+        assert_eq!(range("1000..=1100"), ProblemRange(1000..=1100));
+        assert_eq!(range("1000.."), ProblemRange(1000..=u32::MAX));
+        assert_eq!(range("..100"), ProblemRange(0..=99));
+        assert_eq!(range("1000..1100").len(), 100);
+    }
+
+    #[test]
+    fn parse_rejects_invalid_ranges() {
+        for value in ["abc", "1000..abc", "1100..1000", "5..5", "..0", ""] {
+            assert!(value.parse::<ProblemRange>().is_err(), "{value}");
+        }
+    }
+
+    #[test]
+    fn related_number_needs_separator() {
+        assert_eq!(related_number(OsStr::new("1000.cpp")), Some(1000));
+        assert_eq!(related_number(OsStr::new("1000_2.cpp")), Some(1000));
+        assert_eq!(related_number(OsStr::new("1000-input.txt")), Some(1000));
+        assert_eq!(related_number(OsStr::new("1000")), Some(1000));
+        assert_eq!(related_number(OsStr::new("1000abc.cpp")), None);
+        assert_eq!(related_number(OsStr::new("main.cpp")), None);
+    }
+
+    #[test]
+    fn find_collects_variants_and_companions() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(root.join("1000")).unwrap();
+        fs::create_dir_all(root.join(STATE_DIR)).unwrap();
+        for name in [
+            "1000/1000.cpp",
+            "1000/1000_2.py",
+            "1000/1000.in",
+            "1000/1001.cpp",
+            "10000.cpp",
+            ".ps-organizer/1000.cpp",
+        ] {
+            fs::write(root.join(name), "").unwrap();
+        }
+        fs::write(root.join("main.cpp"), "// BOJ 1000\n").unwrap();
+
+        let options = ScanOptions {
+            header_lines: 5,
+            ..ScanOptions::default()
+        };
+        let files = find(root, &ProblemRange::single(1000), &options);
+
+        let paths: Vec<_> = files.iter().map(|f| f.path.clone()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("1000/1000.cpp"),
+                PathBuf::from("1000/1000.in"),
+                PathBuf::from("1000/1000_2.py"),
+                PathBuf::from("main.cpp"),
+            ]
+        );
+    }
+
+    #[test]
+    fn find_range_sorts_by_number() {
+        let temp = TempDir::new().unwrap();
+        for name in ["1002.cpp", "1000.cpp", "1001.py", "1100.cpp"] {
+            fs::write(temp.path().join(name), "").unwrap();
+        }
+
+        let files = find(temp.path(), &range("1000..1100"), &ScanOptions::default());
+
+        let numbers: Vec<_> = files.iter().map(|f| f.number).collect();
+        assert_eq!(numbers, vec![1000, 1001, 1002]);
+    }
+}
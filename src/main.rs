@@ -1,38 +1,187 @@
+mod cli;
+mod commands;
+mod output;
+
 use anyhow::Result;
 use clap::Parser;
-use ps_organizer::cli::Cli;
-use ps_organizer::{executor, planner, scanner};
+use cli::{Cli, Command, LogFormat};
+use output::Console;
+use ps_organizer::events::{Event, JsonObserver, Observer, TextObserver, Verbosity};
+use ps_organizer::executor::CleanupRules;
+use ps_organizer::organizer::{Options, Organizer, Plan};
+use ps_organizer::review::ReviewOutcome;
+use ps_organizer::types::Error;
+use ps_organizer::{i18n, review, t, tree};
+use std::collections::HashMap;
+use std::env;
+use std::io::{self, IsTerminal};
+use std::process::ExitCode;
 
-fn main() -> Result<()> {
+fn main() -> ExitCode {
     let args = Cli::parse();
+    i18n::set_lang(args.lang.unwrap_or_else(i18n::Lang::detect));
 
-    let root = args.target_dir.canonicalize().unwrap_or(args.target_dir);
+    let result = match &args.command {
+        Some(Command::Meta { action }) => commands::meta::run(action),
+        Some(Command::New(new)) => commands::new::run(new),
+        Some(Command::Find(find)) => commands::find::run(find),
+        Some(Command::Path(path)) => commands::path::run(path),
+        Some(Command::Conflicts(conflicts)) => commands::conflicts::run(conflicts),
+        Some(Command::Import(import)) => commands::import::run(import),
+        Some(Command::Export(export)) => commands::export::run(export),
+        Some(Command::Flatten(flatten)) => commands::flatten::run(flatten),
+        None => run(&args),
+    };
 
-    if args.verbose {
-        println!("대상 디렉토리: {}", root.display());
-        println!("임계값: {}", args.threshold);
-        println!("Dry-run: {}\n", args.dry_run);
+    match result {
+        Ok(code) => code,
+        Err(error) => {
+            eprintln!("{}", t!("main.error", error = format!("{error:#}")));
+            let code = error.downcast_ref::<Error>().map_or(1, Error::exit_code);
+            ExitCode::from(code)
+        }
     }
+}
+
+/// 정리 작업을 실행합니다.
+///
+/// 작업은 끝났지만 스캔 중 건너뛴 경로가 있으면, 첫 에러 분류의 종료 코드를 돌려줍니다.
+fn run(args: &Cli) -> Result<ExitCode> {
+    let verbosity = Verbosity::from_flags(args.quiet, args.verbose);
+    let console = Console {
+        verbosity,
+        human: args.log_format == LogFormat::Text,
+    };
+    let mut observer: Box<dyn Observer> = match args.log_format {
+        LogFormat::Text => Box::new(TextObserver::new(io::stdout(), verbosity)),
+        LogFormat::Json => Box::new(JsonObserver::new(io::stdout())),
+    };
 
-    let entries = scanner::scan_directory(&root);
+    let mut organizer = Organizer::builder(&args.target_dir)
+        .threshold(args.threshold)
+        .extension_policy(args.extension_policy())
+        .symlinks(args.scan.symlinks.into())
+        .header_lines(args.headers)
+        .group_by(args.group_by)
+        .date_source(args.date_source)
+        .cache(!args.no_cache)
+        .jobs(args.jobs)
+        .options(cleanup_options(args))
+        .observer(move |event: &Event| observer.on_event(event))
+        .build()?;
 
-    if args.verbose {
-        println!("스캔된 파일: {} 개\n", entries.len());
+    console.say(
+        Verbosity::Verbose,
+        &t!("main.target_dir", path = organizer.root().display()),
+    );
+    console.say(
+        Verbosity::Verbose,
+        &t!("main.threshold", threshold = args.threshold),
+    );
+    console.say(
+        Verbosity::Verbose,
+        &format!("{}\n", t!("main.dry_run", enabled = args.dry_run)),
+    );
+
+    let scan = organizer.scan();
+    for error in &scan.errors {
+        eprintln!("{}", t!("main.warning", error = error));
     }
+    let scan_status = scan
+        .errors
+        .first()
+        .map_or(ExitCode::SUCCESS, |e| ExitCode::from(e.exit_code()));
+
+    console.say(
+        Verbosity::Verbose,
+        &format!("{}\n", t!("main.scanned", count = scan.entries.len())),
+    );
 
-    if entries.is_empty() {
-        println!("정리할 파일이 없습니다.");
-        return Ok(());
+    if scan.entries.is_empty() {
+        console.say(Verbosity::Normal, &t!("main.nothing_to_do"));
+        return Ok(scan_status);
     }
 
-    let moves = planner::plan_moves(&entries, args.threshold);
+    let overrides = if args.interactive {
+        let outcome = review::review_plan(
+            &scan.entries,
+            &organizer.plan_options(),
+            organizer.key_extractor(&scan).as_ref(),
+            io::stdin().lock(),
+            io::stdout(),
+        )?;
+        match outcome {
+            ReviewOutcome::Accepted { overrides, .. } => overrides,
+            ReviewOutcome::Aborted => {
+                console.say(Verbosity::Normal, &t!("main.aborted"));
+                return Ok(scan_status);
+            }
+        }
+    } else {
+        HashMap::new()
+    };
+    // 검토에서 고정한 목적지로 다시 계획해, 충돌 목록과 계획 이벤트를 검토하지 않을
+    // 때와 같이 얻습니다.
+    let plan = organizer.plan_with_overrides(&scan, &overrides);
 
     if args.dry_run {
-        executor::print_plan(&moves, args.verbose);
+        return dry_run(args, &console, &organizer, &plan, scan_status);
+    }
+
+    if plan.moves.is_empty() {
+        console.say(Verbosity::Normal, &t!("exec.empty"));
     } else {
-        executor::execute_moves(&root, &moves, args.verbose)?;
-        executor::cleanup_empty_dirs(&root, args.verbose)?;
+        console.say(
+            Verbosity::Normal,
+            &format!("{}\n", t!("exec.header", count = plan.moves.len())),
+        );
+    }
+
+    let report = organizer.execute(&plan)?;
+    if let Err(error) = organizer.save_cache(&scan) {
+        eprintln!("{}", t!("main.cache_save_failed", error = error));
     }
 
-    Ok(())
+    output::print_summary(&console, &report);
+
+    Ok(scan_status)
+}
+
+/// 계획을 출력하고, 실행하면 실패할 작업을 시뮬레이션으로 찾습니다.
+///
+/// 실패할 작업이 있으면 첫 실패의 에러 분류에 맞는 종료 코드를 돌려줍니다.
+fn dry_run(
+    args: &Cli,
+    console: &Console,
+    organizer: &Organizer,
+    plan: &Plan,
+    scan_status: ExitCode,
+) -> Result<ExitCode> {
+    if console.human {
+        if args.tree {
+            let color = io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none();
+            tree::build_tree_diff(&plan.entries, &plan.moves)
+                .render(&mut io::stdout().lock(), color)?;
+        } else {
+            output::print_plan(&plan.moves, console.verbosity >= Verbosity::Verbose);
+        }
+    }
+
+    let simulation = organizer.simulate(plan);
+    output::print_simulation(console, &simulation);
+    Ok(simulation
+        .failures
+        .first()
+        .map_or(scan_status, |e| ExitCode::from(e.exit_code())))
+}
+
+/// 빈 폴더 정리 옵션을 만듭니다. 거부 목록은 기본 목록에 더합니다.
+fn cleanup_options(args: &Cli) -> Options {
+    let mut rules = CleanupRules::default();
+    rules.allow.extend(args.cleanup_allow.iter().cloned());
+    rules.deny.extend(args.cleanup_deny.iter().cloned());
+    Options {
+        cleanup_empty_dirs: !args.keep_empty,
+        cleanup_rules: rules,
+    }
 }
@@ -0,0 +1,299 @@
+//! 문제 메타데이터 저장소.
+//!
+//! 문제 번호별 제목, 난이도, 태그, 풀이 상태, 푼 날짜를 대상 디렉토리의
+//! `.ps-organizer/problems.toml`에 저장합니다.
+//!
+//! ```toml
+//! [1000]
+//! title = "A+B"
+//! tier = "bronze5"
+//! tags = ["math", "implementation"]
+//! status = "solved"
+//! solved = 2024-03-01
+//! ```
+
+use crate::types::Error;
+use crate::vfs::{FileSystem, RealFs};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+pub use toml::value::Date;
+
+/// 도구가 상태를 저장하는 디렉토리 이름. 스캔 대상에서 제외됩니다.
+pub const STATE_DIR: &str = ".ps-organizer";
+
+const FILE_NAME: &str = "problems.toml";
+
+/// 풀이 상태.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    /// 맞음.
+    Solved,
+    /// 틀림.
+    Wrong,
+    /// 아직 풀지 않음.
+    Todo,
+}
+
+/// 문제 하나의 메타데이터.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProblemMeta {
+    /// 문제 제목.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// 난이도 (예: `gold3`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tier: Option<String>,
+    /// 알고리즘 분류 태그.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// 풀이 상태.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<Status>,
+    /// 푼 날짜.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub solved: Option<Date>,
+}
+
+impl ProblemMeta {
+    /// 저장할 값이 하나도 없는지 확인합니다.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// 문제 번호를 키로 하는 메타데이터 저장소.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MetadataStore {
+    problems: BTreeMap<u32, ProblemMeta>,
+}
+
+impl MetadataStore {
+    /// 대상 디렉토리의 저장소 파일 경로.
+    pub fn path(root: &Path) -> PathBuf {
+        root.join(STATE_DIR).join(FILE_NAME)
+    }
+
+    /// 대상 디렉토리에서 저장소를 읽습니다. 파일이 없으면 빈 저장소를 반환합니다.
+    ///
+    /// # Errors
+    ///
+    /// 파일을 읽을 수 없으면 I/O 에러를, 형식이 잘못되었으면
+    /// [`Error::InvalidMetadata`]를 반환합니다.
+    pub fn load(root: &Path) -> Result<Self, Error> {
+        Self::load_in(&RealFs, root)
+    }
+
+    /// [`MetadataStore::load`]와 같지만 주어진 파일 시스템에서 읽습니다.
+    ///
+    /// # Errors
+    ///
+    /// [`MetadataStore::load`]와 같습니다.
+    pub fn load_in(fs: &dyn FileSystem, root: &Path) -> Result<Self, Error> {
+        let path = Self::path(root);
+        let text = match fs.read_to_string(&path) {
+            Ok(text) => text,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(source) => return Err(Error::from_io(path, source)),
+        };
+        Self::from_toml(&text).map_err(|reason| Error::InvalidMetadata { path, reason })
+    }
+
+    /// 저장소를 대상 디렉토리에 씁니다.
+    ///
+    /// 임시 파일에 먼저 쓴 뒤 이름을 바꾸므로, 중간에 실패해도 기존 파일은 유지됩니다.
+    ///
+    /// # Errors
+    ///
+    /// 디렉토리 생성이나 파일 쓰기에 실패하면 에러를 반환합니다.
+    pub fn save(&self, root: &Path) -> Result<(), Error> {
+        let path = Self::path(root);
+        let dir = root.join(STATE_DIR);
+        fs::create_dir_all(&dir).map_err(|source| Error::from_io(dir, source))?;
+
+        let temp = path.with_extension("toml.tmp");
+        fs::write(&temp, self.to_toml()).map_err(|source| Error::from_io(temp.clone(), source))?;
+        fs::rename(&temp, &path).map_err(|source| Error::from_io(path, source))
+    }
+
+    /// TOML 문서에서 저장소를 읽습니다.
+    fn from_toml(text: &str) -> Result<Self, String> {
+        let raw: BTreeMap<String, ProblemMeta> =
+            toml::from_str(text).map_err(|error| error.message().to_string())?;
+        let problems = raw
+            .into_iter()
+            .map(|(key, meta)| {
+                key.parse::<u32>()
+                    .map(|number| (number, meta))
+                    .map_err(|_| format!("`{key}` is not a problem number"))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { problems })
+    }
+
+    /// 저장소를 TOML 문서로 만듭니다.
+    pub fn to_toml(&self) -> String {
+        let raw: BTreeMap<String, &ProblemMeta> = self
+            .problems
+            .iter()
+            .filter(|(_, meta)| !meta.is_empty())
+            .map(|(number, meta)| (number.to_string(), meta))
+            .collect();
+        toml::to_string(&raw).unwrap_or_default()
+    }
+
+    /// 문제의 메타데이터를 반환합니다.
+    pub fn get(&self, number: u32) -> Option<&ProblemMeta> {
+        self.problems.get(&number)
+    }
+
+    /// 문제의 메타데이터를 수정할 수 있도록 반환합니다. 없으면 빈 항목을 만듭니다.
+    pub fn entry(&mut self, number: u32) -> &mut ProblemMeta {
+        self.problems.entry(number).or_default()
+    }
+
+    /// 문제의 메타데이터를 삭제합니다.
+    pub fn remove(&mut self, number: u32) -> Option<ProblemMeta> {
+        self.problems.remove(&number)
+    }
+
+    /// 문제 번호 순으로 모든 항목을 순회합니다.
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &ProblemMeta)> {
+        self.problems.iter().map(|(number, meta)| (*number, meta))
+    }
+
+    /// 저장된 문제 수.
+    pub fn len(&self) -> usize {
+        self.problems.len()
+    }
+
+    /// 저장된 문제가 없는지 확인합니다.
+    pub fn is_empty(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// `YYYY-MM-DD` 형식의 날짜를 파싱합니다.
+///
+/// # Errors
+///
+/// 형식이 맞지 않거나 시각이 포함되어 있으면 에러 메시지를 반환합니다.
+pub fn parse_date(value: &str) -> Result<Date, String> {
+    let datetime: toml::value::Datetime = value.parse().map_err(|_| invalid_date(value))?;
+    match datetime {
+        toml::value::Datetime {
+            date: Some(date),
+            time: None,
+            offset: None,
+        } => Ok(date),
+        _ => Err(invalid_date(value)),
+    }
+}
+
+fn invalid_date(value: &str) -> String {
+    format!("expected YYYY-MM-DD, got `{value}`")
+}
+
+/// 오늘 날짜 (UTC).
+pub fn today() -> Date {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    date_from_days(i64::try_from(secs / 86_400).unwrap_or(0))
+}
+
+/// 1970-01-01부터 지난 일수를 UTC 날짜로 바꿉니다.
+pub(crate) fn date_from_days(days: i64) -> Date {
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    Date {
+        year: u16::try_from(year).unwrap_or(0),
+        month: u8::try_from(month).unwrap_or(1),
+        day: u8::try_from(day).unwrap_or(1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn load_missing_store_is_empty() {
+        let temp = TempDir::new().unwrap();
+        let store = MetadataStore::load(temp.path()).unwrap();
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let temp = TempDir::new().unwrap();
+        let mut store = MetadataStore::default();
+        let meta = store.entry(1000);
+        meta.title = Some("A+B".to_string());
+        meta.tier = Some("bronze5".to_string());
+        meta.tags = vec!["math".to_string()];
+        meta.status = Some(Status::Solved);
+        meta.solved = Some(parse_date("2024-03-01").unwrap());
+
+        store.save(temp.path()).unwrap();
+        let loaded = MetadataStore::load(temp.path()).unwrap();
+
+        assert_eq!(loaded, store);
+        let text = fs::read_to_string(MetadataStore::path(temp.path())).unwrap();
+        assert!(text.contains("[1000]"));
+        assert!(text.contains("solved = 2024-03-01"));
+    }
+
+    #[test]
+    fn empty_entries_are_not_written() {
+        let mut store = MetadataStore::default();
+        store.entry(1000);
+        assert_eq!(store.to_toml(), "");
+    }
+
+    #[test]
+    fn load_rejects_invalid_documents() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir(temp.path().join(STATE_DIR)).unwrap();
+        let path = MetadataStore::path(temp.path());
+
+        for text in ["[abc]\ntitle = \"x\"\n", "[1000]\nstatus = \"maybe\"\n"] {
+            fs::write(&path, text).unwrap();
+            let result = MetadataStore::load(temp.path());
+            assert!(matches!(result, Err(Error::InvalidMetadata { .. })));
+        }
+    }
+
+    #[test]
+    fn parse_date_accepts_local_dates_only() {
+        let date = parse_date("2024-03-01").unwrap();
+        assert_eq!((date.year, date.month, date.day), (2024, 3, 1));
+        assert!(parse_date("2024-03-01T10:00:00").is_err());
+        assert!(parse_date("yesterday").is_err());
+    }
+
+    #[test]
+    fn date_from_days_matches_calendar() {
+        let date = date_from_days(0);
+        assert_eq!((date.year, date.month, date.day), (1970, 1, 1));
+        let date = date_from_days(19_783);
+        assert_eq!((date.year, date.month, date.day), (2024, 3, 1));
+        let date = date_from_days(11_016);
+        assert_eq!((date.year, date.month, date.day), (2000, 2, 29));
+    }
+}
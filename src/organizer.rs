@@ -0,0 +1,1560 @@
+//! 정리 작업의 단일 진입점.
+//!
+//! [`Organizer`]는 스캔, 계획, 실행 단계를 묶어 결과 구조체로 돌려줍니다.
+//! 화면 출력은 하지 않으며, 진행 상황은 빌더에 등록한 [`Observer`]로만 전달합니다.
+//!
+//! ```no_run
+//! use ps_organizer::organizer::Organizer;
+//!
+//! let mut organizer = Organizer::builder("./solutions").threshold(30).build()?;
+//! let scan = organizer.scan();
+//! let plan = organizer.plan(&scan);
+//! let report = organizer.execute(&plan)?;
+//! println!("{} moved", report.moved.len());
+//! # Ok::<(), ps_organizer::types::Error>(())
+//! ```
+
+use crate::archive::{self, ArchiveFormat};
+use crate::events::{Event, NoopObserver, Observer};
+use crate::executor::{
+    CleanupRules, Link, cleanup_affected_dirs_in, collect_links, execute_moves_in,
+    retarget_links_in,
+};
+use crate::export::{self, ExportFilter};
+use crate::extension::ExtensionPolicy;
+use crate::import::{
+    self, ConflictPolicy, DuplicatePolicy, ImportOptions, ImportPlan, transfer_files_in,
+};
+use crate::keys::{self, DateSource, GroupBy, KeyExtractor};
+use crate::metadata::{self, MetadataStore, STATE_DIR};
+use crate::planner::{PlanOptions, conflict_base, plan_moves_into, plan_moves_with};
+use crate::scaffold;
+pub use crate::scanner::ScanReport;
+use crate::scanner::{ScanOptions, SymlinkPolicy, scan_directory_in, with_jobs};
+use crate::simulate::{Simulation, simulate_in};
+use crate::types::{Error, FileEntry, MoveOperation};
+use crate::vfs::{FileSystem, RealFs};
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsStr;
+use std::hash::BuildHasher;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+/// 폴더 구조를 정하는 방식.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Strategy {
+    /// 파일 수가 임계값을 넘으면 번호 범위로 자동 그룹핑.
+    #[default]
+    Adaptive,
+    /// 그룹 폴더 없이 번호 파일을 모두 루트에 둠.
+    Flat,
+}
+
+/// 실행 옵션.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// 실행 후 이번 이동으로 비게 된 폴더를 삭제할지 여부.
+    pub cleanup_empty_dirs: bool,
+    /// 빈 폴더 정리 규칙.
+    pub cleanup_rules: CleanupRules,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            cleanup_empty_dirs: true,
+            cleanup_rules: CleanupRules::default(),
+        }
+    }
+}
+
+/// 이동 계획.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Plan {
+    /// 계획의 바탕이 된 소스 파일 목록.
+    pub entries: Vec<FileEntry>,
+    /// 실제로 필요한 이동 목록.
+    pub moves: Vec<MoveOperation>,
+    /// 이름 충돌로 파일명이 바뀐 이동 목록.
+    pub conflicts: Vec<MoveOperation>,
+}
+
+/// 실행 결과.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExecutionReport {
+    /// 완료된 이동 목록.
+    pub moved: Vec<MoveOperation>,
+    /// 새로 만든 폴더 목록 (상대 경로).
+    pub created_dirs: Vec<PathBuf>,
+    /// 삭제한 빈 폴더 목록 (상대 경로).
+    pub removed_dirs: Vec<PathBuf>,
+    /// 다른 디렉토리에서 가져온 파일 목록. `from`은 원본 디렉토리 기준입니다.
+    pub imported: Vec<MoveOperation>,
+}
+
+impl ExecutionReport {
+    fn record(&mut self, event: &Event) {
+        match event {
+            Event::Moved { from, to } => self
+                .moved
+                .push(MoveOperation::new(from.clone(), to.clone())),
+            Event::Imported { from, to } => self
+                .imported
+                .push(MoveOperation::new(from.clone(), to.clone())),
+            Event::DirCreated { path } => self.created_dirs.push(path.clone()),
+            Event::DirRemoved { path } => self.removed_dirs.push(path.clone()),
+            _ => {}
+        }
+    }
+}
+
+/// [`Organizer`] 빌더.
+pub struct OrganizerBuilder<'a> {
+    root: PathBuf,
+    threshold: usize,
+    strategy: Strategy,
+    group_by: GroupBy,
+    date_source: DateSource,
+    scan_options: ScanOptions,
+    options: Options,
+    metadata: Option<MetadataStore>,
+    observer: Box<dyn Observer + 'a>,
+    fs: &'a dyn FileSystem,
+}
+
+impl<'a> OrganizerBuilder<'a> {
+    /// 그룹핑 임계값을 지정합니다 (기본값: 20).
+    #[must_use]
+    pub const fn threshold(mut self, threshold: usize) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// 폴더 구조 방식을 지정합니다.
+    #[must_use]
+    pub const fn strategy(mut self, strategy: Strategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// 상위 폴더를 정하는 기준을 지정합니다 (기본값: 번호 범위).
+    ///
+    /// 태그, 난이도, 날짜 기준이면 키 폴더 안에서 번호 범위 그룹핑을 적용합니다.
+    #[must_use]
+    pub const fn group_by(mut self, group_by: GroupBy) -> Self {
+        self.group_by = group_by;
+        self
+    }
+
+    /// 날짜 기준 그룹핑에서 푼 날짜가 없는 파일의 날짜 출처를 지정합니다.
+    #[must_use]
+    pub const fn date_source(mut self, source: DateSource) -> Self {
+        self.date_source = source;
+        self
+    }
+
+    /// 수집할 확장자 목록을 지정합니다 (점 제외).
+    #[must_use]
+    pub fn extensions<I, S>(mut self, extensions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.scan_options.extensions = extensions.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// 확장자 인식 규칙을 지정합니다 (기본값: 대소문자 무시, 기본 별칭 사용).
+    #[must_use]
+    pub fn extension_policy(mut self, policy: ExtensionPolicy) -> Self {
+        self.scan_options.extension_policy = policy;
+        self
+    }
+
+    /// 심볼릭 링크 처리 방식을 지정합니다 (기본값: 무시).
+    #[must_use]
+    pub const fn symlinks(mut self, policy: SymlinkPolicy) -> Self {
+        self.scan_options.symlinks = policy;
+        self
+    }
+
+    /// 문제 번호를 찾을 머리 주석 줄 수를 지정합니다 (기본값: 0, 읽지 않음).
+    #[must_use]
+    pub const fn header_lines(mut self, lines: usize) -> Self {
+        self.scan_options.header_lines = lines;
+        self
+    }
+
+    /// 증분 스캔 캐시를 쓸지 지정합니다 (기본값: 사용 안 함).
+    ///
+    /// 캐시는 대상 디렉토리의 `.ps-organizer/scan-cache.json`에 저장합니다.
+    #[must_use]
+    pub const fn cache(mut self, enabled: bool) -> Self {
+        self.scan_options.cache = enabled;
+        self
+    }
+
+    /// 스캔과 가져오기의 중복 비교에 쓸 작업 스레드 수를 지정합니다 (기본값: 1, 0이면
+    /// CPU 수만큼).
+    ///
+    /// 캐시를 쓰는 스캔도 새로 읽을 디렉토리와 파일을 나눠 처리합니다. 스레드 수와
+    /// 관계없이 스캔 결과는 경로 순으로 정렬되어 같게 나옵니다.
+    #[must_use]
+    pub const fn jobs(mut self, jobs: usize) -> Self {
+        self.scan_options.jobs = jobs;
+        self
+    }
+
+    /// 실행 옵션을 지정합니다.
+    #[must_use]
+    pub fn options(mut self, options: Options) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// 문제 메타데이터를 지정합니다.
+    ///
+    /// 지정하지 않으면 대상 디렉토리의 저장소 파일을 읽습니다.
+    #[must_use]
+    pub fn metadata(mut self, metadata: MetadataStore) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// 진행 이벤트를 받을 관찰자를 지정합니다.
+    #[must_use]
+    pub fn observer(mut self, observer: impl Observer + 'a) -> Self {
+        self.observer = Box::new(observer);
+        self
+    }
+
+    /// 스캔, 실행, 정리, 시뮬레이션에 쓸 파일 시스템을 지정합니다 (기본값: [`RealFs`]).
+    #[must_use]
+    pub fn filesystem(mut self, fs: &'a dyn FileSystem) -> Self {
+        self.fs = fs;
+        self
+    }
+
+    /// 설정을 검증하고 [`Organizer`]를 생성합니다.
+    ///
+    /// # Errors
+    ///
+    /// 대상 경로가 디렉토리가 아니면 [`Error::InvalidRoot`]를, 메타데이터
+    /// 저장소를 읽을 수 없으면 해당 에러를 반환합니다.
+    pub fn build(self) -> Result<Organizer<'a>, Error> {
+        if !self.fs.metadata(&self.root).is_ok_and(|m| m.is_dir()) {
+            return Err(Error::InvalidRoot { path: self.root });
+        }
+        let root = self.fs.canonicalize(&self.root).unwrap_or(self.root);
+        let metadata = match self.metadata {
+            Some(metadata) => metadata,
+            None => MetadataStore::load_in(self.fs, &root)?,
+        };
+
+        Ok(Organizer {
+            root,
+            threshold: self.threshold,
+            strategy: self.strategy,
+            group_by: self.group_by,
+            date_source: self.date_source,
+            scan_options: self.scan_options,
+            options: self.options,
+            metadata,
+            observer: self.observer,
+            fs: self.fs,
+        })
+    }
+}
+
+/// 스캔, 계획, 실행을 담당하는 정리 작업 진입점.
+pub struct Organizer<'a> {
+    root: PathBuf,
+    threshold: usize,
+    strategy: Strategy,
+    group_by: GroupBy,
+    date_source: DateSource,
+    scan_options: ScanOptions,
+    options: Options,
+    metadata: MetadataStore,
+    observer: Box<dyn Observer + 'a>,
+    fs: &'a dyn FileSystem,
+}
+
+impl<'a> Organizer<'a> {
+    /// 대상 디렉토리로 빌더를 시작합니다.
+    pub fn builder(root: impl Into<PathBuf>) -> OrganizerBuilder<'a> {
+        OrganizerBuilder {
+            root: root.into(),
+            threshold: 20,
+            strategy: Strategy::default(),
+            group_by: GroupBy::default(),
+            date_source: DateSource::default(),
+            scan_options: ScanOptions::default(),
+            options: Options::default(),
+            metadata: None,
+            observer: Box::new(NoopObserver),
+            fs: &RealFs,
+        }
+    }
+
+    /// 정규화된 대상 디렉토리 경로.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// 문제 메타데이터 저장소.
+    pub const fn metadata(&self) -> &MetadataStore {
+        &self.metadata
+    }
+
+    /// 전략을 반영한 실제 그룹핑 임계값.
+    pub const fn effective_threshold(&self) -> usize {
+        match self.strategy {
+            Strategy::Adaptive => self.threshold,
+            Strategy::Flat => usize::MAX,
+        }
+    }
+
+    /// 전략과 확장자 규칙을 반영한 계획 옵션.
+    pub fn plan_options(&self) -> PlanOptions {
+        PlanOptions {
+            threshold: self.effective_threshold(),
+            normalize_extensions: self.scan_options.extension_policy.normalize_on_move,
+        }
+    }
+
+    /// 그룹 기준에 맞는 키 추출기를 만듭니다.
+    pub fn key_extractor(&self, scan: &ScanReport) -> Box<dyn KeyExtractor + '_> {
+        keys::extractor(
+            self.group_by,
+            self.date_source,
+            &self.root,
+            &scan.entries,
+            &self.metadata,
+        )
+    }
+
+    /// 대상 디렉토리를 스캔합니다.
+    ///
+    /// 읽을 수 없는 경로는 건너뛰고 [`ScanReport::errors`]에 모읍니다.
+    pub fn scan(&mut self) -> ScanReport {
+        scan_directory_in(
+            self.fs,
+            &self.root,
+            &self.scan_options,
+            self.observer.as_mut(),
+        )
+    }
+
+    /// 스캔이 갱신한 캐시를 저장합니다. 캐시를 쓰지 않은 스캔이면 아무것도 하지 않습니다.
+    ///
+    /// 스캔은 캐시를 저장하지 않으므로, 미리보기가 아닌 실제 실행을 마친 뒤에
+    /// 부릅니다. 저장한 캐시는 실행 전 트리를 담지만, 실행으로 바뀐 디렉토리는
+    /// 수정 시각이 달라져 다음 스캔에서 다시 읽습니다.
+    ///
+    /// # Errors
+    ///
+    /// 캐시 파일을 쓰지 못하면 에러를 반환합니다.
+    pub fn save_cache(&self, scan: &ScanReport) -> Result<(), Error> {
+        match &scan.cache {
+            Some(cache) => cache.save(self.fs, &self.root),
+            None => Ok(()),
+        }
+    }
+
+    /// 스캔 결과로 이동 계획을 세웁니다.
+    pub fn plan(&mut self, scan: &ScanReport) -> Plan {
+        self.plan_with_overrides(scan, &HashMap::new())
+    }
+
+    /// 일부 파일의 목적지를 고정한 채 이동 계획을 세웁니다.
+    ///
+    /// 자세한 규칙은 [`plan_moves_with`]를 참고하세요.
+    pub fn plan_with_overrides<S: BuildHasher>(
+        &mut self,
+        scan: &ScanReport,
+        overrides: &HashMap<PathBuf, PathBuf, S>,
+    ) -> Plan {
+        let options = self.plan_options();
+        let keys = keys::extractor(
+            self.group_by,
+            self.date_source,
+            &self.root,
+            &scan.entries,
+            &self.metadata,
+        );
+        let mut conflicts = Vec::new();
+        let observer = self.observer.as_mut();
+        let mut recorder = |event: &Event| {
+            if let Event::Conflict { from, to } = event {
+                conflicts.push(MoveOperation::new(from.clone(), to.clone()));
+            }
+            observer.on_event(event);
+        };
+
+        let moves = plan_moves_with(
+            &scan.entries,
+            &options,
+            keys.as_ref(),
+            overrides,
+            &mut recorder,
+        );
+
+        Plan {
+            entries: scan.entries.clone(),
+            moves,
+            conflicts,
+        }
+    }
+
+    /// 문제 번호 `number`의 `ext` 풀이 파일이 현재 트리에서 놓일 위치를 계산합니다.
+    ///
+    /// 자세한 규칙은 [`Organizer::placements`]를 참고하세요.
+    pub fn placement(&self, scan: &ScanReport, number: u32, ext: &str) -> PathBuf {
+        self.placements(scan, ext, [number])
+            .pop()
+            .map(|(_, path)| path)
+            .unwrap_or_default()
+    }
+
+    /// 여러 문제 번호의 `ext` 풀이 파일이 현재 트리에서 놓일 위치를 계산합니다.
+    ///
+    /// 스캔한 파일 가운데 정리 후 계산한 위치를 차지하는 파일이 있으면 그 파일의 현재
+    /// 경로를 돌려줍니다. 다른 폴더에 있는 같은 이름의 파일은 `_conflictN` 이름으로
+    /// 밀려나므로 고르지 않습니다. 없는 파일은 모두 만든다고 보고 스캔한 파일에 더해
+    /// 한 번만 계획을 세우므로, 파일이 늘어 그룹핑이 바뀌는 경우도 반영합니다. 날짜
+    /// 기준이면 오늘 날짜를 씁니다. 반환값은 `numbers` 순서이며 대상 디렉토리 기준
+    /// 상대 경로입니다.
+    pub fn placements(
+        &self,
+        scan: &ScanReport,
+        ext: &str,
+        numbers: impl IntoIterator<Item = u32>,
+    ) -> Vec<(u32, PathBuf)> {
+        let keys = self.key_extractor(scan);
+        let today = (self.group_by == GroupBy::Date).then(|| {
+            let date = metadata::today();
+            format!("{:04}/{:02}", date.year, date.month)
+        });
+        let pending_keys = |entry: &FileEntry| {
+            let key = keys.key(entry);
+            if entry.current_path.starts_with(STATE_DIR) {
+                key.or_else(|| today.clone())
+            } else {
+                key
+            }
+        };
+        let plan = |entries: &[FileEntry]| {
+            plan_moves_with(
+                entries,
+                &self.plan_options(),
+                &pending_keys,
+                &HashMap::new(),
+                &mut NoopObserver,
+            )
+        };
+
+        // 정리 후 경로의 파일 이름으로 찾으면, 그 이름을 차지하는 파일만 남습니다.
+        let moves = plan(&scan.entries);
+        let destinations: HashMap<&Path, &Path> = moves
+            .iter()
+            .map(|op| (op.from.as_path(), op.to.as_path()))
+            .collect();
+        let existing: HashMap<&OsStr, &Path> = scan
+            .entries
+            .iter()
+            .filter_map(|entry| {
+                let current = entry.current_path.as_path();
+                let destination = destinations.get(current).copied().unwrap_or(current);
+                Some((destination.file_name()?, current))
+            })
+            .collect();
+
+        let mut entries = scan.entries.clone();
+        let placements: Vec<(u32, PathBuf)> = numbers
+            .into_iter()
+            .map(|number| {
+                let filename = scaffold::file_name(number, ext, 1);
+                if let Some(path) = existing.get(OsStr::new(&filename)) {
+                    return (number, path.to_path_buf());
+                }
+                let pending = Path::new(STATE_DIR).join(&filename);
+                if !entries.iter().any(|entry| entry.current_path == pending) {
+                    entries.push(FileEntry::new(pending.clone(), Some(number)));
+                }
+                (number, pending)
+            })
+            .collect();
+        if entries.len() == scan.entries.len() {
+            return placements;
+        }
+
+        let moves = plan(&entries);
+        let planned: HashMap<&Path, &Path> = moves
+            .iter()
+            .map(|op| (op.from.as_path(), op.to.parent().unwrap_or(Path::new(""))))
+            .collect();
+
+        placements
+            .into_iter()
+            .map(|(number, path)| {
+                if !path.starts_with(STATE_DIR) {
+                    return (number, path);
+                }
+                let folder = planned
+                    .get(path.as_path())
+                    .copied()
+                    .unwrap_or(Path::new(""));
+                (number, folder.join(path.file_name().unwrap_or_default()))
+            })
+            .collect()
+    }
+
+    /// 이동 계획을 실행하고, 옵션에 따라 이번 이동으로 비게 된 폴더를 정리합니다.
+    ///
+    /// # Errors
+    ///
+    /// 폴더 생성, 파일 이동, 빈 폴더 삭제 중 하나라도 실패하면 에러를 반환합니다.
+    pub fn execute(&mut self, plan: &Plan) -> Result<ExecutionReport, Error> {
+        let links = self.followed_links();
+        let mut report = ExecutionReport::default();
+        let observer = self.observer.as_mut();
+        let mut recorder = |event: &Event| {
+            report.record(event);
+            observer.on_event(event);
+        };
+
+        execute_moves_in(self.fs, &self.root, &plan.moves, &mut recorder)?;
+        retarget_links_in(self.fs, &self.root, &links, &plan.moves, &mut recorder)?;
+        if self.options.cleanup_empty_dirs {
+            cleanup_affected_dirs_in(
+                self.fs,
+                &self.root,
+                &cleanup_moves(&plan.moves, &links),
+                &self.options.cleanup_rules,
+                &mut recorder,
+            )?;
+        }
+
+        Ok(report)
+    }
+
+    /// 링크를 따라가 스캔한다면, 대상 디렉토리 안을 가리키는 링크를 모두 찾습니다.
+    fn followed_links(&self) -> Vec<Link> {
+        if self.scan_options.symlinks == SymlinkPolicy::Follow {
+            collect_links(self.fs, &self.root)
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// 이동 계획을 실행하지 않고, 실행했을 때 실패할 작업을 모두 찾습니다.
+    ///
+    /// 옵션에 따라 빈 폴더 정리까지 흉내 냅니다. 파일 시스템은 바꾸지 않으며
+    /// 이벤트도 보고하지 않습니다. 자세한 내용은 [`simulate_in`]을 보세요.
+    #[must_use]
+    pub fn simulate(&self, plan: &Plan) -> Simulation {
+        let cleanup = self
+            .options
+            .cleanup_empty_dirs
+            .then_some(&self.options.cleanup_rules);
+        simulate_in(self.fs, &self.root, &plan.moves, cleanup)
+    }
+
+    /// 가져올 원본 디렉토리를 대상과 같은 스캔 옵션으로 스캔합니다.
+    ///
+    /// 원본에는 스캔 캐시를 쓰지 않고, 스캔 이벤트도 보고하지 않습니다. 항목의
+    /// 경로는 원본 디렉토리 기준입니다.
+    ///
+    /// # Errors
+    ///
+    /// 원본이 디렉토리가 아니면 [`Error::InvalidRoot`]를, 원본과 대상 디렉토리가
+    /// 서로를 포함하면 [`Error::Io`]를 반환합니다.
+    pub fn scan_source(&self, source: &Path) -> Result<ScanReport, Error> {
+        let source = self.source_dir(source)?;
+        let options = ScanOptions {
+            cache: false,
+            ..self.scan_options.clone()
+        };
+        Ok(scan_directory_in(
+            self.fs,
+            &source,
+            &options,
+            &mut NoopObserver,
+        ))
+    }
+
+    fn source_dir(&self, source: &Path) -> Result<PathBuf, Error> {
+        if !self.fs.metadata(source).is_ok_and(|m| m.is_dir()) {
+            return Err(Error::InvalidRoot {
+                path: source.to_path_buf(),
+            });
+        }
+        let source = self
+            .fs
+            .canonicalize(source)
+            .unwrap_or_else(|_| source.to_path_buf());
+        if source.starts_with(&self.root) || self.root.starts_with(&source) {
+            return Err(Error::Io {
+                path: source,
+                source: io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "source and target directories overlap",
+                ),
+            });
+        }
+        Ok(source)
+    }
+
+    /// 원본 디렉토리의 파일을 대상 트리로 가져오는 계획을 세웁니다.
+    ///
+    /// 가져올 파일까지 더한 트리로 폴더 구조를 정하므로, 가져온 뒤 임계값을 넘는
+    /// 폴더는 [`ImportPlan::rebalance`]에서 다시 나눕니다. 대상 트리의 파일이 먼저
+    /// 이름을 가지고, 가져올 파일은 원본 경로의 깊이와 사전순으로 이름을 정합니다.
+    /// 이름이 겹치면 [`ConflictPolicy`]에 따라 `_conflictN` 이름을 받거나 건너뛰고,
+    /// 내용이 같은 풀이가 이미 있으면 [`DuplicatePolicy`]에 따라 건너뜁니다.
+    ///
+    /// 대상 트리의 이동은 [`Organizer::plan`]과 같이 보고하고, 건너뛴 파일마다
+    /// [`Event::Duplicate`]나 [`Event::ImportSkipped`]를 보고합니다.
+    ///
+    /// # Errors
+    ///
+    /// [`Organizer::scan_source`]와 같습니다.
+    pub fn plan_import(
+        &mut self,
+        scan: &ScanReport,
+        source: &Path,
+        incoming: &ScanReport,
+        options: &ImportOptions,
+    ) -> Result<ImportPlan, Error> {
+        let mut plan = ImportPlan {
+            source: self.source_dir(source)?,
+            mode: options.mode,
+            ..ImportPlan::default()
+        };
+
+        // 가져올 파일은 대상 트리의 파일과 겹치지 않는 상태 폴더 아래에 있는 것으로 둡니다.
+        let staging = Path::new(STATE_DIR).join("import");
+        let pending: Vec<FileEntry> = self
+            .drop_duplicates(scan, incoming, options.duplicates, &mut plan)
+            .into_iter()
+            .map(|mut entry| {
+                entry.current_path = staging.join(&entry.current_path);
+                entry
+            })
+            .collect();
+
+        let plan_options = self.plan_options();
+        let target_keys = keys::extractor(
+            self.group_by,
+            self.date_source,
+            &self.root,
+            &scan.entries,
+            &self.metadata,
+        );
+        let incoming_keys = keys::extractor(
+            self.group_by,
+            self.date_source,
+            &plan.source,
+            &incoming.entries,
+            &self.metadata,
+        );
+        let combined_keys = |entry: &FileEntry| match entry.current_path.strip_prefix(&staging) {
+            Ok(original) => {
+                let mut original_entry = entry.clone();
+                original_entry.current_path = original.to_path_buf();
+                incoming_keys.key(&original_entry)
+            }
+            Err(_) => target_keys.key(entry),
+        };
+        let entries: Vec<FileEntry> = scan.entries.iter().chain(&pending).cloned().collect();
+
+        // 가져올 파일까지 세어 폴더 구조를 정한 뒤, 가져올 파일은 제자리에 고정하고
+        // 대상 트리의 파일만 다시 배치합니다.
+        let layout = plan_moves_with(
+            &entries,
+            &plan_options,
+            &combined_keys,
+            &HashMap::new(),
+            &mut NoopObserver,
+        );
+        let pinned: HashMap<PathBuf, PathBuf> = pending
+            .iter()
+            .map(|e| (e.current_path.clone(), e.current_path.clone()))
+            .collect();
+        let mut conflicts = Vec::new();
+        let observer = self.observer.as_mut();
+        let mut recorder = |event: &Event| {
+            if let Event::Conflict { from, to } = event {
+                conflicts.push(MoveOperation::new(from.clone(), to.clone()));
+            }
+            observer.on_event(event);
+        };
+        let moves = plan_moves_with(
+            &entries,
+            &plan_options,
+            &combined_keys,
+            &pinned,
+            &mut recorder,
+        );
+
+        // 다시 나눈 뒤 대상 트리의 파일이 차지하는 경로와, 비워지지 않는 기존 항목은
+        // 가져올 파일이 쓰지 않습니다.
+        let vacated: HashSet<&Path> = moves.iter().map(|op| op.from.as_path()).collect();
+        let taken: HashSet<&Path> = scan
+            .entries
+            .iter()
+            .map(|e| e.current_path.as_path())
+            .filter(|path| !vacated.contains(path))
+            .chain(moves.iter().map(|op| op.to.as_path()))
+            .collect();
+        let (fs, root) = (self.fs, &self.root);
+        let is_taken = |path: &Path| {
+            taken.contains(path)
+                || (!vacated.contains(path) && fs.symlink_metadata(&root.join(path)).is_ok())
+        };
+        let wanted = layout
+            .iter()
+            .filter_map(|op| {
+                let original = op.from.strip_prefix(&staging).ok()?;
+                let entry = pending.iter().find(|e| e.current_path == op.from)?;
+                let folder = op.to.parent().unwrap_or_else(|| Path::new(""));
+                let filename = plan_options.target_filename(entry);
+                Some(MoveOperation::new(
+                    original.to_path_buf(),
+                    folder.join(filename),
+                ))
+            })
+            .collect();
+        import::assign_names(&mut plan, wanted, is_taken, options.conflicts, observer);
+        plan.rebalance = Plan {
+            entries: scan.entries.clone(),
+            moves,
+            conflicts,
+        };
+        Ok(plan)
+    }
+
+    /// 대상 트리에 내용이 같은 풀이가 있는 파일을 계획의 중복 목록으로 옮기고,
+    /// 나머지 파일을 돌려줍니다.
+    fn drop_duplicates(
+        &mut self,
+        scan: &ScanReport,
+        incoming: &ScanReport,
+        policy: DuplicatePolicy,
+        plan: &mut ImportPlan,
+    ) -> Vec<FileEntry> {
+        if policy == DuplicatePolicy::Keep {
+            return incoming.entries.clone();
+        }
+        // 이름을 정할 때와 같은 순서로 보아, 원본 안에서 겹치면 얕은 경로의 파일을 남깁니다.
+        let mut entries: Vec<&FileEntry> = incoming.entries.iter().collect();
+        entries
+            .sort_by_cached_key(|e| (e.current_path.components().count(), e.current_path.clone()));
+
+        // 같은 풀이일 수 있는 파일은 작업 스레드에서 한 번씩 해시해 두고, 해시가 같을
+        // 때만 내용을 바이트 단위로 비교합니다.
+        let keys: HashSet<_> = entries.iter().map(|e| import::duplicate_key(e)).collect();
+        let paths: Vec<PathBuf> = entries
+            .iter()
+            .map(|e| plan.source.join(&e.current_path))
+            .chain(
+                scan.entries
+                    .iter()
+                    .filter(|e| keys.contains(&import::duplicate_key(e)))
+                    .map(|e| self.root.join(&e.current_path)),
+            )
+            .collect();
+        let fs = self.fs;
+        let hashes: HashMap<PathBuf, Option<u64>> = with_jobs(self.scan_options.jobs, |parallel| {
+            let hash = |path: PathBuf| {
+                let hash = import::content_hash(fs, &path);
+                (path, hash)
+            };
+            if parallel {
+                paths.into_par_iter().map(hash).collect()
+            } else {
+                paths.into_iter().map(hash).collect()
+            }
+        });
+        let hash_of = |path: &Path| hashes.get(path).copied().flatten();
+
+        let mut kept: Vec<FileEntry> = Vec::new();
+        for entry in entries {
+            let key = import::duplicate_key(entry);
+            let path = plan.source.join(&entry.current_path);
+            let hash = hash_of(&path);
+            let same = |dir: &Path, e: &FileEntry| {
+                let other = dir.join(&e.current_path);
+                import::duplicate_key(e) == key
+                    && hash.is_some()
+                    && hash_of(&other) == hash
+                    && import::same_contents(self.fs, &path, &other)
+            };
+            let existing = scan
+                .entries
+                .iter()
+                .find(|e| same(&self.root, e))
+                .or_else(|| kept.iter().find(|e| same(&plan.source, e)));
+            match existing {
+                Some(existing) => {
+                    self.observer.on_event(&Event::Duplicate {
+                        path: entry.current_path.clone(),
+                        existing: existing.current_path.clone(),
+                    });
+                    plan.duplicates.push(MoveOperation::new(
+                        entry.current_path.clone(),
+                        existing.current_path.clone(),
+                    ));
+                }
+                None => kept.push(entry.clone()),
+            }
+        }
+        kept
+    }
+
+    /// 가져오기 계획을 실행합니다.
+    ///
+    /// 대상 트리를 먼저 다시 나눈 뒤 파일을 가져오고, 옵션에 따라 다시 나누면서
+    /// 비게 된 폴더를 정리합니다.
+    ///
+    /// # Errors
+    ///
+    /// 폴더 생성, 파일 이동이나 가져오기, 빈 폴더 삭제 중 하나라도 실패하면 에러를
+    /// 반환합니다.
+    pub fn execute_import(&mut self, plan: &ImportPlan) -> Result<ExecutionReport, Error> {
+        let mut report = ExecutionReport::default();
+        let observer = self.observer.as_mut();
+        let mut recorder = |event: &Event| {
+            report.record(event);
+            observer.on_event(event);
+        };
+
+        execute_moves_in(self.fs, &self.root, &plan.rebalance.moves, &mut recorder)?;
+        transfer_files_in(
+            self.fs,
+            &plan.source,
+            &self.root,
+            &plan.transfers,
+            plan.mode,
+            &mut recorder,
+        )?;
+        if self.options.cleanup_empty_dirs {
+            cleanup_affected_dirs_in(
+                self.fs,
+                &self.root,
+                &plan.rebalance.moves,
+                &self.options.cleanup_rules,
+                &mut recorder,
+            )?;
+        }
+
+        Ok(report)
+    }
+
+    /// 스캔한 풀이를 모두 한 폴더로 되돌리는 계획을 세웁니다.
+    ///
+    /// `into`는 모을 폴더이며, 빈 경로면 루트입니다. 이름이 겹치면
+    /// [`plan_moves_with`]와 같은 순서로 앞선 파일이 원래 이름을 가지고, 나머지는
+    /// `policy`에 따라 `_conflictN` 이름을 받거나 제자리에 남습니다. 실행은
+    /// [`Organizer::execute`]로 하며, 옵션에 따라 비게 된 그룹 폴더를 정리합니다.
+    ///
+    /// 계획 이벤트는 [`Organizer::plan`]과 같이 보고하고, 제자리에 남는 파일마다
+    /// [`Event::FlattenSkipped`]를 보고합니다.
+    ///
+    /// # Errors
+    ///
+    /// `into`가 대상 디렉토리 밖이면 [`Error::Io`]를 반환합니다.
+    pub fn plan_flatten(
+        &mut self,
+        scan: &ScanReport,
+        into: &Path,
+        policy: ConflictPolicy,
+    ) -> Result<Plan, Error> {
+        let folder = self.flatten_dir(into)?;
+        let mut events = Vec::new();
+        let mut moves = plan_moves_into(
+            &scan.entries,
+            &self.plan_options(),
+            &folder,
+            &mut |event: &Event| events.push(event.clone()),
+        );
+
+        // 충돌 이벤트가 계획 이벤트보다 먼저 오므로, 건너뛸 파일을 먼저 알 수 있습니다.
+        let mut conflicts = Vec::new();
+        let mut skipped = HashSet::new();
+        for event in events {
+            match event {
+                Event::Conflict { from, to } if policy == ConflictPolicy::Skip => {
+                    let wanted =
+                        to.with_file_name(conflict_base(to.file_name().unwrap_or_default()));
+                    self.observer.on_event(&Event::FlattenSkipped {
+                        path: from.clone(),
+                        to: wanted,
+                    });
+                    skipped.insert(from);
+                }
+                Event::Planned { ref from, .. } if skipped.contains(from) => {}
+                event => {
+                    if let Event::Conflict { from, to } = &event {
+                        conflicts.push(MoveOperation::new(from.clone(), to.clone()));
+                    }
+                    self.observer.on_event(&event);
+                }
+            }
+        }
+        moves.retain(|op| !skipped.contains(&op.from));
+
+        Ok(Plan {
+            entries: scan.entries.clone(),
+            moves,
+            conflicts,
+        })
+    }
+
+    /// 모을 폴더를 대상 디렉토리 기준 상대 경로로 바꿉니다.
+    fn flatten_dir(&self, into: &Path) -> Result<PathBuf, Error> {
+        let relative = if into.is_absolute() {
+            into.strip_prefix(&self.root).ok()
+        } else {
+            Some(into)
+        };
+        match relative {
+            Some(relative)
+                if relative
+                    .components()
+                    .all(|part| matches!(part, Component::Normal(_) | Component::CurDir)) =>
+            {
+                Ok(relative
+                    .components()
+                    .filter(|part| matches!(part, Component::Normal(_)))
+                    .collect())
+            }
+            _ => Err(Error::Io {
+                path: into.to_path_buf(),
+                source: io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "flatten directory must be inside the target directory",
+                ),
+            }),
+        }
+    }
+
+    /// 조건에 맞는 풀이와 정리 후 경로를 고릅니다.
+    ///
+    /// 스캔한 파일 전체로 [`Organizer::plan`]과 같은 계획을 세운 뒤 조건에 맞는 파일만
+    /// 고르므로, 압축 파일 안의 배치는 정리했을 때의 트리와 같습니다. 반환값은 현재
+    /// 경로와 정리 후 경로의 쌍이며, 정리 후 경로순으로 정렬됩니다. 계획 이벤트는
+    /// [`Organizer::plan`]과 같이 보고합니다.
+    pub fn plan_export(&mut self, scan: &ScanReport, filter: &ExportFilter) -> Vec<MoveOperation> {
+        let plan = self.plan(scan);
+        let planned: HashMap<&Path, &Path> = plan
+            .moves
+            .iter()
+            .map(|op| (op.from.as_path(), op.to.as_path()))
+            .collect();
+        let mut files: Vec<MoveOperation> = scan
+            .entries
+            .iter()
+            .filter(|entry| filter.matches(entry, &self.metadata))
+            .map(|entry| {
+                let to = planned
+                    .get(entry.current_path.as_path())
+                    .map_or_else(|| entry.current_path.clone(), |to| to.to_path_buf());
+                MoveOperation::new(entry.current_path.clone(), to)
+            })
+            .collect();
+        files.sort_by(|a, b| a.to.cmp(&b.to));
+        files
+    }
+
+    /// [`Organizer::plan_export`]로 고른 파일을 정리 후 경로로 담은 압축 파일을 만듭니다.
+    ///
+    /// 작업 트리는 바꾸지 않습니다. 같은 파일이면 항상 같은 바이트가 나옵니다.
+    ///
+    /// # Errors
+    ///
+    /// 파일을 읽지 못하거나, 경로가 UTF-8이 아니거나, 압축 형식이 나타낼 수 없는
+    /// 경로나 크기면 에러를 반환합니다.
+    pub fn export(&self, files: &[MoveOperation], format: ArchiveFormat) -> Result<Vec<u8>, Error> {
+        let files = export::read_files_in(self.fs, &self.root, files)?;
+        archive::write_archive(format, &files).map_err(|source| Error::Io {
+            path: self.root.clone(),
+            source,
+        })
+    }
+}
+
+/// 빈 폴더 정리에서 살펴볼 이동.
+///
+/// 링크가 가리키는 폴더 안에서 나온 이동은 빼서, 그 폴더가 지워져 링크가 깨지지
+/// 않게 합니다.
+fn cleanup_moves(moves: &[MoveOperation], links: &[Link]) -> Vec<MoveOperation> {
+    moves
+        .iter()
+        .filter(|op| !links.iter().any(|link| link.keeps_dir(&op.from)))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vfs::memory_with;
+    use std::fs::{self, File};
+    use tempfile::TempDir;
+
+    fn touch(root: &Path, name: &str) {
+        let path = root.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        File::create(path).unwrap();
+    }
+
+    #[test]
+    fn build_rejects_missing_root() {
+        let temp = TempDir::new().unwrap();
+        let result = Organizer::builder(temp.path().join("missing")).build();
+        assert!(matches!(result, Err(Error::InvalidRoot { .. })));
+    }
+
+    #[test]
+    fn build_loads_metadata_and_scan_skips_state_dir() {
+        let temp = TempDir::new().unwrap();
+        touch(temp.path(), "1000.cpp");
+        touch(temp.path(), ".ps-organizer/1001.cpp");
+        fs::write(
+            MetadataStore::path(temp.path()),
+            "[1000]\ntitle = \"A+B\"\n",
+        )
+        .unwrap();
+
+        let mut organizer = Organizer::builder(temp.path()).build().unwrap();
+        let scan = organizer.scan();
+
+        assert_eq!(
+            organizer.metadata().get(1000).unwrap().title.as_deref(),
+            Some("A+B")
+        );
+        assert_eq!(scan.entries.len(), 1);
+    }
+
+    #[test]
+    fn group_by_tag_uses_metadata() {
+        let temp = TempDir::new().unwrap();
+        touch(temp.path(), "1000.cpp");
+        touch(temp.path(), "1001.cpp");
+        let mut metadata = MetadataStore::default();
+        metadata.entry(1000).tags = vec!["dp".to_string()];
+
+        let mut organizer = Organizer::builder(temp.path())
+            .group_by(GroupBy::Tag)
+            .metadata(metadata)
+            .build()
+            .unwrap();
+        let scan = organizer.scan();
+        let plan = organizer.plan(&scan);
+
+        assert_eq!(
+            plan.moves,
+            vec![MoveOperation::new(
+                PathBuf::from("1000.cpp"),
+                PathBuf::from("dp/1000.cpp")
+            )]
+        );
+    }
+
+    #[test]
+    fn placement_follows_grouping_with_new_file() {
+        let temp = TempDir::new().unwrap();
+        touch(temp.path(), "1000.cpp");
+        touch(temp.path(), "1001.cpp");
+
+        let mut organizer = Organizer::builder(temp.path())
+            .threshold(2)
+            .build()
+            .unwrap();
+        let scan = organizer.scan();
+        let expected = crate::planner::plan_moves(
+            &[
+                FileEntry::new(PathBuf::from("1000.cpp"), Some(1000)),
+                FileEntry::new(PathBuf::from("1001.cpp"), Some(1001)),
+                FileEntry::new(PathBuf::from("1002.cpp"), Some(1002)),
+            ],
+            2,
+        );
+
+        let placement = organizer.placement(&scan, 1002, "cpp");
+
+        assert_ne!(placement, PathBuf::from("1002.cpp"));
+        assert!(expected.iter().any(|op| op.to == placement));
+
+        let organizer = Organizer::builder(temp.path())
+            .threshold(3)
+            .build()
+            .unwrap();
+        assert_eq!(
+            organizer.placement(&scan, 1002, "cpp"),
+            PathBuf::from("1002.cpp")
+        );
+    }
+
+    #[test]
+    fn placement_ignores_same_named_file_in_other_folder() {
+        let fs = memory_with(&[
+            ("/target/1010.cpp", ""),
+            ("/target/backup/1010.cpp", ""),
+            ("/target/old/1011.cpp", ""),
+            ("/target/backup/1011.cpp", ""),
+        ]);
+        let mut organizer = Organizer::builder("/target")
+            .filesystem(&fs)
+            .build()
+            .unwrap();
+        let scan = organizer.scan();
+
+        assert_eq!(
+            organizer.placements(&scan, "cpp", [1010, 1011]),
+            [
+                (1010, PathBuf::from("1010.cpp")),
+                (1011, PathBuf::from("backup/1011.cpp")),
+            ]
+        );
+    }
+
+    #[test]
+    fn placement_uses_metadata_key() {
+        let temp = TempDir::new().unwrap();
+        let mut metadata = MetadataStore::default();
+        metadata.entry(1000).tags = vec!["dp".to_string()];
+
+        let mut organizer = Organizer::builder(temp.path())
+            .group_by(GroupBy::Tag)
+            .metadata(metadata)
+            .build()
+            .unwrap();
+        let scan = organizer.scan();
+
+        assert_eq!(
+            organizer.placement(&scan, 1000, "cpp"),
+            PathBuf::from("dp/1000.cpp")
+        );
+    }
+
+    #[test]
+    fn extensions_limit_scan() {
+        let temp = TempDir::new().unwrap();
+        touch(temp.path(), "1001.cpp");
+        touch(temp.path(), "1002.rs");
+
+        let mut organizer = Organizer::builder(temp.path())
+            .extensions(["rs"])
+            .build()
+            .unwrap();
+        let scan = organizer.scan();
+
+        assert_eq!(scan.entries.len(), 1);
+        assert_eq!(scan.entries[0].problem_number, Some(1002));
+    }
+
+    #[test]
+    fn flat_strategy_never_groups() {
+        let temp = TempDir::new().unwrap();
+        for n in 1001..=1050 {
+            touch(temp.path(), &format!("{n}.cpp"));
+        }
+
+        let mut organizer = Organizer::builder(temp.path())
+            .strategy(Strategy::Flat)
+            .build()
+            .unwrap();
+        let scan = organizer.scan();
+        let plan = organizer.plan(&scan);
+
+        assert!(plan.moves.is_empty());
+    }
+
+    #[test]
+    fn execute_reports_moves_and_directories() {
+        let temp = TempDir::new().unwrap();
+        touch(temp.path(), "old/1001.cpp");
+        touch(temp.path(), "1002.cpp");
+
+        let mut organizer = Organizer::builder(temp.path()).build().unwrap();
+        let scan = organizer.scan();
+        let plan = organizer.plan(&scan);
+        let report = organizer.execute(&plan).unwrap();
+
+        assert!(plan.conflicts.is_empty());
+        assert_eq!(report.moved, plan.moves);
+        assert_eq!(report.removed_dirs, vec![PathBuf::from("old")]);
+    }
+
+    #[test]
+    fn overrides_report_conflicts_and_events() {
+        let fs = memory_with(&[
+            ("/target/a/1001.cpp", ""),
+            ("/target/b/1001.cpp", ""),
+            ("/target/c/1001.cpp", ""),
+        ]);
+        let mut events = Vec::new();
+        let plan = {
+            let mut organizer = Organizer::builder("/target")
+                .filesystem(&fs)
+                .observer(|event: &Event| events.push(event.clone()))
+                .build()
+                .unwrap();
+            let scan = organizer.scan();
+            let overrides =
+                HashMap::from([(PathBuf::from("a/1001.cpp"), PathBuf::from("a/1001.cpp"))]);
+            organizer.plan_with_overrides(&scan, &overrides)
+        };
+
+        let conflict = MoveOperation::new(
+            PathBuf::from("c/1001.cpp"),
+            PathBuf::from("1001_conflict1.cpp"),
+        );
+        assert_eq!(plan.conflicts, vec![conflict.clone()]);
+        assert!(events.contains(&Event::Conflict {
+            from: conflict.from,
+            to: conflict.to,
+        }));
+        assert!(events.contains(&Event::Planned {
+            from: PathBuf::from("b/1001.cpp"),
+            to: PathBuf::from("1001.cpp"),
+        }));
+    }
+
+    #[test]
+    fn normalize_on_move_renames_extension() {
+        let temp = TempDir::new().unwrap();
+        touch(temp.path(), "1001.CXX");
+
+        let mut organizer = Organizer::builder(temp.path())
+            .extension_policy(ExtensionPolicy {
+                normalize_on_move: true,
+                ..ExtensionPolicy::default()
+            })
+            .build()
+            .unwrap();
+        let scan = organizer.scan();
+        let plan = organizer.plan(&scan);
+        organizer.execute(&plan).unwrap();
+
+        assert!(temp.path().join("1001.cpp").is_file());
+        assert!(!temp.path().join("1001.CXX").exists());
+    }
+
+    #[test]
+    fn strict_policy_ignores_capital_extension() {
+        let temp = TempDir::new().unwrap();
+        touch(temp.path(), "1001.CPP");
+
+        let mut organizer = Organizer::builder(temp.path())
+            .extension_policy(ExtensionPolicy::strict())
+            .build()
+            .unwrap();
+
+        assert!(organizer.scan().entries.is_empty());
+    }
+
+    #[test]
+    fn plan_import_keeps_target_names_and_skips_duplicates() {
+        let fs = memory_with(&[
+            ("/target/1000.cpp", "a"),
+            ("/target/1001.cpp", "b"),
+            ("/src/x/1000.cpp", "a"),
+            ("/src/1001.cpp", "other"),
+            ("/src/1002.py", "c"),
+        ]);
+        let mut organizer = Organizer::builder("/target")
+            .filesystem(&fs)
+            .build()
+            .unwrap();
+        let scan = organizer.scan();
+        let incoming = organizer.scan_source(Path::new("/src")).unwrap();
+
+        let plan = organizer
+            .plan_import(
+                &scan,
+                Path::new("/src"),
+                &incoming,
+                &ImportOptions::default(),
+            )
+            .unwrap();
+
+        let op = |from: &str, to: &str| MoveOperation::new(PathBuf::from(from), PathBuf::from(to));
+        assert_eq!(plan.duplicates, [op("x/1000.cpp", "1000.cpp")]);
+        assert_eq!(
+            plan.transfers,
+            [
+                op("1001.cpp", "1001_conflict1.cpp"),
+                op("1002.py", "1002.py")
+            ]
+        );
+        assert_eq!(plan.renamed, [op("1001.cpp", "1001_conflict1.cpp")]);
+        assert!(plan.rebalance.moves.is_empty());
+
+        let skip = ImportOptions {
+            conflicts: ConflictPolicy::Skip,
+            duplicates: DuplicatePolicy::Keep,
+            ..ImportOptions::default()
+        };
+        let plan = organizer
+            .plan_import(&scan, Path::new("/src"), &incoming, &skip)
+            .unwrap();
+        assert!(plan.duplicates.is_empty());
+        assert_eq!(
+            plan.skipped,
+            [op("1001.cpp", "1001.cpp"), op("x/1000.cpp", "1000.cpp")]
+        );
+        assert_eq!(plan.transfers, [op("1002.py", "1002.py")]);
+    }
+
+    #[test]
+    fn plan_import_skips_duplicates_within_source() {
+        let fs = memory_with(&[
+            ("/target/1000.cpp", "a"),
+            ("/src/sub/1002.cpp", "b"),
+            ("/src/1002.cpp", "b"),
+            ("/src/x/1000.cpp", "a"),
+            ("/src/y/1000.cpp", "other"),
+        ]);
+        let op = |from: &str, to: &str| MoveOperation::new(PathBuf::from(from), PathBuf::from(to));
+
+        for jobs in [1, 4] {
+            let mut organizer = Organizer::builder("/target")
+                .filesystem(&fs)
+                .jobs(jobs)
+                .build()
+                .unwrap();
+            let scan = organizer.scan();
+            let incoming = organizer.scan_source(Path::new("/src")).unwrap();
+
+            let plan = organizer
+                .plan_import(
+                    &scan,
+                    Path::new("/src"),
+                    &incoming,
+                    &ImportOptions::default(),
+                )
+                .unwrap();
+
+            assert_eq!(
+                plan.transfers,
+                [
+                    op("1002.cpp", "1002.cpp"),
+                    op("y/1000.cpp", "1000_conflict1.cpp")
+                ],
+                "jobs = {jobs}"
+            );
+            assert_eq!(
+                plan.duplicates,
+                [op("sub/1002.cpp", "1002.cpp"), op("x/1000.cpp", "1000.cpp")],
+                "jobs = {jobs}"
+            );
+        }
+    }
+
+    #[test]
+    fn import_rebalances_folders_over_threshold() {
+        let fs = memory_with(&[
+            ("/target/1000.cpp", ""),
+            ("/target/1001.cpp", ""),
+            ("/target/2000.cpp", ""),
+            ("/src/1002.cpp", ""),
+        ]);
+        let mut organizer = Organizer::builder("/target")
+            .threshold(3)
+            .filesystem(&fs)
+            .build()
+            .unwrap();
+        let scan = organizer.scan();
+        let incoming = organizer.scan_source(Path::new("/src")).unwrap();
+        let options = ImportOptions {
+            mode: crate::import::TransferMode::Move,
+            ..ImportOptions::default()
+        };
+
+        let plan = organizer
+            .plan_import(&scan, Path::new("/src"), &incoming, &options)
+            .unwrap();
+        let report = organizer.execute_import(&plan).unwrap();
+
+        assert_eq!(report.moved.len(), 2);
+        assert_eq!(report.imported.len(), 1);
+        let mut rescanned: Vec<PathBuf> = organizer
+            .scan()
+            .entries
+            .into_iter()
+            .map(|e| e.current_path)
+            .collect();
+        rescanned.sort();
+        assert_eq!(
+            rescanned,
+            [
+                PathBuf::from("1000/1000.cpp"),
+                PathBuf::from("1000/1001.cpp"),
+                PathBuf::from("1000/1002.cpp"),
+                PathBuf::from("2000.cpp"),
+            ]
+        );
+        assert!(!fs.exists(Path::new("/src/1002.cpp")));
+        let scan = organizer.scan();
+        assert!(organizer.plan(&scan).moves.is_empty());
+    }
+
+    #[test]
+    fn scan_source_rejects_overlapping_dirs() {
+        let fs = memory_with(&[("/target/inbox/1000.cpp", "")]);
+        let organizer = Organizer::builder("/target")
+            .filesystem(&fs)
+            .build()
+            .unwrap();
+
+        assert!(matches!(
+            organizer.scan_source(Path::new("/target/inbox")),
+            Err(Error::Io { .. })
+        ));
+        assert!(matches!(
+            organizer.scan_source(Path::new("/missing")),
+            Err(Error::InvalidRoot { .. })
+        ));
+    }
+
+    #[test]
+    fn export_uses_planned_layout_without_moving() {
+        let fs = memory_with(&[
+            ("/target/1000.cpp", "a"),
+            ("/target/1001.cpp", "b"),
+            ("/target/misc/1002.py", "c"),
+            ("/target/2000.cpp", "d"),
+        ]);
+        let mut organizer = Organizer::builder("/target")
+            .threshold(3)
+            .filesystem(&fs)
+            .build()
+            .unwrap();
+        let scan = organizer.scan();
+        let filter = ExportFilter {
+            languages: vec!["cpp".to_string(), "py".to_string()],
+            range: Some("1000..2000".parse().unwrap()),
+            ..ExportFilter::default()
+        };
+
+        let files = organizer.plan_export(&scan, &filter);
+        assert_eq!(
+            files,
+            [
+                MoveOperation::new("1000.cpp".into(), "1000/1000.cpp".into()),
+                MoveOperation::new("1001.cpp".into(), "1000/1001.cpp".into()),
+                MoveOperation::new("misc/1002.py".into(), "1000/1002.py".into()),
+            ]
+        );
+        let archive = organizer.export(&files, ArchiveFormat::Zip).unwrap();
+        assert_eq!(
+            archive,
+            organizer.export(&files, ArchiveFormat::Zip).unwrap()
+        );
+        assert!(fs.exists(Path::new("/target/misc/1002.py")));
+        assert!(!fs.exists(Path::new("/target/1000")));
+    }
+
+    fn flatten_fixture() -> crate::vfs::MemoryFs {
+        memory_with(&[
+            ("/target/1000.cpp", "a"),
+            ("/target/1000/1000.cpp", "b"),
+            ("/target/1000/1001.cpp", "c"),
+            ("/target/etc/main.py", "d"),
+        ])
+    }
+
+    #[test]
+    fn flatten_moves_everything_to_root_and_removes_group_folders() {
+        let fs = flatten_fixture();
+        let mut organizer = Organizer::builder("/target")
+            .filesystem(&fs)
+            .build()
+            .unwrap();
+        let scan = organizer.scan();
+
+        let plan = organizer
+            .plan_flatten(&scan, Path::new(""), ConflictPolicy::Rename)
+            .unwrap();
+        assert_eq!(
+            plan.conflicts,
+            [MoveOperation::new(
+                "1000/1000.cpp".into(),
+                "1000_conflict1.cpp".into()
+            )]
+        );
+        let report = organizer.execute(&plan).unwrap();
+
+        assert_eq!(report.moved.len(), 3);
+        assert_eq!(
+            fs.read_to_string(Path::new("/target/1000.cpp")).unwrap(),
+            "a"
+        );
+        assert_eq!(
+            fs.read_to_string(Path::new("/target/1000_conflict1.cpp"))
+                .unwrap(),
+            "b"
+        );
+        assert!(fs.exists(Path::new("/target/main.py")));
+        assert!(!fs.exists(Path::new("/target/1000")));
+        assert!(!fs.exists(Path::new("/target/etc")));
+
+        let scan = organizer.scan();
+        let replan = organizer
+            .plan_flatten(&scan, Path::new("."), ConflictPolicy::Rename)
+            .unwrap();
+        assert!(replan.moves.is_empty());
+    }
+
+    #[test]
+    fn flatten_skip_policy_leaves_clashing_files() {
+        let fs = flatten_fixture();
+        let mut events = Vec::new();
+        let plan = {
+            let mut organizer = Organizer::builder("/target")
+                .filesystem(&fs)
+                .observer(|event: &Event| events.push(event.clone()))
+                .build()
+                .unwrap();
+            let scan = organizer.scan();
+            let plan = organizer
+                .plan_flatten(&scan, Path::new("/target/all"), ConflictPolicy::Skip)
+                .unwrap();
+            organizer.execute(&plan).unwrap();
+            plan
+        };
+
+        assert_eq!(plan.moves.len(), 3);
+        assert!(plan.conflicts.is_empty());
+        assert!(events.contains(&Event::FlattenSkipped {
+            path: "1000/1000.cpp".into(),
+            to: "all/1000.cpp".into(),
+        }));
+        assert!(!events.iter().any(|e| matches!(e, Event::Conflict { .. })));
+        assert!(fs.exists(Path::new("/target/all/1000.cpp")));
+        assert!(fs.exists(Path::new("/target/all/1001.cpp")));
+        assert!(fs.exists(Path::new("/target/1000/1000.cpp")));
+        assert!(!fs.exists(Path::new("/target/1000/1001.cpp")));
+    }
+
+    #[test]
+    fn flatten_rejects_folders_outside_root() {
+        let fs = flatten_fixture();
+        let mut organizer = Organizer::builder("/target")
+            .filesystem(&fs)
+            .build()
+            .unwrap();
+        let scan = organizer.scan();
+        for into in ["../elsewhere", "/other"] {
+            assert!(matches!(
+                organizer.plan_flatten(&scan, Path::new(into), ConflictPolicy::Rename),
+                Err(Error::Io { .. })
+            ));
+        }
+    }
+}
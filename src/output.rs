@@ -0,0 +1,108 @@
+//! 화면 출력.
+//!
+//! 라이브러리는 결과 구조체와 이벤트만 돌려주고, 사람이 읽는 출력은 모두 여기서 합니다.
+
+use ps_organizer::events::Verbosity;
+use ps_organizer::import::ImportPlan;
+use ps_organizer::organizer::ExecutionReport;
+use ps_organizer::simulate::Simulation;
+use ps_organizer::t;
+use ps_organizer::types::MoveOperation;
+use std::collections::BTreeSet;
+
+/// 사람이 읽는 안내 문구를 상세 수준에 맞춰 출력합니다.
+///
+/// JSON 로그 모드에서는 이벤트 외의 문구를 출력하지 않습니다.
+pub struct Console {
+    pub verbosity: Verbosity,
+    pub human: bool,
+}
+
+impl Console {
+    pub fn say(&self, level: Verbosity, message: &str) {
+        if self.human && self.verbosity >= level {
+            println!("{message}");
+        }
+    }
+}
+
+/// 실행 결과를 요약해 출력합니다.
+pub fn print_summary(console: &Console, report: &ExecutionReport) {
+    if !report.moved.is_empty() {
+        console.say(
+            Verbosity::Normal,
+            &format!("\n{}", t!("exec.done", count = report.moved.len())),
+        );
+    }
+    if !report.removed_dirs.is_empty() {
+        console.say(
+            Verbosity::Normal,
+            &t!("exec.dirs_removed", count = report.removed_dirs.len()),
+        );
+    }
+}
+
+/// dry-run 시뮬레이션 결과를 출력합니다.
+///
+/// 실패할 작업은 로그 형식과 관계없이 표준 에러로 출력합니다.
+pub fn print_simulation(console: &Console, simulation: &Simulation) {
+    for error in &simulation.failures {
+        eprintln!("{}", t!("simulate.failure", error = error));
+    }
+    if simulation.is_ok() {
+        console.say(Verbosity::Normal, &format!("\n{}", t!("simulate.ok")));
+    } else {
+        console.say(
+            Verbosity::Normal,
+            &format!(
+                "\n{}",
+                t!("simulate.failed", count = simulation.failures.len())
+            ),
+        );
+    }
+}
+
+/// 이동 계획을 화면에 출력합니다 (dry-run 모드용).
+pub fn print_plan(moves: &[MoveOperation], verbose: bool) {
+    if moves.is_empty() {
+        println!("{}", t!("plan.empty"));
+        return;
+    }
+
+    println!("{}\n", t!("plan.header", count = moves.len()));
+
+    for op in moves {
+        println!("  {} -> {}", op.from.display(), op.to.display());
+    }
+
+    if verbose {
+        let folders: BTreeSet<_> = moves
+            .iter()
+            .filter_map(|m| m.to.parent())
+            .filter(|p| !p.as_os_str().is_empty())
+            .collect();
+
+        if !folders.is_empty() {
+            println!("\n{}", t!("plan.folders"));
+            for folder in folders {
+                println!("  {}/", folder.display());
+            }
+        }
+    }
+}
+
+/// 가져오기 계획을 화면에 출력합니다 (dry-run 모드용).
+///
+/// 가져올 파일 다음에 대상 트리를 다시 나누는 이동 계획을 출력합니다.
+pub fn print_import_plan(plan: &ImportPlan, verbose: bool) {
+    if plan.transfers.is_empty() {
+        println!("{}", t!("import.empty"));
+    } else {
+        println!("{}\n", t!("import.header", count = plan.transfers.len()));
+        for op in &plan.transfers {
+            println!("  {} -> {}", op.from.display(), op.to.display());
+        }
+    }
+    println!();
+    print_plan(&plan.rebalance.moves, verbose);
+}
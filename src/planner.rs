@@ -1,61 +1,309 @@
 //! 이동 계획 생성.
 
+use crate::events::{Event, NoopObserver, Observer};
 use crate::grouper::compute_structure;
+use crate::keys::{KeyExtractor, NumberKey, sanitize_key};
 use crate::types::{FileEntry, MoveOperation};
-use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ffi::{OsStr, OsString};
+use std::hash::BuildHasher;
+use std::path::{Path, PathBuf};
 
 /// 파일명 충돌 시 새로운 이름을 생성합니다.
-pub fn resolve_conflict(filename: &str, conflict_num: u32) -> String {
-    filename.rfind('.').map_or_else(
-        || format!("{filename}_conflict{conflict_num}"),
-        |dot_pos| {
-            let (name, ext) = filename.split_at(dot_pos);
-            format!("{name}_conflict{conflict_num}{ext}")
-        },
-    )
+///
+/// 마지막 `.` 앞에 `_conflictN`을 붙입니다. UTF-8이 아닌 파일명도 바이트 그대로 유지합니다.
+pub fn resolve_conflict(filename: impl AsRef<OsStr>, conflict_num: u32) -> OsString {
+    let path = Path::new(filename.as_ref());
+    let mut resolved = path.file_stem().unwrap_or_default().to_os_string();
+    resolved.push(format!("_conflict{conflict_num}"));
+    if let Some(ext) = path.extension() {
+        resolved.push(".");
+        resolved.push(ext);
+    }
+    resolved
 }
 
-/// 파일 목록을 분석하여 이동 계획을 생성합니다.
-pub fn plan_moves(entries: &[FileEntry], threshold: usize) -> Vec<MoveOperation> {
-    let numbers: Vec<u32> = entries.iter().filter_map(|e| e.problem_number).collect();
+/// 충돌 이름에 붙는 꼬리표.
+const CONFLICT_SUFFIX: &[u8] = b"_conflict";
 
-    let structure = compute_structure(&numbers, threshold, "");
+/// 파일명 줄기에서 `_conflictN` 꼬리표를 떼어 냅니다.
+///
+/// 꼬리표가 없으면 줄기를 그대로, 번호를 `None`으로 반환합니다.
+pub(crate) fn split_conflict_suffix(stem: &[u8]) -> (&[u8], Option<u32>) {
+    let Some(start) = stem
+        .windows(CONFLICT_SUFFIX.len())
+        .rposition(|window| window == CONFLICT_SUFFIX)
+    else {
+        return (stem, None);
+    };
+    let digits = &stem[start + CONFLICT_SUFFIX.len()..];
+    if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+        return (stem, None);
+    }
+    match std::str::from_utf8(digits)
+        .ok()
+        .and_then(|d| d.parse().ok())
+    {
+        Some(number) => (&stem[..start], Some(number)),
+        None => (stem, None),
+    }
+}
 
-    let mut moves = Vec::new();
-    let mut target_files: HashMap<PathBuf, HashSet<String>> = HashMap::new();
-    let mut conflict_counts: HashMap<String, u32> = HashMap::new();
+/// [`resolve_conflict`]로 만든 이름이면 충돌 번호를 반환합니다.
+pub fn conflict_number(filename: impl AsRef<OsStr>) -> Option<u32> {
+    let stem = Path::new(filename.as_ref()).file_stem()?;
+    split_conflict_suffix(stem.as_encoded_bytes()).1
+}
 
-    for entry in entries {
-        let target_folder = entry.problem_number.map_or_else(
-            || "etc".to_string(),
-            |num| structure.get(&num).cloned().unwrap_or_default(),
-        );
+/// 충돌 이름에서 원래 파일명을 되살립니다 (`1010_conflict2.cpp` → `1010.cpp`).
+///
+/// 충돌 이름이 아니면 파일명을 그대로 반환합니다.
+pub fn conflict_base(filename: impl AsRef<OsStr>) -> OsString {
+    let filename = filename.as_ref();
+    let path = Path::new(filename);
+    let Some(stem) = path.file_stem() else {
+        return filename.to_os_string();
+    };
+    let (base, number) = split_conflict_suffix(stem.as_encoded_bytes());
+    if number.is_none() {
+        return filename.to_os_string();
+    }
+    let Some(mut resolved) = os_string_from_bytes(base) else {
+        return filename.to_os_string();
+    };
+    if let Some(ext) = path.extension() {
+        resolved.push(".");
+        resolved.push(ext);
+    }
+    resolved
+}
+
+#[cfg(unix)]
+#[allow(clippy::unnecessary_wraps)]
+fn os_string_from_bytes(bytes: &[u8]) -> Option<OsString> {
+    use std::os::unix::ffi::OsStrExt;
+    Some(OsStr::from_bytes(bytes).to_os_string())
+}
+
+#[cfg(not(unix))]
+fn os_string_from_bytes(bytes: &[u8]) -> Option<OsString> {
+    std::str::from_utf8(bytes).ok().map(OsString::from)
+}
+
+/// 계획 옵션.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlanOptions {
+    /// 그룹핑 임계값 (이 값 이하면 폴더를 만들지 않음).
+    pub threshold: usize,
+    /// 목적지 파일명의 확장자를 정식 확장자로 바꿀지 여부.
+    pub normalize_extensions: bool,
+}
 
-        let target_folder_path = PathBuf::from(&target_folder);
-        let existing_files = target_files.entry(target_folder_path.clone()).or_default();
+impl PlanOptions {
+    /// 임계값만 지정한 기본 옵션을 생성합니다.
+    pub const fn new(threshold: usize) -> Self {
+        Self {
+            threshold,
+            normalize_extensions: false,
+        }
+    }
 
-        let final_filename = if existing_files.contains(&entry.filename) {
-            let key = format!("{target_folder}/{}", entry.filename);
-            let count = conflict_counts.entry(key).or_insert(0);
-            *count += 1;
-            resolve_conflict(&entry.filename, *count)
+    /// 옵션에 따라 파일이 목적지에서 가질 이름을 반환합니다.
+    pub(crate) fn target_filename(&self, entry: &FileEntry) -> OsString {
+        if self.normalize_extensions {
+            entry.normalized_filename()
         } else {
             entry.filename.clone()
-        };
+        }
+    }
+}
 
-        existing_files.insert(final_filename.clone());
+/// 파일 목록을 분석하여 이동 계획을 생성합니다.
+pub fn plan_moves(entries: &[FileEntry], threshold: usize) -> Vec<MoveOperation> {
+    plan_moves_by(entries, threshold, &NumberKey)
+}
 
-        let target_path = if target_folder.is_empty() {
-            PathBuf::from(&final_filename)
-        } else {
-            PathBuf::from(&target_folder).join(&final_filename)
-        };
+/// 키 추출기로 상위 폴더를 정해 이동 계획을 생성합니다.
+///
+/// 번호가 있는 파일은 `keys`가 돌려준 폴더 아래에 모이고, 같은 키를 가진 파일끼리
+/// 번호 범위 그룹핑을 따로 적용합니다. 키가 없는 파일은 [`plan_moves`]와 같이
+/// 배치합니다.
+pub fn plan_moves_by(
+    entries: &[FileEntry],
+    threshold: usize,
+    keys: &dyn KeyExtractor,
+) -> Vec<MoveOperation> {
+    plan_moves_with(
+        entries,
+        &PlanOptions::new(threshold),
+        keys,
+        &HashMap::new(),
+        &mut NoopObserver,
+    )
+}
+
+/// 옵션에 따라, 일부 파일의 목적지를 고정한 채 이동 계획을 생성합니다.
+///
+/// `overrides`는 현재 경로에서 고정 목적지로의 매핑입니다. 이동을 거부한 파일은
+/// 현재 경로를 목적지로 지정하면 제자리에 남습니다. 고정된 목적지가 먼저 이름을
+/// 차지하고, 나머지 파일의 충돌은 그에 맞춰 다시 계산됩니다.
+///
+/// 여러 파일이 같은 목적지를 원하면 아래 순서로 앞선 파일이 원래 이름을 가지고,
+/// 나머지는 `_conflictN` 이름을 받습니다. 이미 충돌 이름인 파일은 꼬리표를 뗀 원래
+/// 이름에 비어 있는 번호를 붙입니다. 결과는 `entries`의 순서와 관계없습니다.
+///
+/// 1. 이미 목적지에 있는 파일
+/// 2. 현재 경로의 깊이가 얕은 파일
+/// 3. 현재 경로가 사전순으로 앞선 파일
+///
+/// 이미 목적지에 있는 파일(충돌 이름 포함)은 다른 파일에 밀려나지 않고, 나머지
+/// 파일은 한 번씩만 옮깁니다. 따라서 고정 목적지 때문에 비켜야 하는 경우를 빼면
+/// 이동 수는 목적지에 있지 않은 파일 수와 같아 최소가 됩니다. 목적지에 있지 않은
+/// 파일을 옮기지 않고 남겨 이동을 줄이지는 않습니다.
+///
+/// 이동 계획은 현재 경로 순으로 정렬합니다. 필요한 이동마다 [`Event::Planned`]를,
+/// 이름을 바꾼 경우 [`Event::Conflict`]를 같은 순서로 보고합니다.
+pub fn plan_moves_with<S: BuildHasher>(
+    entries: &[FileEntry],
+    options: &PlanOptions,
+    keys: &dyn KeyExtractor,
+    overrides: &HashMap<PathBuf, PathBuf, S>,
+    observer: &mut dyn Observer,
+) -> Vec<MoveOperation> {
+    plan_moves_in(entries, options, keys, overrides, None, observer)
+}
+
+/// 모든 파일을 `folder`(루트 기준) 한 곳에 두는 이동 계획을 생성합니다.
+///
+/// 키와 그룹핑을 쓰지 않는 것 말고는 [`plan_moves_with`]와 같은 순서로 이름을 정하고
+/// 같은 이벤트를 보고합니다.
+pub fn plan_moves_into(
+    entries: &[FileEntry],
+    options: &PlanOptions,
+    folder: &Path,
+    observer: &mut dyn Observer,
+) -> Vec<MoveOperation> {
+    plan_moves_in(
+        entries,
+        options,
+        &|_: &FileEntry| None,
+        &HashMap::new(),
+        Some(folder),
+        observer,
+    )
+}
+
+/// `folder`가 있으면 모든 파일을 그 폴더에, 없으면 키와 그룹핑에 따라 배치합니다.
+fn plan_moves_in<S: BuildHasher>(
+    entries: &[FileEntry],
+    options: &PlanOptions,
+    keys: &dyn KeyExtractor,
+    overrides: &HashMap<PathBuf, PathBuf, S>,
+    folder: Option<&Path>,
+    observer: &mut dyn Observer,
+) -> Vec<MoveOperation> {
+    let entry_keys: Vec<String> = entries
+        .iter()
+        .map(|e| {
+            e.problem_number
+                .and_then(|_| keys.key(e))
+                .and_then(|key| sanitize_key(&key))
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let mut buckets: BTreeMap<&str, Vec<u32>> = BTreeMap::new();
+    for (entry, key) in entries.iter().zip(&entry_keys) {
+        if let Some(number) = entry.problem_number {
+            buckets.entry(key).or_default().push(number);
+        }
+    }
+    let structures: BTreeMap<&str, BTreeMap<u32, String>> = buckets
+        .into_iter()
+        .map(|(key, numbers)| (key, compute_structure(&numbers, options.threshold, key)))
+        .collect();
 
+    let mut moves = Vec::new();
+    let mut taken: HashSet<PathBuf> = entries
+        .iter()
+        .filter_map(|e| overrides.get(&e.current_path).cloned())
+        .collect();
+
+    let mut wanted = Vec::new();
+    for (entry, key) in entries.iter().zip(&entry_keys) {
+        if let Some(pinned) = overrides.get(&entry.current_path) {
+            moves.push(MoveOperation::new(
+                entry.current_path.clone(),
+                pinned.clone(),
+            ));
+            continue;
+        }
+
+        let target_folder = folder.map_or_else(
+            || {
+                PathBuf::from(entry.problem_number.map_or_else(
+                    || "etc".to_string(),
+                    |num| {
+                        structures
+                            .get(key.as_str())
+                            .and_then(|structure| structure.get(&num))
+                            .cloned()
+                            .unwrap_or_default()
+                    },
+                ))
+            },
+            Path::to_path_buf,
+        );
+        let filename = options.target_filename(entry);
+        wanted.push((entry, target_folder, filename));
+    }
+    wanted.sort_by_cached_key(|(entry, folder, filename)| {
+        let in_place = folder.join(filename) == entry.current_path;
+        (
+            !in_place,
+            entry.current_path.components().count(),
+            entry.current_path.clone(),
+        )
+    });
+
+    let mut conflicts = Vec::new();
+    for (entry, target_folder, filename) in wanted {
+        // 충돌 이름끼리 다시 부딪히면 꼬리표를 겹쳐 붙이지 않고 번호만 바꿉니다.
+        let base = conflict_base(&filename);
+        let mut target_path = target_folder.join(&filename);
+        let mut conflict_num = 0;
+        while taken.contains(&target_path) {
+            conflict_num += 1;
+            target_path = target_folder.join(resolve_conflict(&base, conflict_num));
+        }
+
+        if conflict_num > 0 {
+            conflicts.push(MoveOperation::new(
+                entry.current_path.clone(),
+                target_path.clone(),
+            ));
+        }
+
+        taken.insert(target_path.clone());
         moves.push(MoveOperation::new(entry.current_path.clone(), target_path));
     }
 
-    moves.into_iter().filter(MoveOperation::is_needed).collect()
+    conflicts.sort_by(|a, b| a.from.cmp(&b.from));
+    for op in conflicts {
+        observer.on_event(&Event::Conflict {
+            from: op.from,
+            to: op.to,
+        });
+    }
+    moves.retain(MoveOperation::is_needed);
+    moves.sort_by(|a, b| a.from.cmp(&b.from));
+    for op in &moves {
+        observer.on_event(&Event::Planned {
+            from: op.from.clone(),
+            to: op.to.clone(),
+        });
+    }
+    moves
 }
 
 #[cfg(test)]
@@ -73,13 +321,35 @@ mod tests {
         assert_eq!(resolve_conflict("readme", 1), "readme_conflict1");
     }
 
+    #[test]
+    fn resolve_conflict_keeps_inner_dots() {
+        assert_eq!(resolve_conflict("a.tar.gz", 1), "a.tar_conflict1.gz");
+    }
+
+    #[test]
+    fn conflict_base_strips_suffix() {
+        assert_eq!(conflict_base("1010_conflict2.cpp"), "1010.cpp");
+        assert_eq!(conflict_base("a.tar_conflict1.gz"), "a.tar.gz");
+        assert_eq!(conflict_base("readme_conflict1"), "readme");
+        assert_eq!(conflict_base("1010_conflict.cpp"), "1010_conflict.cpp");
+        assert_eq!(conflict_number("1010_conflict12.cpp"), Some(12));
+        assert_eq!(conflict_number("1010.cpp"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn resolve_conflict_keeps_non_utf8_bytes() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let name = OsStr::from_bytes(b"\xb9\xae\xc1\xa6.cpp");
+        let resolved = resolve_conflict(name, 1);
+
+        assert_eq!(resolved.as_bytes(), b"\xb9\xae\xc1\xa6_conflict1.cpp");
+    }
+
     #[test]
     fn plan_moves_no_move_when_already_correct() {
-        let entries = vec![FileEntry {
-            current_path: PathBuf::from("1010.cpp"),
-            problem_number: Some(1010),
-            filename: "1010.cpp".to_string(),
-        }];
+        let entries = vec![FileEntry::new(PathBuf::from("1010.cpp"), Some(1010))];
 
         let moves = plan_moves(&entries, 20);
         assert!(moves.is_empty());
@@ -87,11 +357,7 @@ mod tests {
 
     #[test]
     fn plan_moves_etc_for_non_matching() {
-        let entries = vec![FileEntry {
-            current_path: PathBuf::from("solution.cpp"),
-            problem_number: None,
-            filename: "solution.cpp".to_string(),
-        }];
+        let entries = vec![FileEntry::new(PathBuf::from("solution.cpp"), None)];
 
         let moves = plan_moves(&entries, 20);
         assert_eq!(moves.len(), 1);
@@ -101,42 +367,337 @@ mod tests {
     #[test]
     fn plan_moves_handles_conflict() {
         let entries = vec![
-            FileEntry {
-                current_path: PathBuf::from("1010.cpp"),
-                problem_number: Some(1010),
-                filename: "1010.cpp".to_string(),
-            },
-            FileEntry {
-                current_path: PathBuf::from("old/1010.cpp"),
-                problem_number: Some(1010),
-                filename: "1010.cpp".to_string(),
-            },
+            FileEntry::new(PathBuf::from("1010.cpp"), Some(1010)),
+            FileEntry::new(PathBuf::from("old/1010.cpp"), Some(1010)),
         ];
 
         let moves = plan_moves(&entries, 20);
 
-        let destinations: Vec<_> = moves.iter().map(|m| m.to.clone()).collect();
-        assert!(
-            destinations.contains(&PathBuf::from("1010.cpp"))
-                || destinations.contains(&PathBuf::from("1010_conflict1.cpp"))
+        assert_eq!(
+            moves,
+            vec![MoveOperation::new(
+                PathBuf::from("old/1010.cpp"),
+                PathBuf::from("1010_conflict1.cpp")
+            )]
         );
     }
 
     #[test]
     fn plan_moves_splits_when_over_threshold() {
         let entries: Vec<FileEntry> = (1001..=1050)
-            .map(|n| FileEntry {
-                current_path: PathBuf::from(format!("{n}.cpp")),
-                problem_number: Some(n),
-                filename: format!("{n}.cpp"),
-            })
+            .map(|n| FileEntry::new(PathBuf::from(format!("{n}.cpp")), Some(n)))
             .collect();
 
         let moves = plan_moves(&entries, 20);
 
         assert!(!moves.is_empty());
-        let sample_move = moves.iter().find(|m| m.from == PathBuf::from("1001.cpp"));
+        let sample_move = moves.iter().find(|m| m.from == Path::new("1001.cpp"));
         assert!(sample_move.is_some());
         assert!(sample_move.unwrap().to.to_string_lossy().contains("1000"));
     }
+
+    #[test]
+    fn plan_moves_with_keeps_rejected_in_place() {
+        let entries = vec![FileEntry::new(PathBuf::from("old/1010.cpp"), Some(1010))];
+        let overrides =
+            HashMap::from([(PathBuf::from("old/1010.cpp"), PathBuf::from("old/1010.cpp"))]);
+
+        let moves = plan_moves_with(
+            &entries,
+            &PlanOptions::new(20),
+            &NumberKey,
+            &overrides,
+            &mut NoopObserver,
+        );
+        assert!(moves.is_empty());
+    }
+
+    #[test]
+    fn plan_moves_with_recomputes_conflicts() {
+        let entries = vec![
+            FileEntry::new(PathBuf::from("a/1010.cpp"), Some(1010)),
+            FileEntry::new(PathBuf::from("b/1010.cpp"), Some(1010)),
+        ];
+        let overrides = HashMap::from([(PathBuf::from("b/1010.cpp"), PathBuf::from("1010.cpp"))]);
+
+        let mut events = Vec::new();
+        let moves = plan_moves_with(
+            &entries,
+            &PlanOptions::new(20),
+            &NumberKey,
+            &overrides,
+            &mut |e: &Event| {
+                events.push(e.clone());
+            },
+        );
+
+        assert_eq!(
+            moves,
+            vec![
+                MoveOperation::new(
+                    PathBuf::from("a/1010.cpp"),
+                    PathBuf::from("1010_conflict1.cpp")
+                ),
+                MoveOperation::new(PathBuf::from("b/1010.cpp"), PathBuf::from("1010.cpp")),
+            ]
+        );
+        assert!(events.contains(&Event::Conflict {
+            from: PathBuf::from("a/1010.cpp"),
+            to: PathBuf::from("1010_conflict1.cpp"),
+        }));
+    }
+
+    #[test]
+    fn folder_option_collects_every_file() {
+        let entries = vec![
+            FileEntry::new(PathBuf::from("flat/1010.cpp"), Some(1010)),
+            FileEntry::new(PathBuf::from("1000/1010.cpp"), Some(1010)),
+            FileEntry::new(PathBuf::from("1000/1011.cpp"), Some(1011)),
+            FileEntry::new(PathBuf::from("etc/main.py"), None),
+        ];
+        let moves = plan_moves_into(
+            &entries,
+            &PlanOptions::new(1),
+            Path::new("flat"),
+            &mut NoopObserver,
+        );
+        assert_eq!(
+            moves,
+            vec![
+                MoveOperation::new(
+                    PathBuf::from("1000/1010.cpp"),
+                    PathBuf::from("flat/1010_conflict1.cpp")
+                ),
+                MoveOperation::new(
+                    PathBuf::from("1000/1011.cpp"),
+                    PathBuf::from("flat/1011.cpp")
+                ),
+                MoveOperation::new(PathBuf::from("etc/main.py"), PathBuf::from("flat/main.py")),
+            ]
+        );
+    }
+
+    #[test]
+    fn plan_moves_by_uses_key_folders() {
+        let entries = vec![
+            FileEntry::new(PathBuf::from("1000.cpp"), Some(1000)),
+            FileEntry::new(PathBuf::from("1001.cpp"), Some(1001)),
+            FileEntry::new(PathBuf::from("main.cpp"), None),
+        ];
+        let keys = |e: &FileEntry| (e.problem_number == Some(1000)).then(|| "dp".to_string());
+
+        let moves = plan_moves_by(&entries, 20, &keys);
+
+        assert_eq!(
+            moves,
+            vec![
+                MoveOperation::new(PathBuf::from("1000.cpp"), PathBuf::from("dp/1000.cpp")),
+                MoveOperation::new(PathBuf::from("main.cpp"), PathBuf::from("etc/main.cpp")),
+            ]
+        );
+    }
+
+    #[test]
+    fn plan_moves_by_splits_inside_each_bucket() {
+        let dp: Vec<FileEntry> = (1001..=1030)
+            .map(|n| FileEntry::new(PathBuf::from(format!("{n}.cpp")), Some(n)))
+            .collect();
+        let mut entries = dp.clone();
+        entries.push(FileEntry::new(PathBuf::from("2001.cpp"), Some(2001)));
+        let keys = |e: &FileEntry| {
+            let key = if e.problem_number < Some(2000) {
+                "dp"
+            } else {
+                "greedy"
+            };
+            Some(key.to_string())
+        };
+
+        let moves = plan_moves_by(&entries, 20, &keys);
+
+        let expected: Vec<MoveOperation> = plan_moves(&dp, 20)
+            .into_iter()
+            .map(|m| MoveOperation::new(m.from, Path::new("dp").join(m.to)))
+            .chain([MoveOperation::new(
+                PathBuf::from("2001.cpp"),
+                PathBuf::from("greedy/2001.cpp"),
+            )])
+            .collect();
+        assert_eq!(moves, expected);
+        assert!(moves[0].to.starts_with("dp/1000"));
+    }
+
+    fn snapshot(moves: &[MoveOperation], events: &[Event]) -> String {
+        let mut lines: Vec<String> = moves
+            .iter()
+            .map(|m| format!("{} -> {}", m.from.display(), m.to.display()))
+            .collect();
+        lines.extend(events.iter().filter_map(|e| match e {
+            Event::Conflict { from, to } => {
+                Some(format!("conflict {} -> {}", from.display(), to.display()))
+            }
+            _ => None,
+        }));
+        lines.join("\n")
+    }
+
+    #[test]
+    fn plan_is_independent_of_entry_order() {
+        let entries = vec![
+            FileEntry::new(PathBuf::from("x/y/1001.cpp"), Some(1001)),
+            FileEntry::new(PathBuf::from("backup/1001.cpp"), Some(1001)),
+            FileEntry::new(PathBuf::from("1001.cpp"), Some(1001)),
+            FileEntry::new(PathBuf::from("a/1002.py"), Some(1002)),
+            FileEntry::new(PathBuf::from("z/main.cpp"), None),
+            FileEntry::new(PathBuf::from("main.cpp"), None),
+            FileEntry::new(PathBuf::from("1003.c"), Some(1003)),
+        ];
+        let expected = "\
+a/1002.py -> 1002.py
+backup/1001.cpp -> 1001_conflict1.cpp
+main.cpp -> etc/main.cpp
+x/y/1001.cpp -> 1001_conflict2.cpp
+z/main.cpp -> etc/main_conflict1.cpp
+conflict backup/1001.cpp -> 1001_conflict1.cpp
+conflict x/y/1001.cpp -> 1001_conflict2.cpp
+conflict z/main.cpp -> etc/main_conflict1.cpp";
+
+        for rotation in 0..entries.len() {
+            let mut shuffled = entries.clone();
+            shuffled.rotate_left(rotation);
+            if rotation % 2 == 1 {
+                shuffled.reverse();
+            }
+
+            let mut events = Vec::new();
+            let moves = plan_moves_with(
+                &shuffled,
+                &PlanOptions::new(20),
+                &NumberKey,
+                &HashMap::new(),
+                &mut |e: &Event| events.push(e.clone()),
+            );
+
+            assert_eq!(snapshot(&moves, &events), expected, "rotation {rotation}");
+        }
+    }
+
+    #[test]
+    fn in_place_files_keep_their_names() {
+        let entries = vec![
+            FileEntry::new(PathBuf::from("a/b/1001.cpp"), Some(1001)),
+            FileEntry::new(PathBuf::from("backup/1001.cpp"), Some(1001)),
+            FileEntry::new(PathBuf::from("1001.cpp"), Some(1001)),
+            FileEntry::new(PathBuf::from("x/1002.cpp"), Some(1002)),
+            FileEntry::new(PathBuf::from("1002.cpp"), Some(1002)),
+            FileEntry::new(PathBuf::from("main.cpp"), None),
+            FileEntry::new(PathBuf::from("etc/main.cpp"), None),
+        ];
+
+        let moves = plan_moves(&entries, 20);
+
+        // 얕은 `main.cpp`보다 이미 제자리에 있는 `etc/main.cpp`가 이름을 가집니다.
+        assert_eq!(
+            moves,
+            vec![
+                MoveOperation::new(
+                    PathBuf::from("a/b/1001.cpp"),
+                    PathBuf::from("1001_conflict2.cpp")
+                ),
+                MoveOperation::new(
+                    PathBuf::from("backup/1001.cpp"),
+                    PathBuf::from("1001_conflict1.cpp")
+                ),
+                MoveOperation::new(
+                    PathBuf::from("main.cpp"),
+                    PathBuf::from("etc/main_conflict1.cpp")
+                ),
+                MoveOperation::new(
+                    PathBuf::from("x/1002.cpp"),
+                    PathBuf::from("1002_conflict1.cpp")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn in_place_file_in_group_folder_wins() {
+        let flat: Vec<FileEntry> = (1001..=1030)
+            .map(|n| FileEntry::new(PathBuf::from(format!("{n}.cpp")), Some(n)))
+            .collect();
+        let mut entries: Vec<FileEntry> = plan_moves(&flat, 20)
+            .into_iter()
+            .zip(1001..)
+            .map(|(op, n)| FileEntry::new(op.to, Some(n)))
+            .collect();
+        assert!(plan_moves(&entries, 20).is_empty());
+
+        entries.insert(0, FileEntry::new(PathBuf::from("1005.cpp"), Some(1005)));
+        let moves = plan_moves(&entries, 20);
+
+        assert_eq!(moves.len(), 1);
+        assert_eq!(moves[0].from, Path::new("1005.cpp"));
+        assert_eq!(moves[0].to.file_name().unwrap(), "1005_conflict1.cpp");
+    }
+
+    #[test]
+    fn only_misplaced_files_move_and_each_moves_once() {
+        let entries = vec![
+            FileEntry::new(PathBuf::from("backup/1001_conflict1.cpp"), Some(1001)),
+            FileEntry::new(PathBuf::from("backup/1001.cpp"), Some(1001)),
+            FileEntry::new(PathBuf::from("1001_conflict1.cpp"), Some(1001)),
+            FileEntry::new(PathBuf::from("1001.cpp"), Some(1001)),
+            FileEntry::new(PathBuf::from("1002.cpp"), Some(1002)),
+        ];
+
+        let moves = plan_moves(&entries, 20);
+
+        // 제자리에 있는 세 파일(충돌 이름 포함)은 그대로 두고, 나머지 두 파일만
+        // 한 번씩 옮기므로 이동 수가 최소입니다.
+        assert_eq!(
+            moves,
+            vec![
+                MoveOperation::new(
+                    PathBuf::from("backup/1001.cpp"),
+                    PathBuf::from("1001_conflict2.cpp")
+                ),
+                MoveOperation::new(
+                    PathBuf::from("backup/1001_conflict1.cpp"),
+                    PathBuf::from("1001_conflict3.cpp")
+                ),
+            ]
+        );
+
+        // 계획대로 옮긴 트리는 다시 계획해도 옮길 파일이 없습니다.
+        let settled: Vec<FileEntry> = entries
+            .iter()
+            .map(|entry| {
+                let path = moves
+                    .iter()
+                    .find(|op| op.from == entry.current_path)
+                    .map_or_else(|| entry.current_path.clone(), |op| op.to.clone());
+                FileEntry::new(path, entry.problem_number)
+            })
+            .collect();
+        assert!(plan_moves(&settled, 20).is_empty());
+    }
+
+    #[test]
+    fn conflict_files_keep_their_slot_and_renumber_from_base() {
+        let entries = vec![
+            FileEntry::new(PathBuf::from("1010.cpp"), Some(1010)),
+            FileEntry::new(PathBuf::from("1010_conflict1.cpp"), Some(1010)),
+            FileEntry::new(PathBuf::from("old/1010_conflict1.cpp"), Some(1010)),
+        ];
+
+        let moves = plan_moves(&entries, 20);
+
+        assert_eq!(
+            moves,
+            vec![MoveOperation::new(
+                PathBuf::from("old/1010_conflict1.cpp"),
+                PathBuf::from("1010_conflict2.cpp")
+            )]
+        );
+    }
 }
@@ -0,0 +1,424 @@
+//! 실행 전 대화형 검토.
+//!
+//! 이동 계획을 목적지 폴더별로 접힌 트리로 보여주고, 사용자가 전체 승인,
+//! 개별 이동 거부, 목적지 수정을 한 뒤에만 실행하도록 합니다.
+
+use crate::events::NoopObserver;
+use crate::keys::KeyExtractor;
+use crate::planner::{PlanOptions, plan_moves_with};
+use crate::t;
+use crate::types::{FileEntry, MoveOperation};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+/// 대화형 검토 결과.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReviewOutcome {
+    /// 승인된 최종 이동 계획.
+    Accepted {
+        /// 최종 이동 목록.
+        moves: Vec<MoveOperation>,
+        /// 사용자가 거부하거나 수정해 고정한 목적지. 현재 경로에서 목적지로의
+        /// 매핑이며, 같은 항목과 옵션으로 [`plan_moves_with`]에 넘기면 `moves`를
+        /// 그대로 다시 얻습니다.
+        overrides: HashMap<PathBuf, PathBuf>,
+    },
+    /// 사용자가 실행을 취소함.
+    Aborted,
+}
+
+struct Review<'a> {
+    entries: &'a [FileEntry],
+    options: &'a PlanOptions,
+    keys: &'a dyn KeyExtractor,
+    overrides: HashMap<PathBuf, PathBuf>,
+    moves: Vec<MoveOperation>,
+    expanded: HashSet<PathBuf>,
+}
+
+impl<'a> Review<'a> {
+    fn new(entries: &'a [FileEntry], options: &'a PlanOptions, keys: &'a dyn KeyExtractor) -> Self {
+        let mut review = Self {
+            entries,
+            options,
+            keys,
+            overrides: HashMap::new(),
+            moves: Vec::new(),
+            expanded: HashSet::new(),
+        };
+        review.replan();
+        review
+    }
+
+    fn replan(&mut self) {
+        self.moves = plan_moves_with(
+            self.entries,
+            self.options,
+            self.keys,
+            &self.overrides,
+            &mut NoopObserver,
+        );
+    }
+
+    fn folders(&self) -> BTreeMap<PathBuf, Vec<usize>> {
+        let mut folders: BTreeMap<PathBuf, Vec<usize>> = BTreeMap::new();
+        for (index, op) in self.moves.iter().enumerate() {
+            let folder = op.to.parent().map(Path::to_path_buf).unwrap_or_default();
+            folders.entry(folder).or_default().push(index);
+        }
+        folders
+    }
+
+    fn render(&self, out: &mut impl Write) -> io::Result<()> {
+        if self.moves.is_empty() {
+            writeln!(out, "{}", t!("exec.empty"))?;
+            return Ok(());
+        }
+
+        writeln!(out, "{}", t!("plan.header", count = self.moves.len()))?;
+        for (folder, indices) in self.folders() {
+            let name = if folder.as_os_str().is_empty() {
+                "./".to_string()
+            } else {
+                format!("{}/", folder.display())
+            };
+
+            let label = t!("review.folder", folder = name, count = indices.len());
+
+            if self.expanded.contains(&folder) {
+                writeln!(out, "▾ {label}")?;
+                for index in indices {
+                    let op = &self.moves[index];
+                    writeln!(
+                        out,
+                        "    [{}] {} -> {}",
+                        index + 1,
+                        op.from.display(),
+                        op.to.display()
+                    )?;
+                }
+            } else {
+                writeln!(out, "▸ {label}")?;
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_index(&self, arg: &str) -> Option<usize> {
+        arg.parse::<usize>()
+            .ok()
+            .filter(|&n| n >= 1 && n <= self.moves.len())
+            .map(|n| n - 1)
+    }
+
+    fn reject(&mut self, args: &[&str], out: &mut impl Write) -> io::Result<()> {
+        let mut sources = Vec::new();
+        for arg in args {
+            let Some(index) = self.parse_index(arg) else {
+                writeln!(out, "{}", t!("review.invalid_index", arg = arg))?;
+                return Ok(());
+            };
+            sources.push(self.moves[index].from.clone());
+        }
+
+        if sources.is_empty() {
+            writeln!(out, "{}", t!("review.reject_usage"))?;
+            return Ok(());
+        }
+
+        for source in sources {
+            self.overrides.insert(source.clone(), source);
+        }
+        self.replan();
+        Ok(())
+    }
+
+    fn edit(&mut self, args: &[&str], out: &mut impl Write) -> io::Result<()> {
+        let [index, destination] = args else {
+            writeln!(out, "{}", t!("review.edit_usage"))?;
+            return Ok(());
+        };
+        let Some(index) = self.parse_index(index) else {
+            writeln!(out, "{}", t!("review.invalid_index", arg = index))?;
+            return Ok(());
+        };
+
+        // 빈 경로나 `.`, `dir/`처럼 파일 이름이 없는 목적지도 실행할 때에야 실패하므로
+        // 거부합니다.
+        let names_file = !matches!(destination.rsplit('/').next(), None | Some("" | "."));
+        let destination = PathBuf::from(destination.trim_start_matches("./"));
+        if !names_file
+            || destination.is_absolute()
+            || destination
+                .components()
+                .any(|c| matches!(c, std::path::Component::ParentDir))
+        {
+            writeln!(out, "{}", t!("review.edit_outside"))?;
+            return Ok(());
+        }
+
+        let source = self.moves[index].from.clone();
+        if self
+            .overrides
+            .iter()
+            .any(|(from, to)| *from != source && *to == destination)
+        {
+            writeln!(
+                out,
+                "{}",
+                t!("review.edit_taken", path = destination.display())
+            )?;
+            return Ok(());
+        }
+
+        self.overrides.insert(source, destination);
+        self.replan();
+        Ok(())
+    }
+
+    fn toggle(&mut self, args: &[&str], expand: bool) {
+        let targets: Vec<PathBuf> = if args == ["*"] {
+            self.folders().into_keys().collect()
+        } else {
+            args.iter()
+                .map(|a| PathBuf::from(a.trim_start_matches("./").trim_end_matches('/')))
+                .collect()
+        };
+
+        for folder in targets {
+            if expand {
+                self.expanded.insert(folder);
+            } else {
+                self.expanded.remove(&folder);
+            }
+        }
+    }
+}
+
+/// 이동 계획을 대화형으로 검토합니다.
+///
+/// `input`에서 명령을 한 줄씩 읽고 `output`에 계획을 출력합니다. 거부하거나
+/// 목적지를 수정한 이동은 고정된 채로 계획 전체를 다시 계산하므로, 충돌 이름도
+/// 함께 갱신됩니다. 입력이 끝나면 취소로 간주합니다.
+///
+/// # Errors
+///
+/// 입출력에 실패하면 에러를 반환합니다.
+pub fn review_plan(
+    entries: &[FileEntry],
+    options: &PlanOptions,
+    keys: &dyn KeyExtractor,
+    mut input: impl BufRead,
+    mut output: impl Write,
+) -> io::Result<ReviewOutcome> {
+    let mut review = Review::new(entries, options, keys);
+    review.render(&mut output)?;
+    writeln!(output, "\n{}", t!("review.hint"))?;
+
+    let mut line = String::new();
+    loop {
+        write!(output, "> ")?;
+        output.flush()?;
+
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(ReviewOutcome::Aborted);
+        }
+
+        let mut words = line.split_whitespace();
+        let Some(command) = words.next() else {
+            continue;
+        };
+        let args: Vec<&str> = words.collect();
+
+        match command {
+            "a" => {
+                return Ok(ReviewOutcome::Accepted {
+                    moves: review.moves,
+                    overrides: review.overrides,
+                });
+            }
+            "q" => return Ok(ReviewOutcome::Aborted),
+            "r" => review.reject(&args, &mut output)?,
+            "e" => review.edit(&args, &mut output)?,
+            "o" => review.toggle(&args, true),
+            "c" => review.toggle(&args, false),
+            "l" => {}
+            "?" => {
+                writeln!(output, "{}", t!("review.help"))?;
+                continue;
+            }
+            _ => {
+                writeln!(output, "{}", t!("review.unknown", command = command))?;
+                continue;
+            }
+        }
+
+        review.render(&mut output)?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::Event;
+    use crate::i18n::{self, Lang};
+    use crate::keys::NumberKey;
+    use std::io::Cursor;
+
+    fn entry(path: &str, number: Option<u32>) -> FileEntry {
+        FileEntry::new(PathBuf::from(path), number)
+    }
+
+    fn moves_of(outcome: &ReviewOutcome) -> Option<Vec<MoveOperation>> {
+        match outcome {
+            ReviewOutcome::Accepted { moves, .. } => Some(moves.clone()),
+            ReviewOutcome::Aborted => None,
+        }
+    }
+
+    fn run(entries: &[FileEntry], commands: &str) -> (ReviewOutcome, String) {
+        i18n::set_lang(Lang::Ko);
+        let mut output = Vec::new();
+        let outcome = review_plan(
+            entries,
+            &PlanOptions::new(20),
+            &NumberKey,
+            Cursor::new(commands),
+            &mut output,
+        )
+        .unwrap();
+        (outcome, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn accept_all_returns_full_plan() {
+        let entries = vec![entry("old/1001.cpp", Some(1001)), entry("main.cpp", None)];
+
+        let (outcome, _) = run(&entries, "a\n");
+
+        assert_eq!(
+            moves_of(&outcome),
+            Some(vec![
+                MoveOperation::new(PathBuf::from("main.cpp"), PathBuf::from("etc/main.cpp")),
+                MoveOperation::new(PathBuf::from("old/1001.cpp"), PathBuf::from("1001.cpp")),
+            ])
+        );
+    }
+
+    #[test]
+    fn end_of_input_aborts() {
+        let entries = vec![entry("old/1001.cpp", Some(1001))];
+
+        let (outcome, _) = run(&entries, "");
+
+        assert_eq!(outcome, ReviewOutcome::Aborted);
+    }
+
+    #[test]
+    fn rejected_move_frees_destination_for_conflicting_file() {
+        let entries = vec![
+            entry("a/1001.cpp", Some(1001)),
+            entry("b/1001.cpp", Some(1001)),
+        ];
+
+        let (outcome, _) = run(&entries, "r 1\na\n");
+
+        assert_eq!(
+            moves_of(&outcome),
+            Some(vec![MoveOperation::new(
+                PathBuf::from("b/1001.cpp"),
+                PathBuf::from("1001.cpp")
+            )])
+        );
+    }
+
+    #[test]
+    fn overrides_reproduce_accepted_plan_and_conflicts() {
+        let entries = vec![
+            entry("a/1001.cpp", Some(1001)),
+            entry("b/1001.cpp", Some(1001)),
+            entry("c/1001.cpp", Some(1001)),
+        ];
+
+        let (outcome, _) = run(&entries, "r 1\na\n");
+        let ReviewOutcome::Accepted { moves, overrides } = outcome else {
+            panic!("expected accepted plan");
+        };
+        let mut events = Vec::new();
+        let replanned = plan_moves_with(
+            &entries,
+            &PlanOptions::new(20),
+            &NumberKey,
+            &overrides,
+            &mut |event: &Event| events.push(event.clone()),
+        );
+
+        assert_eq!(replanned, moves);
+        assert!(events.contains(&Event::Conflict {
+            from: PathBuf::from("c/1001.cpp"),
+            to: PathBuf::from("1001_conflict1.cpp"),
+        }));
+    }
+
+    #[test]
+    fn edited_destination_is_kept() {
+        let entries = vec![entry("main.cpp", None)];
+
+        let (outcome, _) = run(&entries, "e 1 misc/main.cpp\na\n");
+
+        assert_eq!(
+            moves_of(&outcome),
+            Some(vec![MoveOperation::new(
+                PathBuf::from("main.cpp"),
+                PathBuf::from("misc/main.cpp")
+            )])
+        );
+    }
+
+    #[test]
+    fn edit_rejects_paths_outside_root() {
+        let entries = vec![entry("main.cpp", None)];
+
+        let (outcome, output) = run(&entries, "e 1 ../main.cpp\na\n");
+
+        assert!(output.contains("상대 경로만"));
+        assert_eq!(
+            moves_of(&outcome),
+            Some(vec![MoveOperation::new(
+                PathBuf::from("main.cpp"),
+                PathBuf::from("etc/main.cpp")
+            )])
+        );
+    }
+
+    #[test]
+    fn edit_rejects_destinations_without_file_name() {
+        let entries = vec![entry("main.cpp", None)];
+
+        for destination in ["./", ".", "etc/", "etc/."] {
+            let (outcome, output) = run(&entries, &format!("e 1 {destination}\na\n"));
+
+            assert!(output.contains("상대 경로만"), "{destination}");
+            assert_eq!(
+                moves_of(&outcome),
+                Some(vec![MoveOperation::new(
+                    PathBuf::from("main.cpp"),
+                    PathBuf::from("etc/main.cpp")
+                )])
+            );
+        }
+    }
+
+    #[test]
+    fn folders_are_collapsed_until_opened() {
+        let entries = vec![entry("main.cpp", None)];
+
+        let (_, output) = run(&entries, "o etc\nq\n");
+
+        assert!(output.contains("▸ etc/ (1 개)"));
+        assert!(output.contains("▾ etc/ (1 개)"));
+        assert!(output.contains("[1] main.cpp -> etc/main.cpp"));
+    }
+}
@@ -0,0 +1,277 @@
+//! 새 풀이 파일 생성.
+//!
+//! 템플릿에 문제 정보를 채워 새 소스 파일을 만듭니다. 템플릿은 아래 순서로 찾고,
+//! 없으면 내장 템플릿을 씁니다.
+//!
+//! 1. `<대상 디렉토리>/.ps-organizer/templates/<확장자>`
+//! 2. `$XDG_CONFIG_HOME/ps-organizer/templates/<확장자>`
+//!    (없으면 `~/.config/ps-organizer/templates/<확장자>`)
+//!
+//! 템플릿에는 `{number}`, `{title}`, `{date}`, `{author}`, `{url}`, `{lang}`
+//! 자리표시자를 쓸 수 있습니다.
+
+use crate::extension::ExtensionPolicy;
+use crate::header::Platform;
+use crate::metadata::{Date, STATE_DIR};
+use crate::scanner::ScanOptions;
+use crate::types::Error;
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+const TEMPLATE_DIR: &str = "templates";
+
+const CPP_TEMPLATE: &str = "\
+// {url}
+// {number}번: {title}
+// {author} {date}
+
+#include <bits/stdc++.h>
+using namespace std;
+
+int main() {
+    ios::sync_with_stdio(false);
+    cin.tie(nullptr);
+
+    return 0;
+}
+";
+
+const C_TEMPLATE: &str = "\
+// {url}
+// {number}번: {title}
+// {author} {date}
+
+#include <stdio.h>
+
+int main(void) {
+    return 0;
+}
+";
+
+const PY_TEMPLATE: &str = "\
+# {url}
+# {number}번: {title}
+# {author} {date}
+
+import sys
+
+input = sys.stdin.readline
+";
+
+/// 템플릿에 채울 값.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateContext {
+    /// 문제 번호.
+    pub number: u32,
+    /// 문제 제목 (메타데이터에 없으면 빈 문자열).
+    pub title: String,
+    /// 파일을 만든 날짜.
+    pub date: Date,
+    /// 작성자.
+    pub author: String,
+    /// 문제 주소.
+    pub url: String,
+    /// 정식 확장자 (점 제외).
+    pub lang: String,
+}
+
+impl TemplateContext {
+    /// 백준 문제 주소를 채운 값을 생성합니다.
+    pub fn new(number: u32, lang: impl Into<String>, date: Date) -> Self {
+        Self {
+            number,
+            title: String::new(),
+            date,
+            author: String::new(),
+            url: Platform::Boj.problem_url(number),
+            lang: lang.into(),
+        }
+    }
+}
+
+/// 템플릿의 자리표시자를 값으로 바꿉니다. 모르는 자리표시자는 그대로 둡니다.
+pub fn render(template: &str, context: &TemplateContext) -> String {
+    let date = format!(
+        "{:04}-{:02}-{:02}",
+        context.date.year, context.date.month, context.date.day
+    );
+    [
+        ("number", context.number.to_string()),
+        ("title", context.title.clone()),
+        ("date", date),
+        ("author", context.author.clone()),
+        ("url", context.url.clone()),
+        ("lang", context.lang.clone()),
+    ]
+    .iter()
+    .fold(template.to_string(), |text, (name, value)| {
+        text.replace(&format!("{{{name}}}"), value)
+    })
+}
+
+/// 언어 이름을 풀이 파일의 정식 확장자로 바꿉니다 (예: `c++` → `cpp`).
+///
+/// 스캐너가 기본으로 수집하는 확장자가 아니면 `None`을 반환합니다.
+pub fn solution_extension(lang: &str) -> Option<String> {
+    ExtensionPolicy::default().canonical(
+        lang.trim_start_matches('.'),
+        &ScanOptions::default().extensions,
+    )
+}
+
+/// 확장자에 맞는 내장 템플릿.
+pub fn builtin_template(ext: &str) -> Option<&'static str> {
+    match ext {
+        "cpp" => Some(CPP_TEMPLATE),
+        "c" => Some(C_TEMPLATE),
+        "py" => Some(PY_TEMPLATE),
+        _ => None,
+    }
+}
+
+/// 사용자 설정 디렉토리의 템플릿 폴더.
+fn user_template_dir() -> Option<PathBuf> {
+    let config = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+    Some(config.join("ps-organizer").join(TEMPLATE_DIR))
+}
+
+/// 확장자에 맞는 템플릿을 찾습니다. 어디에도 없으면 빈 문자열을 반환합니다.
+///
+/// # Errors
+///
+/// 템플릿 파일이 있지만 읽을 수 없으면 에러를 반환합니다.
+pub fn load_template(root: &Path, ext: &str) -> Result<String, Error> {
+    load_template_from(root, user_template_dir(), ext)
+}
+
+/// 사용자 템플릿 폴더를 `user_dir`로 두고 [`load_template`]과 같이 찾습니다.
+fn load_template_from(root: &Path, user_dir: Option<PathBuf>, ext: &str) -> Result<String, Error> {
+    let candidates = [Some(root.join(STATE_DIR).join(TEMPLATE_DIR)), user_dir];
+    for dir in candidates.into_iter().flatten() {
+        let path = dir.join(ext);
+        match fs::read_to_string(&path) {
+            Ok(template) => return Ok(template),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+            Err(source) => return Err(Error::from_io(path, source)),
+        }
+    }
+    Ok(builtin_template(ext).unwrap_or_default().to_string())
+}
+
+/// `variant`번째 풀이의 파일명 (1이면 `1000.cpp`, 2부터 `1000_2.cpp`).
+pub fn file_name(number: u32, ext: &str, variant: u32) -> String {
+    if variant <= 1 {
+        format!("{number}.{ext}")
+    } else {
+        format!("{number}_{variant}.{ext}")
+    }
+}
+
+/// `folder`에 아직 없는 가장 작은 변형 파일명의 경로를 반환합니다.
+///
+/// `folder`는 `root` 기준 상대 경로이며, 반환값도 같은 기준입니다.
+pub fn next_variant(root: &Path, folder: &Path, number: u32, ext: &str) -> PathBuf {
+    (2..=u32::MAX)
+        .map(|variant| folder.join(file_name(number, ext, variant)))
+        .find(|path| !root.join(path).exists())
+        .unwrap_or_default()
+}
+
+/// 새 파일을 만들고 내용을 씁니다. 필요한 상위 폴더도 만듭니다.
+///
+/// # Errors
+///
+/// 파일이 이미 있으면 [`Error::DestinationExists`]를, 그 밖에 폴더나 파일을
+/// 만들 수 없으면 해당 에러를 반환합니다.
+pub fn create_file(path: &Path, contents: &str) -> Result<(), Error> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|source| Error::from_io(parent.to_path_buf(), source))?;
+    }
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .map_err(|source| Error::from_io(path.to_path_buf(), source))?;
+    file.write_all(contents.as_bytes())
+        .map_err(|source| Error::from_io(path.to_path_buf(), source))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::parse_date;
+    use tempfile::TempDir;
+
+    fn context() -> TemplateContext {
+        let mut context = TemplateContext::new(1000, "cpp", parse_date("2024-03-01").unwrap());
+        context.title = "A+B".to_string();
+        context.author = "kim".to_string();
+        context
+    }
+
+    #[test]
+    fn render_fills_placeholders() {
+        let text = render(
+            "{url}\n{number} {title} {author} {date} {lang} {unknown}",
+            &context(),
+        );
+
+        assert_eq!(
+            text,
+            "https://www.acmicpc.net/problem/1000\n1000 A+B kim 2024-03-01 cpp {unknown}"
+        );
+    }
+
+    #[test]
+    fn solution_extension_accepts_aliases() {
+        assert_eq!(solution_extension("cpp").as_deref(), Some("cpp"));
+        assert_eq!(solution_extension(".C++").as_deref(), Some("cpp"));
+        assert_eq!(solution_extension("py3").as_deref(), Some("py"));
+        assert_eq!(solution_extension("java"), None);
+    }
+
+    #[test]
+    fn load_template_prefers_root_template() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path().join(STATE_DIR).join(TEMPLATE_DIR);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("cpp"), "// {number}\n").unwrap();
+        let config = TempDir::new().unwrap();
+        fs::write(config.path().join("cpp"), "// user\n").unwrap();
+        fs::write(config.path().join("py"), "# user\n").unwrap();
+        let load = |ext| load_template_from(temp.path(), Some(config.path().to_path_buf()), ext);
+
+        assert_eq!(load("cpp").unwrap(), "// {number}\n");
+        assert_eq!(load("py").unwrap(), "# user\n");
+        assert_eq!(load("c").unwrap(), C_TEMPLATE);
+    }
+
+    #[test]
+    fn next_variant_skips_existing_files() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir(temp.path().join("1000")).unwrap();
+        fs::write(temp.path().join("1000/1000_2.cpp"), "").unwrap();
+
+        let path = next_variant(temp.path(), Path::new("1000"), 1000, "cpp");
+
+        assert_eq!(path, Path::new("1000/1000_3.cpp"));
+    }
+
+    #[test]
+    fn create_file_refuses_to_overwrite() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("1000/1000.cpp");
+
+        create_file(&path, "first").unwrap();
+        let result = create_file(&path, "second");
+
+        assert!(matches!(result, Err(Error::DestinationExists { .. })));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "first");
+    }
+}
@@ -1,69 +1,600 @@
 //! 디렉토리 스캔 및 문제 번호 추출.
 
-use crate::types::FileEntry;
-use std::path::Path;
-use walkdir::WalkDir;
+use crate::cache::{self, ScanCache};
+use crate::events::{Event, NoopObserver, Observer};
+use crate::extension::ExtensionPolicy;
+use crate::header::{HeaderInfo, HeaderMismatch, read_header_in};
+use crate::locate::related_number;
+use crate::metadata::STATE_DIR;
+use crate::planner::split_conflict_suffix;
+use crate::types::{Error, FileEntry};
+use crate::vfs::{EntryKind, FileSystem, RealFs};
+use rayon::ThreadPoolBuilder;
+use rayon::prelude::*;
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::io;
+use std::path::{Path, PathBuf};
 
 const VALID_EXTENSIONS: [&str; 3] = ["cpp", "c", "py"];
 
+/// 심볼릭 링크 처리 방식.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SymlinkPolicy {
+    /// 링크를 무시합니다.
+    #[default]
+    Skip,
+    /// 링크를 따라가 실제 파일을 수집합니다.
+    ///
+    /// 순환은 순회 에러로, 대상 디렉토리 밖을 가리키는 링크는
+    /// [`Error::OutsideRoot`]로 보고하고 건너뜁니다. 같은 파일에 여러 경로로
+    /// 닿으면 한 번만 수집하며, 항목의 경로는 링크가 아닌 실제 파일 경로입니다.
+    /// 이 방식으로 정리하면 [`Organizer::execute`]가 옮긴 파일을 가리키던 링크를
+    /// 고치고 링크가 가리키는 폴더를 남겨, 깨진 링크가 생기지 않습니다.
+    ///
+    /// [`Organizer::execute`]: crate::organizer::Organizer::execute
+    Follow,
+    /// 파일 이름을 가진 링크를 따라가지 않고 링크 자체를 파일처럼 옮깁니다.
+    MoveLink,
+}
+
+/// 스캔 옵션.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanOptions {
+    /// 수집할 확장자 목록 (점 제외).
+    pub extensions: Vec<String>,
+    /// 확장자 인식 규칙.
+    pub extension_policy: ExtensionPolicy,
+    /// 심볼릭 링크 처리 방식.
+    pub symlinks: SymlinkPolicy,
+    /// 문제 번호를 찾을 머리 주석 줄 수 (0이면 파일 내용을 읽지 않음).
+    pub header_lines: usize,
+    /// 증분 스캔 캐시를 쓸지 여부.
+    ///
+    /// 머리 주석을 읽거나 링크를 따라가는 스캔에는 쓰지 않습니다.
+    pub cache: bool,
+    /// 스캔에 쓸 작업 스레드 수 (1이면 현재 스레드에서 순회, 0이면 CPU 수만큼).
+    ///
+    /// 캐시를 쓰거나 링크를 따라가는 스캔은 항상 현재 스레드에서 순회합니다.
+    pub jobs: usize,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            extensions: VALID_EXTENSIONS.iter().map(ToString::to_string).collect(),
+            extension_policy: ExtensionPolicy::default(),
+            symlinks: SymlinkPolicy::default(),
+            header_lines: 0,
+            cache: false,
+            jobs: 1,
+        }
+    }
+}
+
+/// 풀이 파일명의 구성.
+///
+/// `번호[_변형][_conflictN].확장자` 형식을 나눈 값입니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SolutionName {
+    /// 문제 번호.
+    pub number: u32,
+    /// 변형 번호 (`1000_2.cpp` → 2).
+    pub variant: Option<u32>,
+    /// 충돌 번호 (`1000_conflict1.cpp` → 1).
+    pub conflict: Option<u32>,
+}
+
 /// 파일명에서 문제 번호를 추출합니다.
 ///
-/// `숫자.확장자` 형식의 파일명만 인식합니다 (예: `1010.cpp` → `Some(1010)`).
-/// 파일명에 숫자 외의 문자가 포함되거나 지원하지 않는 확장자면 `None`을 반환합니다.
-pub fn extract_problem_number(filename: &str) -> Option<u32> {
-    for ext in VALID_EXTENSIONS {
-        let suffix = format!(".{ext}");
-        if let Some(stem) = filename.strip_suffix(&suffix)
-            && !stem.is_empty()
-            && stem.chars().all(|c| c.is_ascii_digit())
-        {
-            return stem.parse::<u32>().ok();
-        }
+/// `숫자.확장자` 형식의 파일명을 인식합니다 (예: `1010.cpp` → `Some(1010)`).
+/// 변형(`1010_2.cpp`)과 충돌 이름(`1010_conflict1.cpp`)도 같은 문제로 봅니다.
+/// 확장자는 대소문자를 무시하고 별칭도 인식합니다 (`1010.CXX` → `Some(1010)`).
+/// 그 밖의 문자가 포함되거나 지원하지 않는 확장자면 `None`을 반환합니다.
+pub fn extract_problem_number(filename: impl AsRef<OsStr>) -> Option<u32> {
+    extract_problem_number_with(filename, &ScanOptions::default())
+}
+
+/// 스캔 옵션의 확장자 목록과 인식 규칙으로 파일명에서 문제 번호를 추출합니다.
+///
+/// 파일명을 바이트 단위로 비교하므로 UTF-8이 아닌 파일명도 처리합니다.
+pub fn extract_problem_number_with(
+    filename: impl AsRef<OsStr>,
+    options: &ScanOptions,
+) -> Option<u32> {
+    parse_solution_name(filename, options).map(|name| name.number)
+}
+
+/// 풀이 파일명을 문제 번호, 변형 번호, 충돌 번호로 나눕니다.
+pub fn parse_solution_name(
+    filename: impl AsRef<OsStr>,
+    options: &ScanOptions,
+) -> Option<SolutionName> {
+    let name = Path::new(filename.as_ref());
+    canonical_extension(name, options)?;
+    parse_solution_stem(name.file_stem()?)
+}
+
+/// 확장자를 뺀 파일명을 문제 번호, 변형 번호, 충돌 번호로 나눕니다.
+fn parse_solution_stem(stem: &OsStr) -> Option<SolutionName> {
+    let (stem, conflict) = split_conflict_suffix(stem.as_encoded_bytes());
+    let (number, variant) = match stem.iter().position(|&b| b == b'_') {
+        Some(split) => (&stem[..split], Some(parse_digits(&stem[split + 1..])?)),
+        None => (stem, None),
+    };
+    Some(SolutionName {
+        number: parse_digits(number)?,
+        variant,
+        conflict,
+    })
+}
+
+fn parse_digits(bytes: &[u8]) -> Option<u32> {
+    if bytes.is_empty() || !bytes.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    std::str::from_utf8(bytes).ok()?.parse().ok()
+}
+
+/// 수집 대상이면 파일의 정식 확장자를 반환합니다.
+fn canonical_extension(path: &Path, options: &ScanOptions) -> Option<String> {
+    let ext = path.extension()?.to_str()?;
+    options.extension_policy.canonical(ext, &options.extensions)
+}
+
+/// 스캔 결과.
+#[derive(Debug, Default)]
+pub struct ScanReport {
+    /// 발견한 소스 파일 목록.
+    pub entries: Vec<FileEntry>,
+    /// 순회 중 발생한 에러 목록. 에러가 난 경로는 건너뛰고 스캔을 계속합니다.
+    pub errors: Vec<Error>,
+    /// 파일명과 머리 주석의 문제 번호가 다른 파일 목록.
+    pub mismatches: Vec<HeaderMismatch>,
+    /// 파일명이 문제 번호로 시작하지만 소스 파일이 아닌 딸린 파일 목록
+    /// (예: `1000.in`, `1000_input.txt`). 대상 디렉토리 기준 상대 경로입니다.
+    pub companions: Vec<PathBuf>,
+    /// 캐시를 쓴 스캔이면 이번 순회로 갱신한 캐시.
+    ///
+    /// 스캔은 캐시를 저장하지 않습니다. 정리를 실행한 뒤
+    /// [`Organizer::save_cache`]로 저장하면 다음 스캔이 씁니다.
+    ///
+    /// [`Organizer::save_cache`]: crate::organizer::Organizer::save_cache
+    pub cache: Option<ScanCache>,
+}
+
+impl ScanReport {
+    fn merge(mut self, other: Self) -> Self {
+        self.entries.extend(other.entries);
+        self.errors.extend(other.errors);
+        self.mismatches.extend(other.mismatches);
+        self.companions.extend(other.companions);
+        self
+    }
+
+    /// 순회 순서와 관계없이 같은 결과가 나오도록 경로 순으로 정렬합니다.
+    fn sort(&mut self) {
+        self.entries
+            .sort_unstable_by(|a, b| a.current_path.cmp(&b.current_path));
+        self.mismatches.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+        self.companions.sort_unstable();
+        self.errors.sort_by(|a, b| a.path().cmp(b.path()));
     }
-    None
 }
 
 /// 디렉토리를 재귀적으로 스캔하여 소스 파일 목록을 반환합니다.
 ///
-/// `.cpp`, `.c`, `.py` 확장자를 가진 파일만 수집합니다.
+/// `.cpp`, `.c`, `.py` 확장자를 가진 파일만 수집합니다. 순회 에러 목록이
+/// 필요하면 [`scan_directory_with`]를 사용하세요.
 pub fn scan_directory(root: &Path) -> Vec<FileEntry> {
-    let mut entries = Vec::new();
+    scan_directory_with(root, &ScanOptions::default(), &mut NoopObserver).entries
+}
 
-    for entry in WalkDir::new(root)
-        .into_iter()
-        .filter_map(Result::ok)
-        .filter(|e| e.file_type().is_file())
-    {
-        let path = entry.path();
+/// 링크를 모두 풀어 실제 경로를 구하고, 대상 디렉토리 안에 있는지 확인합니다.
+fn resolve_inside(
+    fs: &dyn FileSystem,
+    canonical_root: &Path,
+    path: &Path,
+) -> Result<PathBuf, Error> {
+    let real = fs
+        .canonicalize(path)
+        .map_err(|source| Error::from_io(path.to_path_buf(), source))?;
+    if real.starts_with(canonical_root) {
+        Ok(real)
+    } else {
+        Err(Error::OutsideRoot {
+            path: path.to_path_buf(),
+            target: real,
+        })
+    }
+}
+
+/// 디렉토리를 읽지 못한 I/O 에러를 분류합니다.
+pub(crate) fn dir_error(path: PathBuf, source: io::Error) -> Error {
+    if source.kind() == io::ErrorKind::PermissionDenied {
+        Error::PermissionDenied { path, source }
+    } else {
+        Error::Walk { path, source }
+    }
+}
+
+/// 옵션에 맞는 파일을 수집하고, 발견한 파일마다 [`Event::ScanFound`]를 보고합니다.
+///
+/// 읽을 수 없는 디렉토리는 건너뛰고 [`ScanReport::errors`]에 모읍니다.
+/// UTF-8이 아닌 파일명도 그대로 수집합니다. 심볼릭 링크는
+/// [`ScanOptions::symlinks`]에 따라 처리합니다.
+///
+/// [`ScanOptions::header_lines`]가 0보다 크면 파일 앞부분의 주석에서도 문제 번호를
+/// 찾아, 파일명에 번호가 없는 파일(`main.cpp` 등)에 씁니다. 두 번호가 다르면
+/// 파일명 번호를 쓰고 [`Event::HeaderMismatch`]로 보고합니다.
+///
+/// [`ScanOptions::cache`]가 켜져 있으면 [`cache`](crate::cache) 모듈의 캐시로
+/// 바뀌지 않은 디렉토리의 목록을 다시 읽지 않고, 갱신한 캐시를 결과에 담습니다.
+/// [`ScanOptions::jobs`]가 1이 아니면 디렉토리와 파일을 여러 스레드에서 나눠
+/// 처리합니다.
+///
+/// 어떤 방식으로 순회하든 결과와 이벤트는 경로 순으로 정렬되어 있습니다.
+pub fn scan_directory_with(
+    root: &Path,
+    options: &ScanOptions,
+    observer: &mut dyn Observer,
+) -> ScanReport {
+    scan_directory_in(&RealFs, root, options, observer)
+}
+
+/// 주어진 파일 시스템에서 [`scan_directory_with`]와 같이 스캔합니다.
+pub fn scan_directory_in(
+    fs: &dyn FileSystem,
+    root: &Path,
+    options: &ScanOptions,
+    observer: &mut dyn Observer,
+) -> ScanReport {
+    let follow = options.symlinks == SymlinkPolicy::Follow;
+    let mut report = if options.cache && options.header_lines == 0 && !follow {
+        scan_cached(fs, root, options, observer)
+    } else if options.jobs != 1 && !follow {
+        scan_parallel(fs, root, options)
+    } else {
+        scan_walk(fs, root, options)
+    };
+    report.sort();
 
-        let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("");
+    for entry in &report.entries {
+        if let Ok(index) = report
+            .mismatches
+            .binary_search_by(|mismatch| mismatch.path.cmp(&entry.current_path))
+        {
+            let mismatch = &report.mismatches[index];
+            observer.on_event(&Event::HeaderMismatch {
+                path: mismatch.path.clone(),
+                filename_number: mismatch.filename_number,
+                header_number: mismatch.header_number,
+            });
+        }
+        observer.on_event(&Event::ScanFound {
+            path: entry.current_path.clone(),
+            problem_number: entry.problem_number,
+        });
+    }
+    report
+}
+
+/// 현재 스레드에서 순회합니다.
+fn scan_walk(fs: &dyn FileSystem, root: &Path, options: &ScanOptions) -> ScanReport {
+    let follow = options.symlinks == SymlinkPolicy::Follow;
+    let canonical_root = fs.canonicalize(root).unwrap_or_else(|_| root.to_path_buf());
+    let mut walker = Walker {
+        fs,
+        options,
+        follow,
+        canonical_root: &canonical_root,
+        base: if follow { &canonical_root } else { root },
+        seen: HashSet::new(),
+        report: ScanReport::default(),
+    };
+    walker.visit(root, true, &mut vec![canonical_root.clone()]);
+    walker.report
+}
+
+/// 현재 스레드에서 순회하는 상태.
+struct Walker<'a> {
+    fs: &'a dyn FileSystem,
+    options: &'a ScanOptions,
+    follow: bool,
+    canonical_root: &'a Path,
+    /// 항목의 상대 경로를 구할 기준. 링크를 따라가면 정규화된 대상 경로입니다.
+    base: &'a Path,
+    /// 이미 수집한 실제 경로. 링크를 따라가면 같은 파일에 여러 경로로 닿을 수 있습니다.
+    seen: HashSet<PathBuf>,
+    report: ScanReport,
+}
+
+impl Walker<'_> {
+    /// 디렉토리 하나를 읽어 파일을 수집하고 하위 디렉토리로 내려갑니다.
+    ///
+    /// `ancestors`는 링크를 따라갈 때 순환을 찾으려고, 지금까지 내려온
+    /// 디렉토리의 실제 경로를 담습니다.
+    fn visit(&mut self, dir: &Path, is_root: bool, ancestors: &mut Vec<PathBuf>) {
+        let listing = match self.fs.read_dir(dir) {
+            Ok(listing) => listing,
+            Err(source) => {
+                self.report
+                    .errors
+                    .push(dir_error(dir.to_path_buf(), source));
+                return;
+            }
+        };
+
+        for entry in listing {
+            if is_root && entry.kind == EntryKind::Dir && entry.name == STATE_DIR {
+                continue;
+            }
+            let path = dir.join(&entry.name);
+            let mut kind = entry.kind;
+            if self.follow && kind == EntryKind::Symlink {
+                match self.fs.metadata(&path) {
+                    Ok(metadata) => kind = metadata.kind,
+                    Err(source) => {
+                        self.report.errors.push(dir_error(path, source));
+                        continue;
+                    }
+                }
+            }
+
+            let real = if self.follow {
+                match resolve_inside(self.fs, self.canonical_root, &path) {
+                    Ok(real) => real,
+                    Err(error) => {
+                        self.report.errors.push(error);
+                        continue;
+                    }
+                }
+            } else {
+                path.clone()
+            };
 
-        if !VALID_EXTENSIONS.contains(&ext) {
-            continue;
+            if kind == EntryKind::Dir {
+                if !self.follow {
+                    self.visit(&path, false, ancestors);
+                } else if ancestors.contains(&real) {
+                    self.report.errors.push(Error::Walk {
+                        source: io::Error::other(format!(
+                            "file system loop found: {} points to an ancestor {}",
+                            path.display(),
+                            real.display()
+                        )),
+                        path,
+                    });
+                } else {
+                    ancestors.push(real);
+                    self.visit(&path, false, ancestors);
+                    ancestors.pop();
+                }
+                continue;
+            }
+
+            let collect = kind == EntryKind::File
+                || (kind == EntryKind::Symlink && self.options.symlinks == SymlinkPolicy::MoveLink);
+            if !collect || !self.seen.insert(real.clone()) {
+                continue;
+            }
+            let relative_path = real.strip_prefix(self.base).unwrap_or(&real).to_path_buf();
+            collect_file(
+                self.fs,
+                &mut self.report,
+                self.options,
+                &real,
+                relative_path,
+                kind == EntryKind::Symlink,
+            );
         }
+    }
+}
 
-        let filename = path
-            .file_name()
-            .and_then(|s| s.to_str())
-            .unwrap_or("")
-            .to_string();
+/// [`ScanOptions::jobs`]개의 작업 스레드로 순회합니다.
+///
+/// 스레드 풀을 만들 수 없으면 현재 스레드에서 순회합니다.
+fn scan_parallel(fs: &dyn FileSystem, root: &Path, options: &ScanOptions) -> ScanReport {
+    match ThreadPoolBuilder::new().num_threads(options.jobs).build() {
+        Ok(pool) => pool.install(|| visit_parallel(fs, root, Path::new(""), options)),
+        Err(_) => scan_walk(fs, root, options),
+    }
+}
 
-        if filename.is_empty() {
-            continue;
+/// 디렉토리 하나를 읽고, 그 안의 파일과 하위 디렉토리를 작업 스레드에 나눠 줍니다.
+fn visit_parallel(
+    fs: &dyn FileSystem,
+    root: &Path,
+    relative: &Path,
+    options: &ScanOptions,
+) -> ScanReport {
+    let dir = if relative.as_os_str().is_empty() {
+        root.to_path_buf()
+    } else {
+        root.join(relative)
+    };
+    let mut report = ScanReport::default();
+    let listing = match fs.read_dir(&dir) {
+        Ok(listing) => listing,
+        Err(source) => {
+            report.errors.push(dir_error(dir, source));
+            return report;
         }
+    };
 
-        let relative_path = path.strip_prefix(root).unwrap_or(path).to_path_buf();
+    let mut files = Vec::new();
+    let mut dirs = Vec::new();
+    for entry in listing {
+        if entry.kind == EntryKind::Dir {
+            if !(relative.as_os_str().is_empty() && entry.name == STATE_DIR) {
+                dirs.push(relative.join(entry.name));
+            }
+        } else if entry.kind == EntryKind::File
+            || (entry.kind == EntryKind::Symlink && options.symlinks == SymlinkPolicy::MoveLink)
+        {
+            files.push((relative.join(entry.name), entry.kind == EntryKind::Symlink));
+        }
+    }
 
-        let problem_number = extract_problem_number(&filename);
+    let (found, nested) = rayon::join(
+        || {
+            files
+                .into_par_iter()
+                .fold(
+                    ScanReport::default,
+                    |mut report, (relative_path, is_symlink)| {
+                        let path = root.join(&relative_path);
+                        collect_file(fs, &mut report, options, &path, relative_path, is_symlink);
+                        report
+                    },
+                )
+                .reduce(ScanReport::default, ScanReport::merge)
+        },
+        || {
+            dirs.into_par_iter()
+                .map(|relative| visit_parallel(fs, root, &relative, options))
+                .reduce(ScanReport::default, ScanReport::merge)
+        },
+    );
+    report.merge(found).merge(nested)
+}
 
-        entries.push(FileEntry {
-            current_path: relative_path,
-            problem_number,
-            filename,
+/// `jobs`가 1이 아니면 그 수만큼의 작업 스레드 풀(0이면 CPU 수만큼)에서 `op`에
+/// `true`를 넘겨 실행합니다. 1이거나 풀을 만들 수 없으면 현재 스레드에서 `false`를
+/// 넘겨 실행하므로, `op`는 `false`를 받으면 rayon을 쓰지 않아야 합니다.
+pub(crate) fn with_jobs<R: Send>(jobs: usize, op: impl FnOnce(bool) -> R + Send) -> R {
+    let pool = (jobs != 1)
+        .then(|| ThreadPoolBuilder::new().num_threads(jobs).build().ok())
+        .flatten();
+    match pool {
+        Some(pool) => pool.install(|| op(true)),
+        None => op(false),
+    }
+}
+
+/// 캐시를 써서 스캔하고, 갱신한 캐시를 [`ScanReport::cache`]에 담습니다.
+///
+/// [`ScanOptions::jobs`]가 1이 아니면 새로 읽을 디렉토리와 파일 수집을 작업 스레드에
+/// 나눠 처리합니다. 스레드 풀을 만들 수 없으면 현재 스레드에서 처리합니다.
+fn scan_cached(
+    fs: &dyn FileSystem,
+    root: &Path,
+    options: &ScanOptions,
+    observer: &mut dyn Observer,
+) -> ScanReport {
+    let previous = ScanCache::load(fs, root);
+    let scan = |parallel: bool| {
+        let walk = cache::walk_in(fs, root, previous.as_ref(), parallel);
+        let files = walk.files.iter().filter(|(_, kind)| {
+            *kind == EntryKind::File
+                || (*kind == EntryKind::Symlink && options.symlinks == SymlinkPolicy::MoveLink)
+        });
+        let collect = |mut report: ScanReport, (relative_path, kind): &(PathBuf, EntryKind)| {
+            let path = root.join(relative_path);
+            let is_symlink = *kind == EntryKind::Symlink;
+            collect_file(
+                fs,
+                &mut report,
+                options,
+                &path,
+                relative_path.clone(),
+                is_symlink,
+            );
+            report
+        };
+        let found = if parallel {
+            files
+                .collect::<Vec<_>>()
+                .into_par_iter()
+                .fold(ScanReport::default, collect)
+                .reduce(ScanReport::default, ScanReport::merge)
+        } else {
+            files.fold(ScanReport::default(), collect)
+        };
+        (walk, found)
+    };
+    let (walk, found) = with_jobs(options.jobs, scan);
+    observer.on_event(&Event::ScanCached {
+        reused: walk.reused,
+        read: walk.read,
+    });
+
+    ScanReport {
+        errors: walk.errors,
+        cache: Some(walk.cache),
+        ..ScanReport::default()
+    }
+    .merge(found)
+}
+
+/// 수집 대상 파일이면 항목을 만들어 보고서에 더합니다.
+fn collect_file(
+    fs: &dyn FileSystem,
+    report: &mut ScanReport,
+    options: &ScanOptions,
+    path: &Path,
+    relative_path: PathBuf,
+    is_symlink: bool,
+) {
+    if path.extension().is_some_and(|ext| ext.to_str().is_none()) {
+        // 풀이 파일명 꼴인데 확장자를 읽지 못하면 소스 파일인지 알 수 없으므로, 조용히
+        // 건너뛰지 않고 알립니다. 그 밖의 파일은 원래 무시하는 파일이므로 넘어갑니다.
+        if path.file_stem().and_then(parse_solution_stem).is_some() {
+            report.errors.push(Error::InvalidUtf8Name {
+                path: relative_path,
+            });
+        }
+        return;
+    }
+    let Some(filename) = path.file_name().map(OsStr::to_os_string) else {
+        return;
+    };
+    let Some(extension) = canonical_extension(path, options) else {
+        if !is_symlink && related_number(&filename).is_some() {
+            report.companions.push(relative_path);
+        }
+        return;
+    };
+
+    let header = if options.header_lines > 0 && !is_symlink {
+        match read_header_in(fs, path, options.header_lines) {
+            Ok(header) => header,
+            Err(source) => {
+                report
+                    .errors
+                    .push(Error::from_source(path.to_path_buf(), source));
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let filename_number = extract_problem_number_with(&filename, options);
+    let problem_number = filename_number.or(header.map(|h| h.problem_number));
+    if let (
+        Some(filename_number),
+        Some(HeaderInfo {
+            problem_number: header_number,
+            ..
+        }),
+    ) = (filename_number, header)
+        && filename_number != header_number
+    {
+        report.mismatches.push(HeaderMismatch {
+            path: relative_path.clone(),
+            filename_number,
+            header_number,
         });
     }
 
-    entries
+    report.entries.push(FileEntry {
+        current_path: relative_path,
+        problem_number,
+        filename,
+        extension,
+        platform: header.map(|h| h.platform),
+    });
 }
 
 #[cfg(test)]
@@ -95,6 +626,27 @@ mod tests {
         assert_eq!(extract_problem_number("1234_solution.cpp"), None);
     }
 
+    #[test]
+    fn accept_variant_and_conflict_suffixes() {
+        let options = ScanOptions::default();
+        let parse = |name: &str| parse_solution_name(name, &options);
+
+        assert_eq!(extract_problem_number("1234_2.cpp"), Some(1234));
+        assert_eq!(extract_problem_number("1234_conflict1.cpp"), Some(1234));
+        assert_eq!(
+            parse("1234_2_conflict3.py"),
+            Some(SolutionName {
+                number: 1234,
+                variant: Some(2),
+                conflict: Some(3),
+            })
+        );
+        assert_eq!(parse("1234.c").unwrap().conflict, None);
+        for name in ["1234_conflict.cpp", "1234__2.cpp", "1234_2_3.cpp", "_2.cpp"] {
+            assert_eq!(parse(name), None, "{name}");
+        }
+    }
+
     #[test]
     fn reject_no_number() {
         assert_eq!(extract_problem_number("main.cpp"), None);
@@ -120,8 +672,402 @@ mod tests {
         assert_eq!(extract_problem_number("0.cpp"), Some(0));
     }
 
+    #[cfg(unix)]
     #[test]
-    fn reject_capital_extension() {
-        assert_eq!(extract_problem_number("1010.CPP"), None);
+    fn scan_reports_unreadable_directories() {
+        use std::fs::{self, File};
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let locked = temp.path().join("locked");
+        fs::create_dir(&locked).unwrap();
+        File::create(locked.join("1001.cpp")).unwrap();
+        File::create(temp.path().join("1002.cpp")).unwrap();
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o000)).unwrap();
+
+        let readable = fs::read_dir(&locked).is_ok();
+        let reports = [1, 4].map(|jobs| {
+            let options = ScanOptions {
+                jobs,
+                ..ScanOptions::default()
+            };
+            scan_directory_with(temp.path(), &options, &mut NoopObserver)
+        });
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+
+        if readable {
+            // root 권한으로 실행하면 권한 검사가 적용되지 않습니다.
+            return;
+        }
+        for report in reports {
+            assert_eq!(report.entries.len(), 1);
+            assert!(matches!(
+                report.errors.as_slice(),
+                [Error::PermissionDenied { .. }]
+            ));
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn extract_from_raw_bytes() {
+        use std::os::unix::ffi::OsStrExt;
+
+        assert_eq!(
+            extract_problem_number(OsStr::from_bytes(b"1000.cpp")),
+            Some(1000)
+        );
+        assert_eq!(
+            extract_problem_number(OsStr::from_bytes(b"\xb9\xae.cpp")),
+            None
+        );
+        assert_eq!(
+            extract_problem_number(OsStr::from_bytes(b"1000.\xff")),
+            None
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn scan_keeps_non_utf8_names() {
+        use std::fs::File;
+        use std::os::unix::ffi::OsStrExt;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let name = OsStr::from_bytes(b"\xb9\xae\xc1\xa6.cpp");
+        File::create(temp.path().join(name)).unwrap();
+        File::create(temp.path().join("1001.cpp")).unwrap();
+
+        let report = scan_directory_with(temp.path(), &ScanOptions::default(), &mut NoopObserver);
+
+        assert!(report.errors.is_empty());
+        assert_eq!(report.entries.len(), 2);
+        let entry = report.entries.iter().find(|e| e.filename == name).unwrap();
+        assert_eq!(entry.problem_number, None);
+        assert_eq!(entry.current_path, Path::new(name));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn scan_reports_non_utf8_extension() {
+        use std::fs::File;
+        use std::os::unix::ffi::OsStrExt;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let name = OsStr::from_bytes(b"1000.\xc6\xc4\xc0\xcf");
+        File::create(temp.path().join(name)).unwrap();
+        File::create(temp.path().join(OsStr::from_bytes(b"notes.\xc6\xc4"))).unwrap();
+        File::create(temp.path().join("1001.cpp")).unwrap();
+
+        let report = scan_directory_with(temp.path(), &ScanOptions::default(), &mut NoopObserver);
+
+        assert_eq!(report.entries.len(), 1);
+        assert!(matches!(
+            report.errors.as_slice(),
+            [Error::InvalidUtf8Name { path }] if path == Path::new(name)
+        ));
+    }
+
+    #[test]
+    fn strict_rejects_capital_extension() {
+        let options = ScanOptions {
+            extension_policy: ExtensionPolicy::strict(),
+            ..ScanOptions::default()
+        };
+        assert_eq!(extract_problem_number_with("1010.CPP", &options), None);
+        assert_eq!(extract_problem_number_with("1010.cxx", &options), None);
+    }
+
+    #[test]
+    fn accept_capital_and_alias_extensions() {
+        assert_eq!(extract_problem_number("1010.CPP"), Some(1010));
+        assert_eq!(extract_problem_number("1010.Py"), Some(1010));
+        assert_eq!(extract_problem_number("1010.cxx"), Some(1010));
+    }
+
+    #[test]
+    fn scan_records_canonical_extension() {
+        use std::fs::File;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        File::create(temp.path().join("1010.CXX")).unwrap();
+        File::create(temp.path().join("1011.java")).unwrap();
+
+        let report = scan_directory_with(temp.path(), &ScanOptions::default(), &mut NoopObserver);
+
+        assert_eq!(report.entries.len(), 1);
+        assert_eq!(report.entries[0].extension, "cpp");
+        assert_eq!(report.entries[0].normalized_filename(), "1010.cpp");
+    }
+
+    #[cfg(unix)]
+    fn symlink_options(symlinks: SymlinkPolicy) -> ScanOptions {
+        ScanOptions {
+            symlinks,
+            ..ScanOptions::default()
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn skip_policy_ignores_links() {
+        use std::fs::{self, File};
+        use std::os::unix::fs::symlink;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        fs::create_dir(temp.path().join("real")).unwrap();
+        File::create(temp.path().join("real/1001.cpp")).unwrap();
+        symlink("real/1001.cpp", temp.path().join("1002.cpp")).unwrap();
+        symlink("real", temp.path().join("linked")).unwrap();
+
+        let report = scan_directory_with(
+            temp.path(),
+            &symlink_options(SymlinkPolicy::Skip),
+            &mut NoopObserver,
+        );
+
+        assert!(report.errors.is_empty());
+        let paths: Vec<_> = report.entries.iter().map(|e| &e.current_path).collect();
+        assert_eq!(paths, vec![Path::new("real/1001.cpp")]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn follow_policy_collects_each_real_file_once() {
+        use std::fs::{self, File};
+        use std::os::unix::fs::symlink;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        fs::create_dir(temp.path().join("real")).unwrap();
+        File::create(temp.path().join("real/1001.cpp")).unwrap();
+        symlink("real/1001.cpp", temp.path().join("1002.cpp")).unwrap();
+        symlink("real", temp.path().join("linked")).unwrap();
+
+        let report = scan_directory_with(
+            temp.path(),
+            &symlink_options(SymlinkPolicy::Follow),
+            &mut NoopObserver,
+        );
+
+        assert!(report.errors.is_empty());
+        assert_eq!(report.entries.len(), 1);
+        assert_eq!(report.entries[0].current_path, Path::new("real/1001.cpp"));
+        assert_eq!(report.entries[0].problem_number, Some(1001));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn follow_policy_stays_inside_root() {
+        use std::fs::{self, File};
+        use std::os::unix::fs::symlink;
+
+        let outside = tempfile::TempDir::new().unwrap();
+        File::create(outside.path().join("2001.cpp")).unwrap();
+        let temp = tempfile::TempDir::new().unwrap();
+        File::create(temp.path().join("1001.cpp")).unwrap();
+        symlink(outside.path(), temp.path().join("shared")).unwrap();
+        symlink(
+            outside.path().join("2001.cpp"),
+            temp.path().join("2001.cpp"),
+        )
+        .unwrap();
+        fs::create_dir(temp.path().join("sub")).unwrap();
+        symlink("..", temp.path().join("sub/up")).unwrap();
+
+        let report = scan_directory_with(
+            temp.path(),
+            &symlink_options(SymlinkPolicy::Follow),
+            &mut NoopObserver,
+        );
+
+        let paths: Vec<_> = report.entries.iter().map(|e| &e.current_path).collect();
+        assert_eq!(paths, vec![Path::new("1001.cpp")]);
+        let outside_errors = report
+            .errors
+            .iter()
+            .filter(|e| matches!(e, Error::OutsideRoot { .. }))
+            .count();
+        assert_eq!(outside_errors, 2);
+        assert!(
+            report
+                .errors
+                .iter()
+                .any(|e| matches!(e, Error::Walk { path, .. } if path.ends_with("sub/up")))
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn move_link_policy_collects_link_itself() {
+        use std::fs::{self, File};
+        use std::os::unix::fs::symlink;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        fs::create_dir(temp.path().join("real")).unwrap();
+        File::create(temp.path().join("real/notes.txt")).unwrap();
+        symlink("real/notes.txt", temp.path().join("1001.cpp")).unwrap();
+        symlink("missing.cpp", temp.path().join("1002.cpp")).unwrap();
+        symlink("real", temp.path().join("linked")).unwrap();
+
+        let report = scan_directory_with(
+            temp.path(),
+            &symlink_options(SymlinkPolicy::MoveLink),
+            &mut NoopObserver,
+        );
+
+        let mut paths: Vec<_> = report.entries.iter().map(|e| &e.current_path).collect();
+        paths.sort();
+        assert_eq!(paths, vec![Path::new("1001.cpp"), Path::new("1002.cpp")]);
+    }
+
+    #[test]
+    fn header_numbers_fill_in_and_report_mismatches() {
+        use crate::header::Platform;
+        use std::fs;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        fs::write(
+            temp.path().join("main.cpp"),
+            "// https://www.acmicpc.net/problem/1000\n",
+        )
+        .unwrap();
+        fs::write(temp.path().join("1001.cpp"), "# BOJ 1002\n").unwrap();
+        let options = ScanOptions {
+            header_lines: 5,
+            ..ScanOptions::default()
+        };
+
+        let mut events = Vec::new();
+        let mut report = scan_directory_with(temp.path(), &options, &mut |e: &Event| {
+            events.push(e.clone());
+        });
+        report
+            .entries
+            .sort_by(|a, b| a.current_path.cmp(&b.current_path));
+
+        assert_eq!(report.entries[0].problem_number, Some(1001));
+        assert_eq!(report.entries[1].problem_number, Some(1000));
+        assert_eq!(report.entries[1].platform, Some(Platform::Boj));
+        assert_eq!(
+            report.mismatches,
+            vec![HeaderMismatch {
+                path: PathBuf::from("1001.cpp"),
+                filename_number: 1001,
+                header_number: 1002,
+            }]
+        );
+        assert!(
+            events
+                .iter()
+                .any(|e| matches!(e, Event::HeaderMismatch { .. }))
+        );
+    }
+
+    #[test]
+    fn headers_are_not_read_by_default() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::write(temp.path().join("main.cpp"), "// BOJ 1000\n").unwrap();
+
+        let report = scan_directory_with(temp.path(), &ScanOptions::default(), &mut NoopObserver);
+
+        assert_eq!(report.entries[0].problem_number, None);
+        assert_eq!(report.entries[0].platform, None);
+    }
+
+    #[test]
+    fn parallel_scan_matches_sequential_scan() {
+        use std::fs;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path();
+        for dir in ["a/b", "c", "1000", STATE_DIR] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        for (name, contents) in [
+            ("3000.cpp", ""),
+            ("a/2000.py", ""),
+            ("a/b/main.cpp", "// BOJ 1500\n"),
+            ("c/1001.c", "// BOJ 1002\n"),
+            ("1000/1000.cpp", ""),
+            ("notes.txt", ""),
+            (".ps-organizer/4000.cpp", ""),
+        ] {
+            fs::write(root.join(name), contents).unwrap();
+        }
+
+        let scan = |jobs| {
+            let options = ScanOptions {
+                header_lines: 5,
+                jobs,
+                ..ScanOptions::default()
+            };
+            let mut events = Vec::new();
+            let report = scan_directory_with(root, &options, &mut |e: &Event| {
+                events.push(e.clone());
+            });
+            (report.entries, report.mismatches, events)
+        };
+
+        let sequential = scan(1);
+        let paths: Vec<_> = sequential
+            .0
+            .iter()
+            .map(|e| e.current_path.clone())
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                "1000/1000.cpp",
+                "3000.cpp",
+                "a/2000.py",
+                "a/b/main.cpp",
+                "c/1001.c"
+            ]
+            .map(PathBuf::from)
+        );
+        for jobs in [0, 2, 8] {
+            assert_eq!(scan(jobs), sequential, "jobs = {jobs}");
+        }
+    }
+
+    #[test]
+    fn cached_scan_matches_for_any_job_count() {
+        use std::fs;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path();
+        for dir in ["a/b", "c", "1000", STATE_DIR] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        for name in [
+            "3000.cpp",
+            "a/2000.py",
+            "a/b/1500.cpp",
+            "c/1001.c",
+            "1000/1000.cpp",
+            "notes.txt",
+        ] {
+            fs::write(root.join(name), "").unwrap();
+        }
+
+        let scan = |jobs| {
+            let options = ScanOptions {
+                cache: true,
+                jobs,
+                ..ScanOptions::default()
+            };
+            let mut events = Vec::new();
+            let report = scan_directory_with(root, &options, &mut |e: &Event| {
+                events.push(e.clone());
+            });
+            (report.entries, report.cache, events)
+        };
+
+        let sequential = scan(1);
+        assert_eq!(sequential.0.len(), 5);
+        assert!(sequential.1.is_some());
+        for jobs in [0, 2, 8] {
+            assert_eq!(scan(jobs), sequential, "jobs = {jobs}");
+        }
     }
 }
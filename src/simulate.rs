@@ -0,0 +1,433 @@
+//! 파일 시스템을 바꾸지 않고 실행을 흉내 내는 dry-run 시뮬레이션.
+//!
+//! 실제 파일 시스템 위에 가상 덧씌움(overlay)을 두고, 실행기와 같은 순서로 폴더를
+//! 만들고 파일을 옮기고 빈 폴더를 지우는 것처럼 기록합니다. 단계마다 실제 실행이
+//! 실패할 조건(사라진 원본, 이미 있는 목적지, 쓰기 권한, 장치 경계, 이름 길이)을
+//! 확인해, 실제 실행과 같은 [`Error`] 분류로 모두 보고합니다.
+
+use crate::executor::{
+    CleanupRules, Step, cleanup_candidates, is_mount_point, is_same_file, schedule,
+};
+use crate::types::{Error, MoveOperation};
+use crate::vfs::{FileSystem, RealFs};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// 파일 이름 한 구성 요소의 최대 길이(바이트).
+const NAME_MAX: usize = 255;
+
+/// 경로 전체의 최대 길이(바이트).
+const PATH_MAX: usize = 4096;
+
+/// 시뮬레이션 결과.
+#[derive(Debug, Default)]
+pub struct Simulation {
+    /// 성공할 이동 (원래 계획의 이동).
+    pub moved: Vec<MoveOperation>,
+    /// 새로 만들어질 폴더 (루트 기준 상대 경로).
+    pub created_dirs: Vec<PathBuf>,
+    /// 정리로 삭제될 폴더 (루트 기준 상대 경로).
+    pub removed_dirs: Vec<PathBuf>,
+    /// 실제 실행이라면 실패할 작업. 실행 순서대로입니다.
+    pub failures: Vec<Error>,
+}
+
+impl Simulation {
+    /// 실패할 작업이 없으면 `true`.
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    File,
+    Dir,
+}
+
+/// 실제 파일 시스템 위의 가상 변경. 경로는 모두 루트 기준 상대 경로입니다.
+struct Overlay<'a> {
+    fs: &'a dyn FileSystem,
+    root: &'a Path,
+    added: BTreeMap<PathBuf, Kind>,
+    removed: BTreeSet<PathBuf>,
+}
+
+impl<'a> Overlay<'a> {
+    fn new(fs: &'a dyn FileSystem, root: &'a Path) -> Self {
+        Self {
+            fs,
+            root,
+            added: BTreeMap::new(),
+            removed: BTreeSet::new(),
+        }
+    }
+
+    /// 가상 상태에서 경로의 종류. 심볼릭 링크는 따라가지 않습니다.
+    fn kind(&self, path: &Path) -> Option<Kind> {
+        if let Some(kind) = self.added.get(path) {
+            return Some(*kind);
+        }
+        if self.removed.contains(path) {
+            return None;
+        }
+        self.fs
+            .symlink_metadata(&self.root.join(path))
+            .ok()
+            .map(|meta| if meta.is_dir() { Kind::Dir } else { Kind::File })
+    }
+
+    /// 경로가 시뮬레이션 중에 새로 생긴 것이면 `true`.
+    fn is_virtual(&self, path: &Path) -> bool {
+        self.added.contains_key(path)
+    }
+
+    fn insert(&mut self, path: &Path, kind: Kind) {
+        self.added.insert(path.to_path_buf(), kind);
+    }
+
+    fn remove(&mut self, path: &Path) {
+        self.added.remove(path);
+        self.removed.insert(path.to_path_buf());
+    }
+
+    /// 가상 상태에서 폴더가 비어 있으면 `true`.
+    fn is_empty_dir(&self, path: &Path) -> bool {
+        let has_real_child = self
+            .fs
+            .read_dir(&self.root.join(path))
+            .is_ok_and(|entries| {
+                entries
+                    .into_iter()
+                    .any(|entry| self.kind(&path.join(entry.name)).is_some())
+            });
+        let has_virtual_child = self.added.keys().any(|added| added.parent() == Some(path));
+        !has_real_child && !has_virtual_child
+    }
+
+    /// 경로 자신이나 조상 가운데 가장 가까운, 실제로 있는 폴더의 절대 경로.
+    fn nearest_real_dir(&self, path: &Path) -> PathBuf {
+        path.ancestors()
+            .find(|dir| {
+                dir.as_os_str().is_empty()
+                    || (!self.is_virtual(dir) && self.kind(dir) == Some(Kind::Dir))
+            })
+            .map_or_else(|| self.root.to_path_buf(), |dir| self.root.join(dir))
+    }
+}
+
+/// 이동 계획을 실행했을 때 일어날 일을 파일 시스템을 바꾸지 않고 계산합니다.
+///
+/// `cleanup`이 있으면 실행 뒤의 빈 폴더 정리까지 흉내 냅니다. 실패할 단계는
+/// 기록하고 건너뛰며 다음 단계를 계속 확인하므로, 실제 실행이 첫 실패에서 멈추는
+/// 것과 달리 실패할 작업을 모두 보고합니다. 실패한 이동의 원본은 제자리에 남은
+/// 것으로 봅니다.
+#[must_use]
+pub fn simulate(
+    root: &Path,
+    moves: &[MoveOperation],
+    cleanup: Option<&CleanupRules>,
+) -> Simulation {
+    simulate_in(&RealFs, root, moves, cleanup)
+}
+
+/// 주어진 파일 시스템에서 [`simulate`]와 같이 실행을 흉내 냅니다.
+#[must_use]
+pub fn simulate_in(
+    fs: &dyn FileSystem,
+    root: &Path,
+    moves: &[MoveOperation],
+    cleanup: Option<&CleanupRules>,
+) -> Simulation {
+    let mut overlay = Overlay::new(fs, root);
+    let mut simulation = Simulation::default();
+
+    for step in schedule(moves) {
+        match check_step(&overlay, root, &step) {
+            Ok(missing_dirs) => {
+                if let Some(dir) = missing_dirs.first() {
+                    simulation.created_dirs.push(dir.clone());
+                }
+                for dir in missing_dirs {
+                    overlay.insert(&dir, Kind::Dir);
+                }
+                let kind = overlay.kind(&step.from).unwrap_or(Kind::File);
+                overlay.remove(&step.from);
+                overlay.insert(&step.to, kind);
+                simulation.moved.extend(step.completes);
+            }
+            Err(error) => simulation.failures.push(error),
+        }
+    }
+
+    if let Some(rules) = cleanup {
+        for dir in cleanup_candidates(fs, root, moves, rules) {
+            let path = root.join(&dir);
+            if overlay.kind(&dir) != Some(Kind::Dir)
+                || (!overlay.is_virtual(&dir) && is_mount_point(fs, &path))
+                || !overlay.is_empty_dir(&dir)
+            {
+                continue;
+            }
+            let parent = dir.parent().unwrap_or(Path::new(""));
+            if !overlay.is_virtual(parent)
+                && let Err(source) = fs.check_writable(&overlay.nearest_real_dir(parent))
+            {
+                simulation.failures.push(Error::from_io(path, source));
+                continue;
+            }
+            overlay.remove(&dir);
+            simulation.removed_dirs.push(dir);
+        }
+    }
+
+    simulation
+}
+
+/// 한 단계를 실제로 실행하면 실패할지 확인합니다.
+///
+/// 성공한다면 이 단계에서 새로 만들 폴더를 바깥쪽부터 반환합니다. 확인 순서는
+/// 실행기와 같습니다: 폴더 생성, 목적지 충돌, 이름 바꾸기.
+fn check_step(overlay: &Overlay<'_>, root: &Path, step: &Step) -> Result<Vec<PathBuf>, Error> {
+    let fs = overlay.fs;
+    let from_abs = root.join(&step.from);
+    let to_abs = root.join(&step.to);
+    let to_parent = step.to.parent().unwrap_or(Path::new(""));
+
+    let mut missing_dirs: Vec<PathBuf> = to_parent
+        .ancestors()
+        .take_while(|dir| !dir.as_os_str().is_empty() && overlay.kind(dir).is_none())
+        .map(Path::to_path_buf)
+        .collect();
+    missing_dirs.reverse();
+    if let Some(first) = missing_dirs.first() {
+        let parent_abs = root.join(to_parent);
+        let existing = first.parent().unwrap_or(Path::new(""));
+        if overlay.kind(existing) == Some(Kind::File) {
+            // 중간 경로가 파일이면 `create_dir_all`은 `NotADirectory`로 실패합니다.
+            return Err(Error::from_io(
+                parent_abs,
+                io::ErrorKind::NotADirectory.into(),
+            ));
+        }
+        check_name_length(&to_abs).map_err(|source| Error::from_io(parent_abs.clone(), source))?;
+        if !overlay.is_virtual(existing) {
+            fs.check_writable(&root.join(existing))
+                .map_err(|source| Error::from_io(parent_abs, source))?;
+        }
+    } else if overlay.kind(to_parent) == Some(Kind::File) {
+        return Err(Error::from_move(
+            from_abs,
+            to_abs,
+            io::ErrorKind::NotADirectory.into(),
+        ));
+    }
+
+    let same_file = !overlay.is_virtual(&step.from)
+        && !overlay.is_virtual(&step.to)
+        && is_same_file(fs, &from_abs, &to_abs);
+    if overlay.kind(&step.to).is_some() && !same_file {
+        return Err(Error::DestinationExists { path: to_abs });
+    }
+
+    let move_error = |source| Error::from_move(from_abs.clone(), to_abs.clone(), source);
+    if overlay.kind(&step.from).is_none() {
+        return Err(move_error(io::ErrorKind::NotFound.into()));
+    }
+    check_name_length(&to_abs).map_err(move_error)?;
+
+    let from_dir = overlay.nearest_real_dir(step.from.parent().unwrap_or(Path::new("")));
+    let to_dir = overlay.nearest_real_dir(to_parent);
+    for dir in [&from_dir, &to_dir] {
+        fs.check_writable(dir).map_err(move_error)?;
+    }
+    if !overlay.is_virtual(&step.from) && !same_device(fs, &from_abs, &to_dir) {
+        return Err(move_error(io::ErrorKind::CrossesDevices.into()));
+    }
+
+    Ok(missing_dirs)
+}
+
+/// 경로 구성 요소나 전체 경로가 운영체제 한도를 넘으면 실패합니다.
+fn check_name_length(path: &Path) -> io::Result<()> {
+    let too_long = path.as_os_str().len() > PATH_MAX
+        || path
+            .components()
+            .any(|component| component.as_os_str().len() > NAME_MAX);
+    if too_long {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidFilename,
+            "file name too long",
+        ));
+    }
+    Ok(())
+}
+
+/// 원본과 목적지 폴더가 같은 장치에 있으면 `true`. 알 수 없으면 같은 장치로 봅니다.
+fn same_device(fs: &dyn FileSystem, from: &Path, to_dir: &Path) -> bool {
+    match (fs.symlink_metadata(from), fs.metadata(to_dir)) {
+        (Ok(from), Ok(to)) => from.dev == to.dev,
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vfs::EntryKind;
+    use std::fs::{self, File};
+    use tempfile::TempDir;
+
+    fn touch(root: &Path, name: &str) {
+        let path = root.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        File::create(path).unwrap();
+    }
+
+    fn mv(from: &str, to: &str) -> MoveOperation {
+        MoveOperation::new(PathBuf::from(from), PathBuf::from(to))
+    }
+
+    fn listing(root: &Path) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+        let mut pending = vec![PathBuf::new()];
+        while let Some(dir) = pending.pop() {
+            for entry in RealFs.read_dir(&root.join(&dir)).unwrap() {
+                let path = dir.join(entry.name);
+                if entry.kind == EntryKind::Dir {
+                    pending.push(path.clone());
+                }
+                paths.push(path);
+            }
+        }
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn successful_plan_touches_nothing() {
+        let temp = TempDir::new().unwrap();
+        touch(temp.path(), "old/1001.cpp");
+        touch(temp.path(), "old/1002.cpp");
+        let before = listing(temp.path());
+
+        let moves = vec![
+            mv("old/1001.cpp", "1000/1001.cpp"),
+            mv("old/1002.cpp", "1000/1002.cpp"),
+        ];
+        let simulation = simulate(temp.path(), &moves, Some(&CleanupRules::default()));
+
+        assert!(simulation.is_ok(), "{:?}", simulation.failures);
+        assert_eq!(simulation.moved, moves);
+        assert_eq!(simulation.created_dirs, vec![PathBuf::from("1000")]);
+        assert_eq!(simulation.removed_dirs, vec![PathBuf::from("old")]);
+        assert_eq!(listing(temp.path()), before);
+    }
+
+    #[test]
+    fn swaps_and_chains_follow_the_executor_schedule() {
+        let temp = TempDir::new().unwrap();
+        touch(temp.path(), "a.cpp");
+        touch(temp.path(), "b.cpp");
+        touch(temp.path(), "c.cpp");
+
+        let moves = vec![
+            mv("a.cpp", "b.cpp"),
+            mv("b.cpp", "a.cpp"),
+            mv("c.cpp", "d.cpp"),
+        ];
+        let simulation = simulate(temp.path(), &moves, None);
+
+        assert!(simulation.is_ok(), "{:?}", simulation.failures);
+        assert_eq!(simulation.moved.len(), 3);
+    }
+
+    #[test]
+    fn reports_every_failure_and_keeps_going() {
+        let temp = TempDir::new().unwrap();
+        touch(temp.path(), "1001.cpp");
+        touch(temp.path(), "1002.cpp");
+        touch(temp.path(), "1003.cpp");
+        // 스캔하지 않은 파일이 목적지를 차지하고 있습니다.
+        touch(temp.path(), "1000/1002.cpp");
+        // 폴더가 있어야 할 자리에 파일이 있습니다. 실행기는 `rename`이나
+        // `create_dir_all`에서 `NotADirectory`로 실패합니다.
+        touch(temp.path(), "2000");
+        touch(temp.path(), "1006.cpp");
+
+        let moves = vec![
+            mv("1001.cpp", "1000/1001.cpp"),
+            mv("1002.cpp", "1000/1002.cpp"),
+            mv("1003.cpp", "2000/1003.cpp"),
+            mv("1004.cpp", "1000/1004.cpp"),
+            mv("1005.cpp", &format!("1000/{}.cpp", "x".repeat(300))),
+            mv("1006.cpp", "2000/sub/1006.cpp"),
+        ];
+        let simulation = simulate(temp.path(), &moves, None);
+
+        assert_eq!(simulation.moved, vec![moves[0].clone()]);
+        let codes: Vec<u8> = simulation.failures.iter().map(Error::exit_code).collect();
+        assert_eq!(codes, vec![5, 9, 4, 4, 9]);
+        assert_eq!(
+            simulation.failures[0].path(),
+            temp.path().join("1000/1002.cpp")
+        );
+        assert_eq!(simulation.failures[1].path(), temp.path().join("1003.cpp"));
+        assert_eq!(simulation.failures[2].path(), temp.path().join("1004.cpp"));
+        assert_eq!(simulation.failures[4].path(), temp.path().join("2000/sub"));
+    }
+
+    #[test]
+    fn name_too_long_is_an_io_error() {
+        let temp = TempDir::new().unwrap();
+        touch(temp.path(), "1001.cpp");
+
+        let long = format!("1000/{}.cpp", "x".repeat(300));
+        let simulation = simulate(temp.path(), &[mv("1001.cpp", &long)], None);
+
+        assert!(matches!(simulation.failures[..], [Error::Io { .. }]));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn read_only_dir_is_permission_denied() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = TempDir::new().unwrap();
+        touch(temp.path(), "locked/1001.cpp");
+        let locked = temp.path().join("locked");
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o555)).unwrap();
+        if RealFs.check_writable(&locked).is_ok() {
+            // root로 실행하면 권한 검사가 통과하므로 확인할 수 없습니다.
+            fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+            return;
+        }
+
+        let simulation = simulate(temp.path(), &[mv("locked/1001.cpp", "1001.cpp")], None);
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+
+        assert!(matches!(
+            simulation.failures[..],
+            [Error::PermissionDenied { .. }]
+        ));
+    }
+
+    #[test]
+    fn cleanup_skips_dirs_that_still_hold_files() {
+        let temp = TempDir::new().unwrap();
+        touch(temp.path(), "old/1001.cpp");
+        touch(temp.path(), "old/notes.txt");
+        touch(temp.path(), "gone/1002.cpp");
+
+        let moves = vec![
+            mv("old/1001.cpp", "1001.cpp"),
+            mv("gone/1002.cpp", "1002.cpp"),
+        ];
+        let simulation = simulate(temp.path(), &moves, Some(&CleanupRules::default()));
+
+        assert!(simulation.is_ok());
+        assert_eq!(simulation.removed_dirs, vec![PathBuf::from("gone")]);
+    }
+}
@@ -0,0 +1,395 @@
+//! 이동 계획의 디렉토리 트리 비교 출력.
+//!
+//! 평탄한 `from -> to` 목록 대신, 계획을 적용한 뒤의 폴더 트리를 폴더별 파일 수와
+//! 함께 보여줍니다. 새로 생기는 폴더, 비게 되는 폴더, 통째로 옮겨지는 폴더를
+//! 표시하고, 변경이 없는 하위 트리는 접어서 한 줄로 요약합니다.
+
+use crate::t;
+use crate::types::{FileEntry, MoveOperation};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[31m";
+const YELLOW: &str = "\x1b[33m";
+const DIM: &str = "\x1b[2m";
+const RESET: &str = "\x1b[0m";
+
+/// 폴더의 변경 상태.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FolderStatus {
+    /// 관련된 이동이 없음.
+    Unchanged,
+    /// 파일이 들어오거나 나감.
+    Changed,
+    /// 계획 적용 후 새로 생김.
+    New,
+    /// 계획 적용 후 파일이 남지 않음.
+    Removed,
+    /// 다른 폴더의 파일을 통째로 넘겨받음 (이전 폴더 경로).
+    Renamed(PathBuf),
+}
+
+#[derive(Debug, Default)]
+struct Node {
+    before: usize,
+    after: usize,
+    touched: bool,
+    children: BTreeMap<String, Node>,
+}
+
+impl Node {
+    fn child_mut(&mut self, folder: &Path) -> &mut Self {
+        let mut node = self;
+        for component in folder.components() {
+            let name = component.as_os_str().to_string_lossy().into_owned();
+            node = node.children.entry(name).or_default();
+        }
+        node
+    }
+
+    fn walk_mut(&mut self, folder: &Path, mut visit: impl FnMut(&mut Self)) {
+        let mut node = self;
+        visit(node);
+        for component in folder.components() {
+            let name = component.as_os_str().to_string_lossy().into_owned();
+            node = node.children.entry(name).or_default();
+            visit(node);
+        }
+    }
+
+    fn status(&self) -> FolderStatus {
+        if !self.touched {
+            FolderStatus::Unchanged
+        } else if self.before == 0 && self.after > 0 {
+            FolderStatus::New
+        } else if self.before > 0 && self.after == 0 {
+            FolderStatus::Removed
+        } else {
+            FolderStatus::Changed
+        }
+    }
+}
+
+/// 계획 적용 전후의 폴더 트리 비교 결과.
+#[derive(Debug)]
+pub struct TreeDiff {
+    root: Node,
+    renamed: HashMap<PathBuf, PathBuf>,
+    moved_files: usize,
+}
+
+fn folder_of(path: &Path) -> &Path {
+    path.parent().unwrap_or_else(|| Path::new(""))
+}
+
+/// 스캔 결과와 이동 계획으로부터 트리 비교 결과를 만듭니다.
+///
+/// 폴더별 파일 수는 하위 폴더를 포함한 값이며, 스캔된 소스 파일만 셉니다.
+pub fn build_tree_diff(entries: &[FileEntry], moves: &[MoveOperation]) -> TreeDiff {
+    let mut root = Node::default();
+
+    let destinations: HashMap<&Path, &Path> = moves
+        .iter()
+        .map(|op| (op.from.as_path(), op.to.as_path()))
+        .collect();
+
+    for entry in entries {
+        let from = entry.current_path.as_path();
+        let to = destinations.get(from).copied().unwrap_or(from);
+
+        root.walk_mut(folder_of(from), |node| node.before += 1);
+        root.walk_mut(folder_of(to), |node| node.after += 1);
+
+        if from != to {
+            root.walk_mut(folder_of(from), |node| node.touched = true);
+            root.walk_mut(folder_of(to), |node| node.touched = true);
+        }
+    }
+
+    let renamed = detect_renames(&mut root, moves);
+
+    TreeDiff {
+        root,
+        renamed,
+        moved_files: moves.len(),
+    }
+}
+
+/// 한 폴더의 파일이 모두 새로 생긴 한 폴더로만 옮겨지는 경우를 이름 변경으로 봅니다.
+fn detect_renames(root: &mut Node, moves: &[MoveOperation]) -> HashMap<PathBuf, PathBuf> {
+    let mut outgoing: BTreeMap<&Path, Vec<&Path>> = BTreeMap::new();
+    let mut incoming: BTreeMap<&Path, Vec<&Path>> = BTreeMap::new();
+    for op in moves {
+        let from = folder_of(&op.from);
+        let to = folder_of(&op.to);
+        outgoing.entry(from).or_default().push(to);
+        incoming.entry(to).or_default().push(from);
+    }
+
+    let mut renamed = HashMap::new();
+    for (from, targets) in &outgoing {
+        let Some(&to) = targets.first() else {
+            continue;
+        };
+        if from.as_os_str().is_empty()
+            || to.as_os_str().is_empty()
+            || to.starts_with(from)
+            || from.starts_with(to)
+            || targets.iter().any(|t| *t != to)
+            || incoming[to].iter().any(|f| f != from)
+        {
+            continue;
+        }
+
+        let from_node = root.child_mut(from);
+        let sole_folder = from_node.children.is_empty();
+        let from_status = from_node.status();
+        let to_status = root.child_mut(to).status();
+
+        if sole_folder && from_status == FolderStatus::Removed && to_status == FolderStatus::New {
+            renamed.insert(to.to_path_buf(), from.to_path_buf());
+        }
+    }
+    renamed
+}
+
+impl TreeDiff {
+    /// 폴더의 변경 상태를 반환합니다. 트리에 없는 폴더면 `None`입니다.
+    pub fn status(&self, folder: &Path) -> Option<FolderStatus> {
+        if let Some(from) = self.renamed.get(folder) {
+            return Some(FolderStatus::Renamed(from.clone()));
+        }
+
+        let mut node = &self.root;
+        for component in folder.components() {
+            node = node
+                .children
+                .get(component.as_os_str().to_string_lossy().as_ref())?;
+        }
+        Some(node.status())
+    }
+
+    /// 트리 비교 결과를 출력합니다.
+    ///
+    /// `color`가 `true`면 ANSI 색상으로 상태를 강조합니다.
+    ///
+    /// # Errors
+    ///
+    /// 출력에 실패하면 에러를 반환합니다.
+    pub fn render(&self, out: &mut impl Write, color: bool) -> io::Result<()> {
+        let painter = Painter { color };
+
+        writeln!(out, "./ {}", count_label(&self.root))?;
+        self.render_children(out, &painter, &self.root, Path::new(""), "")?;
+
+        let mut new = 0;
+        let mut removed = 0;
+        self.count_statuses(&self.root, Path::new(""), &mut new, &mut removed);
+        writeln!(
+            out,
+            "\n{}",
+            t!(
+                "tree.summary",
+                moved = self.moved_files,
+                new = new,
+                removed = removed,
+                renamed = self.renamed.len()
+            )
+        )
+    }
+
+    fn is_renamed_source(&self, path: &Path) -> bool {
+        self.renamed.values().any(|from| from == path)
+    }
+
+    fn count_statuses(&self, node: &Node, path: &Path, new: &mut usize, removed: &mut usize) {
+        for (name, child) in &node.children {
+            let child_path = path.join(name);
+            if self.renamed.contains_key(&child_path) || self.is_renamed_source(&child_path) {
+                continue;
+            }
+            match child.status() {
+                FolderStatus::New => *new += 1,
+                FolderStatus::Removed => *removed += 1,
+                _ => {}
+            }
+            self.count_statuses(child, &child_path, new, removed);
+        }
+    }
+
+    fn render_children(
+        &self,
+        out: &mut impl Write,
+        painter: &Painter,
+        node: &Node,
+        path: &Path,
+        prefix: &str,
+    ) -> io::Result<()> {
+        let visible: Vec<_> = node
+            .children
+            .iter()
+            .filter(|(name, _)| !self.is_renamed_source(&path.join(name)))
+            .collect();
+
+        for (index, (name, child)) in visible.iter().enumerate() {
+            let last = index + 1 == visible.len();
+            let branch = if last { "└── " } else { "├── " };
+            let child_prefix = format!("{prefix}{}", if last { "    " } else { "│   " });
+            let child_path = path.join(name);
+
+            let status = self.renamed.get(&child_path).map_or_else(
+                || child.status(),
+                |from| FolderStatus::Renamed(from.clone()),
+            );
+
+            let label = match &status {
+                FolderStatus::Unchanged => painter.paint(
+                    DIM,
+                    &format!("{name}/ {}", t!("tree.unchanged", count = child.after)),
+                ),
+                FolderStatus::Changed => format!("{name}/ {}", count_label(child)),
+                FolderStatus::New => painter.paint(
+                    GREEN,
+                    &format!("+ {name}/ {}", t!("tree.count", count = child.after)),
+                ),
+                FolderStatus::Removed => painter.paint(
+                    RED,
+                    &format!("- {name}/ {}", t!("tree.count", count = child.before)),
+                ),
+                FolderStatus::Renamed(from) => painter.paint(
+                    YELLOW,
+                    &format!(
+                        "~ {}/ → {name}/ {}",
+                        from.display(),
+                        t!("tree.count", count = child.after)
+                    ),
+                ),
+            };
+            writeln!(out, "{prefix}{branch}{label}")?;
+
+            if matches!(status, FolderStatus::Changed | FolderStatus::New) {
+                self.render_children(out, painter, child, &child_path, &child_prefix)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn count_label(node: &Node) -> String {
+    if node.before == node.after {
+        t!("tree.count", count = node.after)
+    } else {
+        t!(
+            "tree.count_changed",
+            before = node.before,
+            after = node.after
+        )
+    }
+}
+
+struct Painter {
+    color: bool,
+}
+
+impl Painter {
+    fn paint(&self, style: &str, text: &str) -> String {
+        if self.color {
+            format!("{style}{text}{RESET}")
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::{self, Lang};
+
+    fn entry(path: &str) -> FileEntry {
+        FileEntry::new(PathBuf::from(path), None)
+    }
+
+    fn mv(from: &str, to: &str) -> MoveOperation {
+        MoveOperation::new(PathBuf::from(from), PathBuf::from(to))
+    }
+
+    fn render(diff: &TreeDiff) -> String {
+        i18n::set_lang(Lang::Ko);
+        let mut out = Vec::new();
+        diff.render(&mut out, false).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn marks_new_and_removed_folders() {
+        let entries = vec![
+            entry("1001.cpp"),
+            entry("old/1002.cpp"),
+            entry("old/1003.cpp"),
+        ];
+        let moves = vec![
+            mv("1001.cpp", "1000/1001.cpp"),
+            mv("old/1002.cpp", "1000/1002.cpp"),
+            mv("old/1003.cpp", "etc/1003.cpp"),
+        ];
+
+        let diff = build_tree_diff(&entries, &moves);
+
+        assert_eq!(diff.status(Path::new("1000")), Some(FolderStatus::New));
+        assert_eq!(diff.status(Path::new("old")), Some(FolderStatus::Removed));
+        assert_eq!(diff.status(Path::new("")), Some(FolderStatus::Changed));
+    }
+
+    #[test]
+    fn detects_renamed_folder() {
+        let entries = vec![entry("old/1001.cpp"), entry("old/1002.cpp")];
+        let moves = vec![
+            mv("old/1001.cpp", "1000/1001.cpp"),
+            mv("old/1002.cpp", "1000/1002.cpp"),
+        ];
+
+        let diff = build_tree_diff(&entries, &moves);
+
+        assert_eq!(
+            diff.status(Path::new("1000")),
+            Some(FolderStatus::Renamed(PathBuf::from("old")))
+        );
+        assert!(render(&diff).contains("~ old/ → 1000/ (2 개)"));
+    }
+
+    #[test]
+    fn collapses_unchanged_subtrees() {
+        let entries = vec![
+            entry("keep/a/1001.cpp"),
+            entry("keep/b/1002.cpp"),
+            entry("main.cpp"),
+        ];
+        let moves = vec![mv("main.cpp", "etc/main.cpp")];
+
+        let output = render(&build_tree_diff(&entries, &moves));
+
+        assert_eq!(
+            output,
+            "./ (3 개)\n\
+             ├── + etc/ (1 개)\n\
+             └── keep/ (2 개, 변경 없음)\n\
+             \n이동 1 개 / 새 폴더 1 개 / 비는 폴더 0 개 / 이름 변경 0 개\n"
+        );
+    }
+
+    #[test]
+    fn plain_output_has_no_escape_codes() {
+        let entries = vec![entry("main.cpp")];
+        let moves = vec![mv("main.cpp", "etc/main.cpp")];
+        let diff = build_tree_diff(&entries, &moves);
+
+        let mut colored = Vec::new();
+        diff.render(&mut colored, true).unwrap();
+
+        assert!(!render(&diff).contains('\x1b'));
+        assert!(String::from_utf8(colored).unwrap().contains(GREEN));
+    }
+}
@@ -2,20 +2,60 @@
 //!
 //! 이 모듈은 파일 정리 작업에 필요한 기본 데이터 구조를 정의합니다.
 
-use std::path::PathBuf;
+use crate::header::Platform;
+use crate::t;
+use std::ffi::OsString;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
 
 /// 스캔된 소스 파일 정보.
 ///
 /// 디렉토리 스캔 시 발견된 각 파일의 현재 경로, 추출된 문제 번호,
-/// 파일명을 저장합니다.
+/// 파일명, 정규화된 확장자, 문제 출처를 저장합니다.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FileEntry {
     /// 루트 디렉토리 기준 상대 경로.
     pub current_path: PathBuf,
     /// 파일명에서 추출한 문제 번호 (없으면 `None`).
     pub problem_number: Option<u32>,
-    /// 파일명 (확장자 포함).
-    pub filename: String,
+    /// 파일명 (확장자 포함). UTF-8이 아닐 수 있습니다.
+    pub filename: OsString,
+    /// 정식 확장자 (점 제외, 예: `1010.CXX` → `cpp`).
+    pub extension: String,
+    /// 머리 주석에서 찾은 문제 출처.
+    pub platform: Option<Platform>,
+}
+
+impl FileEntry {
+    /// 경로에서 파일명과 소문자 확장자를 채워 새 항목을 생성합니다.
+    pub fn new(current_path: PathBuf, problem_number: Option<u32>) -> Self {
+        let filename = current_path.file_name().unwrap_or_default().to_os_string();
+        let extension = current_path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+            .unwrap_or_default();
+        Self {
+            current_path,
+            problem_number,
+            filename,
+            extension,
+            platform: None,
+        }
+    }
+
+    /// 확장자를 정식 확장자로 바꾼 파일명을 반환합니다.
+    pub fn normalized_filename(&self) -> OsString {
+        let mut name = Path::new(&self.filename)
+            .file_stem()
+            .unwrap_or_default()
+            .to_os_string();
+        if !self.extension.is_empty() {
+            name.push(".");
+            name.push(&self.extension);
+        }
+        name
+    }
 }
 
 /// 파일 이동 작업 정보.
@@ -42,3 +82,302 @@ impl MoveOperation {
         self.from != self.to
     }
 }
+
+/// 정리 작업 중 발생하는 에러.
+///
+/// 원인별로 구분되며, 각 분류는 [`Error::exit_code`]로 서로 다른 종료 코드를 가집니다.
+#[derive(Debug)]
+pub enum Error {
+    /// 대상 경로가 디렉토리가 아님.
+    InvalidRoot {
+        /// 지정한 대상 경로.
+        path: PathBuf,
+    },
+    /// 권한이 없어 읽거나 쓸 수 없음.
+    PermissionDenied {
+        /// 접근하려던 경로.
+        path: PathBuf,
+        /// 원인이 된 I/O 에러.
+        source: io::Error,
+    },
+    /// 이동하려던 원본 파일이 사라짐.
+    SourceVanished {
+        /// 원본 경로.
+        path: PathBuf,
+    },
+    /// 목적지에 이미 파일이 있음.
+    DestinationExists {
+        /// 목적지 경로.
+        path: PathBuf,
+    },
+    /// 원본과 목적지가 서로 다른 장치에 있어 이름 변경으로 옮길 수 없음.
+    CrossDevice {
+        /// 이동 전 경로.
+        from: PathBuf,
+        /// 이동 후 경로.
+        to: PathBuf,
+        /// 원인이 된 I/O 에러.
+        source: io::Error,
+    },
+    /// 파일명이 UTF-8이 아님.
+    InvalidUtf8Name {
+        /// 해당 파일 경로.
+        path: PathBuf,
+    },
+    /// 디렉토리 순회 실패 (심볼릭 링크 순환 등).
+    Walk {
+        /// 순회 중이던 경로.
+        path: PathBuf,
+        /// 원인이 된 I/O 에러.
+        source: io::Error,
+    },
+    /// 심볼릭 링크가 대상 디렉토리 밖을 가리킴.
+    OutsideRoot {
+        /// 링크 경로.
+        path: PathBuf,
+        /// 링크가 가리키는 실제 경로.
+        target: PathBuf,
+    },
+    /// 메타데이터 저장소 형식이 잘못됨.
+    InvalidMetadata {
+        /// 저장소 파일 경로.
+        path: PathBuf,
+        /// 원인 설명.
+        reason: String,
+    },
+    /// 그 밖의 I/O 에러.
+    Io {
+        /// 작업 중이던 경로.
+        path: PathBuf,
+        /// 원인이 된 I/O 에러.
+        source: io::Error,
+    },
+}
+
+impl Error {
+    /// 경로 하나에 대한 I/O 에러를 원인별로 분류합니다.
+    ///
+    /// 폴더 생성이나 목록 읽기처럼 원본 파일이 아닌 경로의 에러에 씁니다.
+    /// `NotFound`는 [`Error::Io`]로 둡니다.
+    pub fn from_io(path: PathBuf, source: io::Error) -> Self {
+        match source.kind() {
+            io::ErrorKind::PermissionDenied => Self::PermissionDenied { path, source },
+            io::ErrorKind::AlreadyExists => Self::DestinationExists { path },
+            _ => Self::Io { path, source },
+        }
+    }
+
+    /// 원본 파일을 읽거나 지우다 난 I/O 에러를 원인별로 분류합니다.
+    ///
+    /// `NotFound`는 원본이 사라진 것으로 봅니다.
+    pub fn from_source(path: PathBuf, source: io::Error) -> Self {
+        match source.kind() {
+            io::ErrorKind::NotFound => Self::SourceVanished { path },
+            _ => Self::from_io(path, source),
+        }
+    }
+
+    /// 파일 이동 중 발생한 I/O 에러를 원인별로 분류합니다.
+    pub fn from_move(from: PathBuf, to: PathBuf, source: io::Error) -> Self {
+        match source.kind() {
+            io::ErrorKind::CrossesDevices => Self::CrossDevice { from, to, source },
+            io::ErrorKind::NotFound => Self::SourceVanished { path: from },
+            io::ErrorKind::AlreadyExists => Self::DestinationExists { path: to },
+            _ => Self::from_io(from, source),
+        }
+    }
+
+    /// 에러가 난 경로. 장치 간 이동은 이동 전 경로입니다.
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::InvalidRoot { path }
+            | Self::PermissionDenied { path, .. }
+            | Self::SourceVanished { path }
+            | Self::DestinationExists { path }
+            | Self::CrossDevice { from: path, .. }
+            | Self::InvalidUtf8Name { path }
+            | Self::Walk { path, .. }
+            | Self::OutsideRoot { path, .. }
+            | Self::InvalidMetadata { path, .. }
+            | Self::Io { path, .. } => path,
+        }
+    }
+
+    /// 분류별 프로세스 종료 코드.
+    ///
+    /// | 분류 | 코드 |
+    /// |------|------|
+    /// | 대상 경로 오류 | 2 |
+    /// | 권한 없음 | 3 |
+    /// | 원본 사라짐 | 4 |
+    /// | 목적지 존재 | 5 |
+    /// | 장치 간 이동 | 6 |
+    /// | UTF-8이 아닌 파일명 | 7 |
+    /// | 순회 실패 | 8 |
+    /// | 그 밖의 I/O | 9 |
+    /// | 대상 밖을 가리키는 링크 | 10 |
+    /// | 메타데이터 형식 오류 | 11 |
+    pub const fn exit_code(&self) -> u8 {
+        match self {
+            Self::InvalidRoot { .. } => 2,
+            Self::PermissionDenied { .. } => 3,
+            Self::SourceVanished { .. } => 4,
+            Self::DestinationExists { .. } => 5,
+            Self::CrossDevice { .. } => 6,
+            Self::InvalidUtf8Name { .. } => 7,
+            Self::Walk { .. } => 8,
+            Self::Io { .. } => 9,
+            Self::OutsideRoot { .. } => 10,
+            Self::InvalidMetadata { .. } => 11,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            Self::InvalidRoot { path } => t!("error.invalid_root", path = path.display()),
+            Self::PermissionDenied { path, .. } => {
+                t!("error.permission_denied", path = path.display())
+            }
+            Self::SourceVanished { path } => t!("error.source_vanished", path = path.display()),
+            Self::DestinationExists { path } => {
+                t!("error.destination_exists", path = path.display())
+            }
+            Self::CrossDevice { from, to, .. } => {
+                t!(
+                    "error.cross_device",
+                    from = from.display(),
+                    to = to.display()
+                )
+            }
+            Self::InvalidUtf8Name { path } => {
+                t!("error.invalid_utf8_name", path = path.display())
+            }
+            Self::Walk { path, source } => {
+                t!("error.walk", path = path.display(), reason = source)
+            }
+            Self::OutsideRoot { path, target } => t!(
+                "error.outside_root",
+                path = path.display(),
+                target = target.display()
+            ),
+            Self::InvalidMetadata { path, reason } => t!(
+                "error.invalid_metadata",
+                path = path.display(),
+                reason = reason
+            ),
+            Self::Io { path, source } => t!("error.io", path = path.display(), reason = source),
+        };
+        f.write_str(&message)
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidRoot { .. }
+            | Self::SourceVanished { .. }
+            | Self::DestinationExists { .. }
+            | Self::InvalidUtf8Name { .. }
+            | Self::OutsideRoot { .. }
+            | Self::InvalidMetadata { .. } => None,
+            Self::PermissionDenied { source, .. }
+            | Self::CrossDevice { source, .. }
+            | Self::Walk { source, .. }
+            | Self::Io { source, .. } => Some(source),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_move_classifies_errors() {
+        let from = PathBuf::from("a.cpp");
+        let to = PathBuf::from("etc/a.cpp");
+        let classify =
+            |kind: io::ErrorKind| Error::from_move(from.clone(), to.clone(), io::Error::from(kind));
+
+        assert!(matches!(
+            classify(io::ErrorKind::PermissionDenied),
+            Error::PermissionDenied { .. }
+        ));
+        assert!(matches!(
+            classify(io::ErrorKind::NotFound),
+            Error::SourceVanished { path } if path == from
+        ));
+        assert!(matches!(
+            classify(io::ErrorKind::AlreadyExists),
+            Error::DestinationExists { path } if path == to
+        ));
+        assert!(matches!(
+            classify(io::ErrorKind::CrossesDevices),
+            Error::CrossDevice { .. }
+        ));
+        assert!(matches!(classify(io::ErrorKind::Other), Error::Io { .. }));
+    }
+
+    #[test]
+    fn not_found_is_source_vanished_only_for_sources() {
+        let path = PathBuf::from("a.cpp");
+
+        assert!(matches!(
+            Error::from_io(path.clone(), io::ErrorKind::NotFound.into()),
+            Error::Io { .. }
+        ));
+        assert!(matches!(
+            Error::from_source(path.clone(), io::ErrorKind::NotFound.into()),
+            Error::SourceVanished { path: p } if p == path
+        ));
+        assert!(matches!(
+            Error::from_source(path, io::ErrorKind::PermissionDenied.into()),
+            Error::PermissionDenied { .. }
+        ));
+    }
+
+    #[test]
+    fn exit_codes_are_distinct() {
+        let errors = [
+            Error::InvalidRoot {
+                path: PathBuf::new(),
+            },
+            Error::from_io(PathBuf::new(), io::ErrorKind::PermissionDenied.into()),
+            Error::SourceVanished {
+                path: PathBuf::new(),
+            },
+            Error::DestinationExists {
+                path: PathBuf::new(),
+            },
+            Error::from_move(
+                PathBuf::new(),
+                PathBuf::new(),
+                io::ErrorKind::CrossesDevices.into(),
+            ),
+            Error::InvalidUtf8Name {
+                path: PathBuf::new(),
+            },
+            Error::Walk {
+                path: PathBuf::new(),
+                source: io::ErrorKind::Other.into(),
+            },
+            Error::from_io(PathBuf::new(), io::ErrorKind::Other.into()),
+            Error::OutsideRoot {
+                path: PathBuf::new(),
+                target: PathBuf::new(),
+            },
+            Error::InvalidMetadata {
+                path: PathBuf::new(),
+                reason: String::new(),
+            },
+        ];
+
+        let mut codes: Vec<u8> = errors.iter().map(Error::exit_code).collect();
+        codes.sort_unstable();
+        codes.dedup();
+        assert_eq!(codes.len(), errors.len());
+        assert!(!codes.contains(&0) && !codes.contains(&1));
+    }
+}
@@ -0,0 +1,1020 @@
+//! 파일 시스템 추상화.
+//!
+//! 스캔, 실행, 빈 폴더 정리, dry-run 시뮬레이션은 [`FileSystem`] 트레이트로만
+//! 파일 시스템에 접근합니다. [`RealFs`]는 `std::fs`로 실제 파일 시스템을 쓰고,
+//! [`MemoryFs`]는 메모리 안의 트리로 동작합니다. [`MemoryFs`]에는 권한 없음,
+//! 장치 간 이동, 읽기 전용 폴더 같은 실패를 원하는 경로에 주입할 수 있어서
+//! 임시 디렉토리로는 만들기 어려운 상황을 테스트할 수 있습니다.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, Cursor, Read};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::SystemTime;
+
+/// 항목의 종류.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryKind {
+    /// 일반 파일.
+    File,
+    /// 디렉토리.
+    Dir,
+    /// 심볼릭 링크 (따라가지 않음).
+    Symlink,
+    /// 그 밖의 항목 (소켓, 장치 등).
+    Other,
+}
+
+impl From<fs::FileType> for EntryKind {
+    fn from(file_type: fs::FileType) -> Self {
+        if file_type.is_symlink() {
+            Self::Symlink
+        } else if file_type.is_dir() {
+            Self::Dir
+        } else if file_type.is_file() {
+            Self::File
+        } else {
+            Self::Other
+        }
+    }
+}
+
+/// 항목의 메타데이터.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Metadata {
+    /// 항목의 종류.
+    pub kind: EntryKind,
+    /// 크기(바이트).
+    pub len: u64,
+    /// 수정 시각. 알 수 없으면 `None`.
+    pub modified: Option<SystemTime>,
+    /// 장치 번호. 알 수 없으면 0.
+    pub dev: u64,
+    /// inode 번호. 알 수 없으면 0.
+    pub ino: u64,
+}
+
+impl Metadata {
+    /// 디렉토리면 `true`.
+    #[must_use]
+    pub fn is_dir(&self) -> bool {
+        self.kind == EntryKind::Dir
+    }
+
+    /// 일반 파일이면 `true`.
+    #[must_use]
+    pub fn is_file(&self) -> bool {
+        self.kind == EntryKind::File
+    }
+
+    /// 심볼릭 링크면 `true`.
+    #[must_use]
+    pub fn is_symlink(&self) -> bool {
+        self.kind == EntryKind::Symlink
+    }
+}
+
+impl From<fs::Metadata> for Metadata {
+    fn from(metadata: fs::Metadata) -> Self {
+        #[cfg(unix)]
+        let (dev, ino) = {
+            use std::os::unix::fs::MetadataExt;
+            (metadata.dev(), metadata.ino())
+        };
+        #[cfg(not(unix))]
+        let (dev, ino) = (0, 0);
+        Self {
+            kind: metadata.file_type().into(),
+            len: metadata.len(),
+            modified: metadata.modified().ok(),
+            dev,
+            ino,
+        }
+    }
+}
+
+/// 디렉토리 안의 항목 하나.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirEntry {
+    /// 항목 이름.
+    pub name: OsString,
+    /// 링크를 따라가지 않은 항목의 종류.
+    pub kind: EntryKind,
+}
+
+/// 정리 작업이 쓰는 파일 시스템 작업.
+///
+/// 경로는 모두 절대 경로이거나 현재 디렉토리 기준 경로이며, 에러는 `std::fs`와
+/// 같은 [`io::ErrorKind`]로 돌려줍니다. 그래야 [`Error::from_io`]와
+/// [`Error::from_move`]가 구현과 관계없이 같은 분류를 합니다.
+///
+/// [`Error::from_io`]: crate::types::Error::from_io
+/// [`Error::from_move`]: crate::types::Error::from_move
+pub trait FileSystem: Send + Sync {
+    /// 링크를 따라가 메타데이터를 읽습니다.
+    ///
+    /// # Errors
+    ///
+    /// 항목이 없거나 읽을 수 없으면 에러를 반환합니다.
+    fn metadata(&self, path: &Path) -> io::Result<Metadata>;
+
+    /// 링크를 따라가지 않고 메타데이터를 읽습니다.
+    ///
+    /// # Errors
+    ///
+    /// 항목이 없거나 읽을 수 없으면 에러를 반환합니다.
+    fn symlink_metadata(&self, path: &Path) -> io::Result<Metadata>;
+
+    /// 디렉토리의 항목 목록을 읽습니다. 순서는 정해져 있지 않습니다.
+    ///
+    /// # Errors
+    ///
+    /// 디렉토리가 아니거나 읽을 수 없으면 에러를 반환합니다.
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<DirEntry>>;
+
+    /// 심볼릭 링크가 가리키는 경로를 읽습니다.
+    ///
+    /// # Errors
+    ///
+    /// 링크가 아니거나 읽을 수 없으면 에러를 반환합니다.
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf>;
+
+    /// 링크를 모두 푼 절대 경로를 구합니다.
+    ///
+    /// # Errors
+    ///
+    /// 경로가 없거나 풀 수 없으면 에러를 반환합니다.
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf>;
+
+    /// 파일을 읽기용으로 엽니다.
+    ///
+    /// # Errors
+    ///
+    /// 파일이 없거나 열 수 없으면 에러를 반환합니다.
+    fn open(&self, path: &Path) -> io::Result<Box<dyn Read>>;
+
+    /// 파일을 만들거나 내용을 바꿉니다.
+    ///
+    /// # Errors
+    ///
+    /// 부모 디렉토리가 없거나 쓸 수 없으면 에러를 반환합니다.
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()>;
+
+    /// 디렉토리와 없는 부모 디렉토리를 모두 만듭니다.
+    ///
+    /// # Errors
+    ///
+    /// 중간 경로가 디렉토리가 아니거나 만들 수 없으면 에러를 반환합니다.
+    fn create_dir_all(&self, path: &Path) -> io::Result<()>;
+
+    /// 링크를 따라가 파일 내용과 권한을 새 파일로 복사합니다.
+    ///
+    /// # Errors
+    ///
+    /// 원본을 읽을 수 없거나, 목적지에 항목이 있거나
+    /// ([`io::ErrorKind::AlreadyExists`]), 쓸 수 없으면 에러를 반환합니다.
+    fn copy(&self, from: &Path, to: &Path) -> io::Result<()>;
+
+    /// 항목의 이름을 바꿉니다. 목적지에 파일이 있으면 덮어씁니다.
+    ///
+    /// # Errors
+    ///
+    /// 원본이 없거나, 다른 장치로 옮기거나, 권한이 없으면 에러를 반환합니다.
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()>;
+
+    /// 파일이나 링크를 지웁니다.
+    ///
+    /// # Errors
+    ///
+    /// 항목이 없거나 디렉토리거나 권한이 없으면 에러를 반환합니다.
+    fn remove_file(&self, path: &Path) -> io::Result<()>;
+
+    /// 빈 디렉토리를 지웁니다.
+    ///
+    /// # Errors
+    ///
+    /// 디렉토리가 아니거나 비어 있지 않거나 권한이 없으면 에러를 반환합니다.
+    fn remove_dir(&self, path: &Path) -> io::Result<()>;
+
+    /// `target`을 가리키는 심볼릭 링크 `link`를 만듭니다.
+    ///
+    /// # Errors
+    ///
+    /// 링크 자리에 항목이 있거나, 링크를 지원하지 않거나, 권한이 없으면 에러를
+    /// 반환합니다.
+    fn symlink(&self, target: &Path, link: &Path) -> io::Result<()>;
+
+    /// 디렉토리에 항목을 만들고 지울 수 있는지 확인합니다.
+    ///
+    /// # Errors
+    ///
+    /// 권한이 없거나 읽기 전용으로 마운트된 파일 시스템이면 에러를 반환합니다.
+    fn check_writable(&self, dir: &Path) -> io::Result<()>;
+
+    /// 링크를 따라가 경로에 항목이 있으면 `true`.
+    fn exists(&self, path: &Path) -> bool {
+        self.metadata(path).is_ok()
+    }
+
+    /// 파일 내용 전체를 문자열로 읽습니다.
+    ///
+    /// # Errors
+    ///
+    /// 파일을 읽을 수 없거나 UTF-8이 아니면 에러를 반환합니다.
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        let mut text = String::new();
+        self.open(path)?.read_to_string(&mut text)?;
+        Ok(text)
+    }
+}
+
+/// `std::fs`로 실제 파일 시스템을 쓰는 구현.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RealFs;
+
+impl FileSystem for RealFs {
+    fn metadata(&self, path: &Path) -> io::Result<Metadata> {
+        fs::metadata(path).map(Metadata::from)
+    }
+
+    fn symlink_metadata(&self, path: &Path) -> io::Result<Metadata> {
+        fs::symlink_metadata(path).map(Metadata::from)
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<DirEntry>> {
+        fs::read_dir(path)?
+            .map(|entry| {
+                let entry = entry?;
+                Ok(DirEntry {
+                    name: entry.file_name(),
+                    kind: entry.file_type()?.into(),
+                })
+            })
+            .collect()
+    }
+
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        fs::read_link(path)
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        path.canonicalize()
+    }
+
+    fn open(&self, path: &Path) -> io::Result<Box<dyn Read>> {
+        Ok(Box::new(fs::File::open(path)?))
+    }
+
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        fs::write(path, contents)
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        fs::create_dir_all(path)
+    }
+
+    fn copy(&self, from: &Path, to: &Path) -> io::Result<()> {
+        let mut source = fs::File::open(from)?;
+        let permissions = source.metadata()?.permissions();
+        let mut target = fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(to)?;
+        io::copy(&mut source, &mut target)?;
+        target.set_permissions(permissions)
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        fs::rename(from, to)
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        fs::remove_file(path)
+    }
+
+    fn remove_dir(&self, path: &Path) -> io::Result<()> {
+        fs::remove_dir(path)
+    }
+
+    #[cfg(unix)]
+    fn symlink(&self, target: &Path, link: &Path) -> io::Result<()> {
+        std::os::unix::fs::symlink(target, link)
+    }
+
+    #[cfg(not(unix))]
+    fn symlink(&self, _target: &Path, _link: &Path) -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    #[cfg(unix)]
+    fn check_writable(&self, dir: &Path) -> io::Result<()> {
+        use rustix::fs::{Access, access};
+
+        access(dir, Access::WRITE_OK | Access::EXEC_OK).map_err(io::Error::from)
+    }
+
+    #[cfg(not(unix))]
+    fn check_writable(&self, dir: &Path) -> io::Result<()> {
+        if fs::metadata(dir)?.permissions().readonly() {
+            return Err(io::ErrorKind::PermissionDenied.into());
+        }
+        Ok(())
+    }
+}
+
+/// [`MemoryFs`]에서 실패를 주입할 수 있는 작업.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    /// [`FileSystem::metadata`], [`FileSystem::symlink_metadata`],
+    /// [`FileSystem::canonicalize`].
+    Metadata,
+    /// [`FileSystem::read_dir`].
+    ReadDir,
+    /// [`FileSystem::read_link`].
+    ReadLink,
+    /// [`FileSystem::open`].
+    Open,
+    /// [`FileSystem::write`].
+    Write,
+    /// [`FileSystem::create_dir_all`].
+    CreateDir,
+    /// [`FileSystem::copy`]. 원본과 목적지 경로 모두에 맞춰 봅니다.
+    Copy,
+    /// [`FileSystem::rename`]. 원본과 목적지 경로 모두에 맞춰 봅니다.
+    Rename,
+    /// [`FileSystem::remove_file`].
+    RemoveFile,
+    /// [`FileSystem::remove_dir`].
+    RemoveDir,
+    /// [`FileSystem::symlink`]. 링크 경로에 맞춰 봅니다.
+    Symlink,
+    /// [`FileSystem::check_writable`].
+    Access,
+}
+
+#[derive(Debug)]
+struct Fault {
+    op: Op,
+    path: PathBuf,
+    kind: io::ErrorKind,
+}
+
+#[derive(Debug, Clone)]
+enum Content {
+    File(Vec<u8>),
+    Dir,
+    Symlink(PathBuf),
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    content: Content,
+    dev: u64,
+    ino: u64,
+    modified: SystemTime,
+    readonly: bool,
+}
+
+impl Node {
+    const fn is_dir(&self) -> bool {
+        matches!(self.content, Content::Dir)
+    }
+
+    fn metadata(&self) -> Metadata {
+        let (kind, len) = match &self.content {
+            Content::File(data) => (EntryKind::File, data.len()),
+            Content::Dir => (EntryKind::Dir, 0),
+            Content::Symlink(target) => (EntryKind::Symlink, target.as_os_str().len()),
+        };
+        Metadata {
+            kind,
+            len: len as u64,
+            modified: Some(self.modified),
+            dev: self.dev,
+            ino: self.ino,
+        }
+    }
+}
+
+/// 링크를 따라가는 최대 횟수. 넘으면 순환으로 봅니다.
+const MAX_LINK_HOPS: usize = 40;
+
+/// 링크를 풀 때 아직 처리하지 않은 경로 구성 요소.
+enum Part {
+    Root,
+    Parent,
+    Name(OsString),
+}
+
+/// 경로를 처리할 순서의 반대로 구성 요소를 나눕니다.
+fn parts_reversed(path: &Path) -> impl Iterator<Item = Part> + '_ {
+    path.components()
+        .rev()
+        .filter_map(|component| match component {
+            Component::Prefix(_) | Component::CurDir => None,
+            Component::RootDir => Some(Part::Root),
+            Component::ParentDir => Some(Part::Parent),
+            Component::Normal(name) => Some(Part::Name(name.to_os_string())),
+        })
+}
+
+/// 링크를 풀지 않고 `.`과 `..`만 글자 그대로 정리한 절대 경로.
+fn lexical(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::from("/");
+    for component in path.components() {
+        match component {
+            Component::Prefix(_) | Component::RootDir | Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            Component::Normal(name) => normalized.push(name),
+        }
+    }
+    normalized
+}
+
+#[derive(Debug)]
+struct State {
+    /// 링크를 푼 절대 경로별 항목.
+    nodes: BTreeMap<PathBuf, Node>,
+    faults: Vec<Fault>,
+    next_ino: u64,
+}
+
+impl State {
+    /// 경로의 링크를 풀어 트리 안의 경로를 구합니다.
+    ///
+    /// 마지막 구성 요소는 없어도 되며, `follow_last`가 `true`일 때만 마지막
+    /// 구성 요소의 링크를 따라갑니다.
+    fn resolve(&self, path: &Path, follow_last: bool) -> io::Result<PathBuf> {
+        let mut pending: Vec<Part> = parts_reversed(path).collect();
+        let mut current = PathBuf::from("/");
+        let mut hops = 0;
+
+        while let Some(part) = pending.pop() {
+            let name = match part {
+                Part::Root => {
+                    current = PathBuf::from("/");
+                    continue;
+                }
+                Part::Parent => {
+                    current.pop();
+                    continue;
+                }
+                Part::Name(name) => name,
+            };
+            let next = current.join(&name);
+            let last = pending.is_empty();
+            match self.nodes.get(&next).map(|node| &node.content) {
+                Some(Content::Symlink(target)) if !last || follow_last => {
+                    hops += 1;
+                    if hops > MAX_LINK_HOPS {
+                        return Err(io::Error::other("too many levels of symbolic links"));
+                    }
+                    pending.extend(parts_reversed(target));
+                }
+                Some(Content::File(_) | Content::Symlink(_)) if !last => {
+                    return Err(io::ErrorKind::NotADirectory.into());
+                }
+                None if !last => return Err(io::ErrorKind::NotFound.into()),
+                _ => current = next,
+            }
+        }
+        Ok(current)
+    }
+
+    /// 링크를 풀어 항목을 찾습니다.
+    fn lookup(&self, path: &Path, follow: bool) -> io::Result<(PathBuf, &Node)> {
+        let resolved = self.resolve(path, follow)?;
+        match self.nodes.get(&resolved) {
+            Some(node) => Ok((resolved, node)),
+            None => Err(io::ErrorKind::NotFound.into()),
+        }
+    }
+
+    /// 주입한 실패 가운데 작업과 경로에 맞는 것이 있으면 그 에러를 돌려줍니다.
+    fn fault(&self, op: Op, path: &Path) -> io::Result<()> {
+        let path = lexical(path);
+        match self
+            .faults
+            .iter()
+            .find(|fault| fault.op == op && path.starts_with(&fault.path))
+        {
+            Some(fault) => Err(fault.kind.into()),
+            None => Ok(()),
+        }
+    }
+
+    /// 새 항목을 만들거나 지울 부모 디렉토리를 확인합니다.
+    fn writable_parent(&self, resolved: &Path) -> io::Result<&Node> {
+        let parent = resolved
+            .parent()
+            .ok_or_else(|| io::Error::from(io::ErrorKind::InvalidInput))?;
+        let node = self
+            .nodes
+            .get(parent)
+            .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))?;
+        if !node.is_dir() {
+            return Err(io::ErrorKind::NotADirectory.into());
+        }
+        if node.readonly {
+            return Err(io::ErrorKind::PermissionDenied.into());
+        }
+        Ok(node)
+    }
+
+    /// 디렉토리 아래에 항목이 있으면 `true`.
+    fn has_children(&self, dir: &Path) -> bool {
+        self.nodes
+            .range(dir.to_path_buf()..)
+            .nth(1)
+            .is_some_and(|(path, _)| path.starts_with(dir))
+    }
+
+    /// 부모 디렉토리의 장치 번호를 물려받아 항목을 만들고, 부모의 수정 시각을 바꿉니다.
+    fn insert(&mut self, resolved: PathBuf, content: Content) {
+        let dev = resolved
+            .parent()
+            .and_then(|parent| self.nodes.get(parent))
+            .map_or(1, |parent| parent.dev);
+        self.next_ino += 1;
+        self.touch_parent(&resolved);
+        self.nodes.insert(
+            resolved,
+            Node {
+                content,
+                dev,
+                ino: self.next_ino,
+                modified: SystemTime::now(),
+                readonly: false,
+            },
+        );
+    }
+
+    fn touch_parent(&mut self, resolved: &Path) {
+        if let Some(parent) = resolved
+            .parent()
+            .and_then(|parent| self.nodes.get_mut(parent))
+        {
+            parent.modified = SystemTime::now();
+        }
+    }
+}
+
+/// 메모리 안의 트리로 동작하는 구현.
+///
+/// 처음에는 빈 루트 디렉토리(`/`)만 있습니다. 장치 번호와 inode 번호를 흉내 내므로
+/// 같은 파일 확인, 장치 간 이동, 마운트 지점 확인이 실제 파일 시스템처럼
+/// 동작합니다. [`inject`](Self::inject)로 특정 작업에 실패를 주입하고,
+/// [`set_device`](Self::set_device)와 [`set_readonly`](Self::set_readonly)로 다른
+/// 장치나 쓸 수 없는 폴더를 만들 수 있습니다.
+#[derive(Debug)]
+pub struct MemoryFs {
+    state: Mutex<State>,
+}
+
+impl Default for MemoryFs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemoryFs {
+    /// 빈 루트 디렉토리만 있는 파일 시스템을 만듭니다.
+    #[must_use]
+    pub fn new() -> Self {
+        let root = Node {
+            content: Content::Dir,
+            dev: 1,
+            ino: 1,
+            modified: SystemTime::now(),
+            readonly: false,
+        };
+        Self {
+            state: Mutex::new(State {
+                nodes: BTreeMap::from([(PathBuf::from("/"), root)]),
+                faults: Vec::new(),
+                next_ino: 1,
+            }),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// `path`나 그 아래 경로에 대한 `op` 작업이 `kind` 에러로 실패하게 합니다.
+    pub fn inject(&self, op: Op, path: impl AsRef<Path>, kind: io::ErrorKind) {
+        self.state().faults.push(Fault {
+            op,
+            path: lexical(path.as_ref()),
+            kind,
+        });
+    }
+
+    /// 주입한 실패를 모두 지웁니다.
+    pub fn clear_faults(&self) {
+        self.state().faults.clear();
+    }
+
+    /// 디렉토리와 그 아래 항목을 다른 장치에 있는 것으로 만듭니다.
+    ///
+    /// 마운트한 것처럼 동작하므로, 장치 경계를 넘는 이름 변경은
+    /// [`io::ErrorKind::CrossesDevices`]로 실패합니다.
+    ///
+    /// # Errors
+    ///
+    /// 경로가 없으면 에러를 반환합니다.
+    pub fn set_device(&self, path: impl AsRef<Path>, dev: u64) -> io::Result<()> {
+        let mut state = self.state();
+        let (resolved, _) = state.lookup(path.as_ref(), true)?;
+        for (_, node) in state
+            .nodes
+            .range_mut(resolved.clone()..)
+            .take_while(|(key, _)| key.starts_with(&resolved))
+        {
+            node.dev = dev;
+        }
+        Ok(())
+    }
+
+    /// 디렉토리에 항목을 만들거나 지울 수 없게 하거나 다시 허용합니다.
+    ///
+    /// # Errors
+    ///
+    /// 경로가 없으면 에러를 반환합니다.
+    pub fn set_readonly(&self, path: impl AsRef<Path>, readonly: bool) -> io::Result<()> {
+        let mut state = self.state();
+        let (resolved, _) = state.lookup(path.as_ref(), true)?;
+        if let Some(node) = state.nodes.get_mut(&resolved) {
+            node.readonly = readonly;
+        }
+        Ok(())
+    }
+}
+
+/// 주어진 (경로, 내용) 파일과 그 상위 폴더가 있는 메모리 파일 시스템을 만듭니다.
+#[cfg(test)]
+pub(crate) fn memory_with(files: &[(&str, &str)]) -> MemoryFs {
+    let fs = MemoryFs::new();
+    for (file, contents) in files {
+        let path = Path::new(file);
+        fs.create_dir_all(path.parent().unwrap()).unwrap();
+        fs.write(path, contents.as_bytes()).unwrap();
+    }
+    fs
+}
+
+impl FileSystem for MemoryFs {
+    fn metadata(&self, path: &Path) -> io::Result<Metadata> {
+        let state = self.state();
+        state.fault(Op::Metadata, path)?;
+        state.lookup(path, true).map(|(_, node)| node.metadata())
+    }
+
+    fn symlink_metadata(&self, path: &Path) -> io::Result<Metadata> {
+        let state = self.state();
+        state.fault(Op::Metadata, path)?;
+        state.lookup(path, false).map(|(_, node)| node.metadata())
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<DirEntry>> {
+        let state = self.state();
+        state.fault(Op::ReadDir, path)?;
+        let (dir, node) = state.lookup(path, true)?;
+        if !node.is_dir() {
+            return Err(io::ErrorKind::NotADirectory.into());
+        }
+        Ok(state
+            .nodes
+            .range(dir.clone()..)
+            .skip(1)
+            .take_while(|(key, _)| key.starts_with(&dir))
+            .filter(|(key, _)| key.parent() == Some(dir.as_path()))
+            .map(|(key, node)| DirEntry {
+                name: key.file_name().unwrap_or_default().to_os_string(),
+                kind: node.metadata().kind,
+            })
+            .collect())
+    }
+
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        let state = self.state();
+        state.fault(Op::ReadLink, path)?;
+        match &state.lookup(path, false)?.1.content {
+            Content::Symlink(target) => Ok(target.clone()),
+            _ => Err(io::ErrorKind::InvalidInput.into()),
+        }
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        let state = self.state();
+        state.fault(Op::Metadata, path)?;
+        state.lookup(path, true).map(|(resolved, _)| resolved)
+    }
+
+    fn open(&self, path: &Path) -> io::Result<Box<dyn Read>> {
+        let state = self.state();
+        state.fault(Op::Open, path)?;
+        match &state.lookup(path, true)?.1.content {
+            Content::File(data) => Ok(Box::new(Cursor::new(data.clone()))),
+            _ => Err(io::ErrorKind::IsADirectory.into()),
+        }
+    }
+
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        let mut state = self.state();
+        state.fault(Op::Write, path)?;
+        let resolved = state.resolve(path, true)?;
+        if let Some(node) = state.nodes.get_mut(&resolved) {
+            let Content::File(data) = &mut node.content else {
+                return Err(io::ErrorKind::IsADirectory.into());
+            };
+            contents.clone_into(data);
+            node.modified = SystemTime::now();
+            return Ok(());
+        }
+        state.writable_parent(&resolved)?;
+        state.insert(resolved, Content::File(contents.to_vec()));
+        Ok(())
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        let mut state = self.state();
+        state.fault(Op::CreateDir, path)?;
+        let target = lexical(path);
+        for dir in target.ancestors().collect::<Vec<_>>().into_iter().rev() {
+            let resolved = state.resolve(dir, true)?;
+            match state.nodes.get(&resolved) {
+                Some(node) if node.is_dir() => {}
+                Some(_) if dir == target => return Err(io::ErrorKind::AlreadyExists.into()),
+                Some(_) => return Err(io::ErrorKind::NotADirectory.into()),
+                None => {
+                    state.writable_parent(&resolved)?;
+                    state.insert(resolved, Content::Dir);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn copy(&self, from: &Path, to: &Path) -> io::Result<()> {
+        let mut state = self.state();
+        state.fault(Op::Copy, from)?;
+        state.fault(Op::Copy, to)?;
+        let (_, node) = state.lookup(from, true)?;
+        let (Content::File(data), modified) = (&node.content, node.modified) else {
+            return Err(io::ErrorKind::IsADirectory.into());
+        };
+        let data = data.clone();
+        let resolved = state.resolve(to, false)?;
+        if state.nodes.contains_key(&resolved) {
+            return Err(io::ErrorKind::AlreadyExists.into());
+        }
+        state.writable_parent(&resolved)?;
+        state.insert(resolved.clone(), Content::File(data));
+        if let Some(node) = state.nodes.get_mut(&resolved) {
+            node.modified = modified;
+        }
+        Ok(())
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        let mut state = self.state();
+        state.fault(Op::Rename, from)?;
+        state.fault(Op::Rename, to)?;
+        let (from, node) = state.lookup(from, false)?;
+        let (from_dir, from_dev) = (node.is_dir(), node.dev);
+        let to = state.resolve(to, false)?;
+        state.writable_parent(&from)?;
+        let to_parent = state.writable_parent(&to)?;
+        if to_parent.dev != from_dev {
+            return Err(io::ErrorKind::CrossesDevices.into());
+        }
+        if from == to {
+            return Ok(());
+        }
+        if to.starts_with(&from) {
+            return Err(io::ErrorKind::InvalidInput.into());
+        }
+        if let Some(existing) = state.nodes.get(&to) {
+            match (from_dir, existing.is_dir()) {
+                (false, true) => return Err(io::ErrorKind::IsADirectory.into()),
+                (true, false) => return Err(io::ErrorKind::NotADirectory.into()),
+                (true, true) if state.has_children(&to) => {
+                    return Err(io::ErrorKind::DirectoryNotEmpty.into());
+                }
+                _ => {}
+            }
+        }
+
+        let moved: Vec<PathBuf> = state
+            .nodes
+            .range(from.clone()..)
+            .take_while(|(key, _)| key.starts_with(&from))
+            .map(|(key, _)| key.clone())
+            .collect();
+        state.touch_parent(&from);
+        state.touch_parent(&to);
+        for key in moved {
+            if let Some(node) = state.nodes.remove(&key) {
+                let suffix = key.strip_prefix(&from).unwrap_or(Path::new(""));
+                let new_key = if suffix.as_os_str().is_empty() {
+                    to.clone()
+                } else {
+                    to.join(suffix)
+                };
+                state.nodes.insert(new_key, node);
+            }
+        }
+        Ok(())
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        let mut state = self.state();
+        state.fault(Op::RemoveFile, path)?;
+        let (resolved, node) = state.lookup(path, false)?;
+        if node.is_dir() {
+            return Err(io::ErrorKind::IsADirectory.into());
+        }
+        state.writable_parent(&resolved)?;
+        state.touch_parent(&resolved);
+        state.nodes.remove(&resolved);
+        Ok(())
+    }
+
+    fn remove_dir(&self, path: &Path) -> io::Result<()> {
+        let mut state = self.state();
+        state.fault(Op::RemoveDir, path)?;
+        let (resolved, node) = state.lookup(path, false)?;
+        if !node.is_dir() {
+            return Err(io::ErrorKind::NotADirectory.into());
+        }
+        if state.has_children(&resolved) {
+            return Err(io::ErrorKind::DirectoryNotEmpty.into());
+        }
+        state.writable_parent(&resolved)?;
+        state.touch_parent(&resolved);
+        state.nodes.remove(&resolved);
+        Ok(())
+    }
+
+    fn symlink(&self, target: &Path, link: &Path) -> io::Result<()> {
+        let mut state = self.state();
+        state.fault(Op::Symlink, link)?;
+        let resolved = state.resolve(link, false)?;
+        if state.nodes.contains_key(&resolved) {
+            return Err(io::ErrorKind::AlreadyExists.into());
+        }
+        state.writable_parent(&resolved)?;
+        state.insert(resolved, Content::Symlink(target.to_path_buf()));
+        Ok(())
+    }
+
+    fn check_writable(&self, dir: &Path) -> io::Result<()> {
+        let state = self.state();
+        state.fault(Op::Access, dir)?;
+        let (_, node) = state.lookup(dir, true)?;
+        if !node.is_dir() {
+            return Err(io::ErrorKind::NotADirectory.into());
+        }
+        if node.readonly {
+            return Err(io::ErrorKind::PermissionDenied.into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_fs_tracks_files_and_dirs() {
+        let fs = memory_with(&[
+            ("/r/a/1001.cpp", "/r/a/1001.cpp"),
+            ("/r/b/1002.cpp", "/r/b/1002.cpp"),
+        ]);
+
+        let mut names: Vec<OsString> = fs
+            .read_dir(Path::new("/r"))
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.name)
+            .collect();
+        names.sort();
+        assert_eq!(names, ["a", "b"]);
+        assert!(fs.metadata(Path::new("/r/a")).unwrap().is_dir());
+        assert_eq!(
+            fs.read_to_string(Path::new("/r/a/1001.cpp")).unwrap(),
+            "/r/a/1001.cpp"
+        );
+
+        fs.copy(Path::new("/r/b/1002.cpp"), Path::new("/r/b/copy.cpp"))
+            .unwrap();
+        assert_eq!(
+            fs.read_to_string(Path::new("/r/b/copy.cpp")).unwrap(),
+            "/r/b/1002.cpp"
+        );
+
+        fs.rename(Path::new("/r/a"), Path::new("/r/c")).unwrap();
+        assert!(!fs.exists(Path::new("/r/a/1001.cpp")));
+        assert!(fs.exists(Path::new("/r/c/1001.cpp")));
+
+        let error = fs.remove_dir(Path::new("/r/c")).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::DirectoryNotEmpty);
+        fs.remove_file(Path::new("/r/c/1001.cpp")).unwrap();
+        fs.remove_dir(Path::new("/r/c")).unwrap();
+        assert!(!fs.exists(Path::new("/r/c")));
+    }
+
+    #[test]
+    fn memory_fs_mirrors_std_errors() {
+        let fs = memory_with(&[("/r/1001.cpp", "")]);
+        let kind = |result: io::Result<()>| result.unwrap_err().kind();
+
+        assert_eq!(
+            kind(fs.create_dir_all(Path::new("/r/1001.cpp"))),
+            io::ErrorKind::AlreadyExists
+        );
+        assert_eq!(
+            kind(fs.create_dir_all(Path::new("/r/1001.cpp/sub"))),
+            io::ErrorKind::NotADirectory
+        );
+        assert_eq!(
+            kind(fs.copy(Path::new("/r/1001.cpp"), Path::new("/r/1001.cpp"))),
+            io::ErrorKind::AlreadyExists
+        );
+        assert_eq!(
+            kind(fs.rename(Path::new("/r/1002.cpp"), Path::new("/r/x.cpp"))),
+            io::ErrorKind::NotFound
+        );
+        assert_eq!(
+            kind(fs.rename(Path::new("/r/1001.cpp"), Path::new("/r/none/x.cpp"))),
+            io::ErrorKind::NotFound
+        );
+    }
+
+    #[test]
+    fn memory_fs_follows_links() {
+        let fs = memory_with(&[("/r/real/1001.cpp", "")]);
+        fs.symlink(Path::new("real"), Path::new("/r/linked"))
+            .unwrap();
+        fs.symlink(Path::new(".."), Path::new("/r/real/up"))
+            .unwrap();
+
+        assert!(
+            fs.symlink_metadata(Path::new("/r/linked"))
+                .unwrap()
+                .is_symlink()
+        );
+        assert!(fs.metadata(Path::new("/r/linked")).unwrap().is_dir());
+        assert_eq!(
+            fs.canonicalize(Path::new("/r/linked/1001.cpp")).unwrap(),
+            Path::new("/r/real/1001.cpp")
+        );
+        assert_eq!(
+            fs.canonicalize(Path::new("/r/real/up/linked")).unwrap(),
+            Path::new("/r/real")
+        );
+
+        fs.symlink(Path::new("loop"), Path::new("/r/loop")).unwrap();
+        assert!(fs.metadata(Path::new("/r/loop")).is_err());
+    }
+
+    #[test]
+    fn memory_fs_injects_faults() {
+        let fs = memory_with(&[("/r/a/1001.cpp", ""), ("/r/b/1002.cpp", "")]);
+        fs.inject(Op::ReadDir, "/r/a", io::ErrorKind::PermissionDenied);
+        fs.set_device("/r/b", 2).unwrap();
+        fs.set_readonly("/r/a", true).unwrap();
+
+        let kind = |result: io::Result<()>| result.unwrap_err().kind();
+        assert_eq!(
+            fs.read_dir(Path::new("/r/a")).unwrap_err().kind(),
+            io::ErrorKind::PermissionDenied
+        );
+        assert_eq!(
+            kind(fs.rename(Path::new("/r/b/1002.cpp"), Path::new("/r/1002.cpp"))),
+            io::ErrorKind::CrossesDevices
+        );
+        assert_eq!(
+            kind(fs.rename(Path::new("/r/a/1001.cpp"), Path::new("/r/1001.cpp"))),
+            io::ErrorKind::PermissionDenied
+        );
+        assert_eq!(
+            kind(fs.check_writable(Path::new("/r/a"))),
+            io::ErrorKind::PermissionDenied
+        );
+
+        fs.clear_faults();
+        fs.set_readonly("/r/a", false).unwrap();
+        assert_eq!(fs.read_dir(Path::new("/r/a")).unwrap().len(), 1);
+        fs.rename(Path::new("/r/a/1001.cpp"), Path::new("/r/1001.cpp"))
+            .unwrap();
+    }
+}
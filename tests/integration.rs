@@ -1,152 +1,905 @@
-use std::fs::{self, File};
+use ps_organizer::events::{Event, NoopObserver};
+use ps_organizer::executor::{
+    CleanupRules, cleanup_affected_dirs_in, cleanup_empty_dirs_in, execute_moves_in,
+};
+use ps_organizer::organizer::Organizer;
+#[cfg(unix)]
+use ps_organizer::organizer::ScanReport;
+use ps_organizer::planner::plan_moves;
+#[cfg(unix)]
+use ps_organizer::scanner::SymlinkPolicy;
+use ps_organizer::scanner::{ScanOptions, scan_directory_in};
+use ps_organizer::types::{Error, FileEntry, MoveOperation};
+use ps_organizer::vfs::{FileSystem, MemoryFs, Op, RealFs};
+use std::fs;
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
 use tempfile::TempDir;
 
-fn create_test_file(dir: &std::path::Path, name: &str) {
-    let path = dir.join(name);
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).unwrap();
-    }
-    File::create(&path).unwrap();
+/// 같은 테스트를 실제 파일 시스템과 메모리 파일 시스템에서 돌리기 위한 작업 공간.
+struct Sandbox {
+    label: &'static str,
+    fs: Box<dyn FileSystem>,
+    root: PathBuf,
+    _temp: Option<TempDir>,
 }
 
-#[test]
-fn integration_organize_flat_files() {
-    let temp = TempDir::new().unwrap();
-    let root = temp.path();
+impl Sandbox {
+    fn real() -> Self {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().join("work");
+        fs::create_dir(&root).unwrap();
+        Self {
+            label: "real",
+            fs: Box::new(RealFs),
+            root,
+            _temp: Some(temp),
+        }
+    }
+
+    fn memory() -> Self {
+        let fs = MemoryFs::new();
+        let root = PathBuf::from("/work");
+        fs.create_dir_all(&root).unwrap();
+        Self {
+            label: "memory",
+            fs: Box::new(fs),
+            root,
+            _temp: None,
+        }
+    }
+
+    fn fs(&self) -> &dyn FileSystem {
+        self.fs.as_ref()
+    }
+
+    fn path(&self, name: impl AsRef<Path>) -> PathBuf {
+        self.root.join(name)
+    }
+
+    /// 대상 디렉토리 옆의 다른 디렉토리 경로.
+    fn outside(&self, name: &str) -> PathBuf {
+        self.root.parent().unwrap().join(name)
+    }
+
+    /// 필요한 부모 폴더와 함께 파일을 만듭니다.
+    fn write(&self, name: impl AsRef<Path>, contents: &str) {
+        self.write_at(&self.path(name), contents);
+    }
 
-    for i in 1001..=1010 {
-        create_test_file(root, &format!("{i}.cpp"));
+    fn write_at(&self, path: &Path, contents: &str) {
+        if let Some(parent) = path.parent() {
+            self.fs.create_dir_all(parent).unwrap();
+        }
+        self.fs.write(path, contents.as_bytes()).unwrap();
     }
 
-    let entries = ps_organizer::scanner::scan_directory(root);
-    assert_eq!(entries.len(), 10);
+    fn create(&self, name: impl AsRef<Path>) {
+        self.write(name, "");
+    }
+
+    fn mkdir(&self, name: impl AsRef<Path>) {
+        self.fs.create_dir_all(&self.path(name)).unwrap();
+    }
+
+    fn exists(&self, name: impl AsRef<Path>) -> bool {
+        self.fs.exists(&self.path(name))
+    }
+
+    fn is_dir(&self, name: impl AsRef<Path>) -> bool {
+        self.fs
+            .metadata(&self.path(name))
+            .is_ok_and(|metadata| metadata.is_dir())
+    }
+
+    fn is_file(&self, name: impl AsRef<Path>) -> bool {
+        self.fs
+            .metadata(&self.path(name))
+            .is_ok_and(|metadata| metadata.is_file())
+    }
+
+    fn read(&self, name: impl AsRef<Path>) -> String {
+        self.fs.read_to_string(&self.path(name)).unwrap()
+    }
+
+    fn read_link(&self, name: impl AsRef<Path>) -> PathBuf {
+        self.fs.read_link(&self.path(name)).unwrap()
+    }
 
-    let moves = ps_organizer::planner::plan_moves(&entries, 20);
-    assert!(moves.is_empty());
+    /// 루트 바로 아래 항목 이름 목록.
+    fn children(&self) -> Vec<PathBuf> {
+        self.fs
+            .read_dir(&self.root)
+            .unwrap()
+            .into_iter()
+            .map(|entry| PathBuf::from(entry.name))
+            .collect()
+    }
+
+    fn subdirs(&self) -> Vec<PathBuf> {
+        self.children()
+            .into_iter()
+            .filter(|name| self.is_dir(name))
+            .collect()
+    }
+
+    fn scan(&self) -> Vec<FileEntry> {
+        self.scan_with(&ScanOptions::default())
+    }
+
+    fn scan_with(&self, options: &ScanOptions) -> Vec<FileEntry> {
+        scan_directory_in(self.fs(), &self.root, options, &mut NoopObserver).entries
+    }
+
+    fn execute(&self, moves: &[MoveOperation]) -> Result<(), Error> {
+        execute_moves_in(self.fs(), &self.root, moves, &mut NoopObserver)
+    }
+
+    fn organizer(&self) -> ps_organizer::organizer::OrganizerBuilder<'_> {
+        Organizer::builder(&self.root).filesystem(self.fs())
+    }
+}
+
+/// 테스트 본문을 두 파일 시스템에서 차례로 실행합니다.
+///
+/// 실패하면 어느 쪽에서 실패했는지 실패 메시지 앞에 붙입니다.
+fn on_each_fs(test: impl Fn(&Sandbox)) {
+    for sandbox in [Sandbox::real(), Sandbox::memory()] {
+        if let Err(cause) = panic::catch_unwind(AssertUnwindSafe(|| test(&sandbox))) {
+            let message = cause
+                .downcast_ref::<String>()
+                .map(String::as_str)
+                .or_else(|| cause.downcast_ref::<&str>().copied())
+                .unwrap_or("test panicked");
+            panic!("[{} filesystem] {message}", sandbox.label);
+        }
+    }
 }
 
 #[test]
-fn integration_organize_needs_grouping() {
-    let temp = TempDir::new().unwrap();
-    let root = temp.path();
+fn integration_organize_flat_files() {
+    on_each_fs(|sandbox| {
+        for i in 1001..=1010 {
+            sandbox.create(format!("{i}.cpp"));
+        }
 
-    for i in 1001..=1050 {
-        create_test_file(root, &format!("{i}.cpp"));
-    }
+        let entries = sandbox.scan();
+        assert_eq!(entries.len(), 10);
 
-    let entries = ps_organizer::scanner::scan_directory(root);
-    assert_eq!(entries.len(), 50);
+        let moves = plan_moves(&entries, 20);
+        assert!(moves.is_empty());
+    });
+}
 
-    let moves = ps_organizer::planner::plan_moves(&entries, 20);
-    assert!(!moves.is_empty());
+#[test]
+fn integration_organize_needs_grouping() {
+    on_each_fs(|sandbox| {
+        for i in 1001..=1050 {
+            sandbox.create(format!("{i}.cpp"));
+        }
 
-    ps_organizer::executor::execute_moves(root, &moves, false).unwrap();
+        let entries = sandbox.scan();
+        assert_eq!(entries.len(), 50);
 
-    assert!(!root.join("1001.cpp").exists());
+        let moves = plan_moves(&entries, 20);
+        assert!(!moves.is_empty());
 
-    let subfolders: Vec<_> = fs::read_dir(root)
-        .unwrap()
-        .filter_map(Result::ok)
-        .filter(|e| e.path().is_dir())
-        .collect();
-    assert!(!subfolders.is_empty());
+        sandbox.execute(&moves).unwrap();
+
+        assert!(!sandbox.exists("1001.cpp"));
+        assert!(!sandbox.subdirs().is_empty());
+    });
 }
 
 #[test]
 fn integration_etc_folder_for_non_matching() {
-    let temp = TempDir::new().unwrap();
-    let root = temp.path();
-
-    create_test_file(root, "solution.cpp");
-    create_test_file(root, "main.c");
-    create_test_file(root, "1001.cpp");
+    on_each_fs(|sandbox| {
+        sandbox.create("solution.cpp");
+        sandbox.create("main.c");
+        sandbox.create("1001.cpp");
 
-    let entries = ps_organizer::scanner::scan_directory(root);
-    assert_eq!(entries.len(), 3);
+        let entries = sandbox.scan();
+        assert_eq!(entries.len(), 3);
 
-    let moves = ps_organizer::planner::plan_moves(&entries, 20);
+        let moves = plan_moves(&entries, 20);
 
-    assert_eq!(moves.iter().filter(|m| m.to.starts_with("etc")).count(), 2);
+        assert_eq!(moves.iter().filter(|m| m.to.starts_with("etc")).count(), 2);
+    });
 }
 
 #[test]
 fn integration_cleanup_empty_dirs() {
-    let temp = TempDir::new().unwrap();
-    let root = temp.path();
+    on_each_fs(|sandbox| {
+        sandbox.mkdir("old/nested");
+        sandbox.create("old/nested/1001.cpp");
+
+        let entries = sandbox.scan();
+        assert_eq!(entries.len(), 1);
 
-    fs::create_dir_all(root.join("old/nested")).unwrap();
-    create_test_file(root, "old/nested/1001.cpp");
+        let moves = plan_moves(&entries, 20);
+        sandbox.execute(&moves).unwrap();
+        cleanup_empty_dirs_in(sandbox.fs(), &sandbox.root, &mut NoopObserver).unwrap();
 
-    let entries = ps_organizer::scanner::scan_directory(root);
-    assert_eq!(entries.len(), 1);
+        assert!(!sandbox.exists("old"));
+    });
+}
 
-    let moves = ps_organizer::planner::plan_moves(&entries, 20);
-    ps_organizer::executor::execute_moves(root, &moves, false).unwrap();
-    ps_organizer::executor::cleanup_empty_dirs(root, false).unwrap();
+#[test]
+fn integration_cleanup_leaves_untouched_empty_dirs() {
+    on_each_fs(|sandbox| {
+        sandbox.create("old/nested/1001.cpp");
+        sandbox.mkdir("inbox");
+        sandbox.mkdir(".git/objects/pack");
+
+        let moves = plan_moves(&sandbox.scan(), 20);
+        sandbox.execute(&moves).unwrap();
+
+        let mut removed = Vec::new();
+        cleanup_affected_dirs_in(
+            sandbox.fs(),
+            &sandbox.root,
+            &moves,
+            &CleanupRules::default(),
+            &mut |event: &Event| {
+                if let Event::DirRemoved { path } = event {
+                    removed.push(path.clone());
+                }
+            },
+        )
+        .unwrap();
+
+        assert_eq!(removed, [PathBuf::from("old/nested"), PathBuf::from("old")]);
+        assert!(sandbox.is_dir("inbox"));
+        assert!(sandbox.is_dir(".git/objects/pack"));
+    });
+}
 
-    assert!(!root.join("old").exists());
+#[test]
+fn integration_simulation_predicts_real_failures() {
+    on_each_fs(|sandbox| {
+        sandbox.create("old/1001.cpp");
+        sandbox.create("old/1002.cpp");
+        let moves = plan_moves(&sandbox.scan(), 20);
+        assert_eq!(moves.len(), 2);
+
+        // 계획을 세운 뒤 목적지 하나를 다른 파일이 차지하고, 원본 하나가 사라집니다.
+        sandbox.create(&moves[0].to);
+        sandbox
+            .fs()
+            .remove_file(&sandbox.path(&moves[1].from))
+            .unwrap();
+
+        let simulation = ps_organizer::simulate::simulate_in(
+            sandbox.fs(),
+            &sandbox.root,
+            &moves,
+            Some(&CleanupRules::default()),
+        );
+        let predicted: Vec<u8> = simulation.failures.iter().map(Error::exit_code).collect();
+        assert_eq!(predicted, [5, 4]);
+        assert!(simulation.moved.is_empty());
+        assert!(sandbox.exists(&moves[0].from));
+
+        let real = sandbox.execute(&moves).unwrap_err();
+        assert_eq!(real.exit_code(), predicted[0]);
+        assert_eq!(real.path(), simulation.failures[0].path());
+    });
 }
 
 #[test]
 fn integration_conflict_resolution() {
-    let temp = TempDir::new().unwrap();
-    let root = temp.path();
+    on_each_fs(|sandbox| {
+        sandbox.write("1001.cpp", "in place");
+        sandbox.write("backup/1001.cpp", "backup");
+
+        let entries = sandbox.scan();
+        assert_eq!(entries.len(), 2);
+
+        // 제자리에 있는 파일이 이름을 지키므로 이동은 하나뿐입니다.
+        let moves = plan_moves(&entries, 20);
+        assert_eq!(
+            moves,
+            vec![MoveOperation::new(
+                PathBuf::from("backup/1001.cpp"),
+                PathBuf::from("1001_conflict1.cpp")
+            )]
+        );
+        sandbox.execute(&moves).unwrap();
+
+        let cpp_files = sandbox
+            .children()
+            .into_iter()
+            .filter(|name| name.extension().is_some_and(|ext| ext == "cpp"))
+            .count();
+        assert_eq!(cpp_files, 2);
+        assert_eq!(sandbox.read("1001.cpp"), "in place");
+        assert_eq!(sandbox.read("1001_conflict1.cpp"), "backup");
+
+        // 충돌 이름도 같은 문제로 인식하므로 다시 스캔해도 옮길 파일이 없습니다.
+        let moves = plan_moves(&sandbox.scan(), 20);
+        assert!(moves.is_empty());
+    });
+}
 
-    create_test_file(root, "1001.cpp");
-    create_test_file(root, "backup/1001.cpp");
+#[test]
+fn integration_conflict_files_follow_their_problem() {
+    use ps_organizer::conflicts::{Resolution, find_conflicts_in, resolve_in};
+
+    on_each_fs(|sandbox| {
+        for i in 1001..=1030 {
+            sandbox.write(format!("{i}.cpp"), "main");
+        }
+        sandbox.write("1001_conflict1.cpp", "other");
+        sandbox.create("1002_2.py");
+
+        let moves = plan_moves(&sandbox.scan(), 20);
+        sandbox.execute(&moves).unwrap();
+
+        let folder = |name: &str| {
+            moves
+                .iter()
+                .find(|m| m.from == Path::new(name))
+                .and_then(|m| m.to.parent())
+                .map(Path::to_path_buf)
+                .unwrap()
+        };
+        assert_eq!(folder("1001_conflict1.cpp"), folder("1001.cpp"));
+        assert_eq!(folder("1002_2.py"), folder("1002.cpp"));
+        assert!(!sandbox.exists("etc"));
+
+        let report = find_conflicts_in(sandbox.fs(), &sandbox.root, &ScanOptions::default());
+        assert!(report.errors.is_empty());
+        assert_eq!(report.conflicts.len(), 1);
+        let conflict = &report.conflicts[0];
+        assert_eq!(conflict.main, folder("1001.cpp").join("1001.cpp"));
+
+        resolve_in(sandbox.fs(), &sandbox.root, conflict, Resolution::Promote).unwrap();
+        assert_eq!(sandbox.read(&conflict.main), "other");
+        assert_eq!(sandbox.read(&conflict.path), "main");
+
+        resolve_in(sandbox.fs(), &sandbox.root, conflict, Resolution::Delete).unwrap();
+        assert!(!sandbox.exists(&conflict.path));
+        assert!(
+            find_conflicts_in(sandbox.fs(), &sandbox.root, &ScanOptions::default())
+                .conflicts
+                .is_empty()
+        );
+    });
+}
+
+#[test]
+fn integration_plan_snapshot_is_deterministic() {
+    on_each_fs(|sandbox| {
+        for i in (1001..=1015).rev() {
+            sandbox.create(format!("{i}.cpp"));
+        }
+        for i in 2001..=2010 {
+            sandbox.create(format!("old/{i}.py"));
+        }
+        sandbox.create("old/1001.cpp");
+        sandbox.create("notes/main.cpp");
+
+        let moves = plan_moves(&sandbox.scan(), 20);
+        let snapshot: Vec<String> = moves
+            .iter()
+            .map(|m| format!("{} -> {}", m.from.display(), m.to.display()))
+            .collect();
+
+        let mut expected: Vec<String> = (1001..=1015)
+            .map(|i| format!("{i}.cpp -> 1000/{i}.cpp"))
+            .collect();
+        expected.push("notes/main.cpp -> etc/main.cpp".to_string());
+        expected.push("old/1001.cpp -> 1000/1001_conflict1.cpp".to_string());
+        expected.extend((2001..=2010).map(|i| format!("old/{i}.py -> {i}.py")));
+        assert_eq!(snapshot, expected);
+    });
+}
 
-    let entries = ps_organizer::scanner::scan_directory(root);
-    assert_eq!(entries.len(), 2);
+#[test]
+fn integration_already_organized() {
+    on_each_fs(|sandbox| {
+        for i in 1001..=1010 {
+            sandbox.create(format!("{i}.cpp"));
+        }
 
-    let moves = ps_organizer::planner::plan_moves(&entries, 20);
-    ps_organizer::executor::execute_moves(root, &moves, false).unwrap();
+        let moves = plan_moves(&sandbox.scan(), 20);
 
-    let cpp_files: Vec<_> = fs::read_dir(root)
-        .unwrap()
-        .filter_map(Result::ok)
-        .filter(|e| e.path().extension().is_some_and(|ext| ext == "cpp"))
-        .collect();
-    assert_eq!(cpp_files.len(), 2);
+        assert!(moves.is_empty());
+    });
 }
 
 #[test]
-fn integration_already_organized() {
-    let temp = TempDir::new().unwrap();
-    let root = temp.path();
+fn integration_nested_structure() {
+    on_each_fs(|sandbox| {
+        for i in 1000..=1100 {
+            sandbox.create(format!("{i}.cpp"));
+        }
+        for i in 30000..=30005 {
+            sandbox.create(format!("{i}.cpp"));
+        }
+
+        let moves = plan_moves(&sandbox.scan(), 20);
+        sandbox.execute(&moves).unwrap();
+
+        assert!(!sandbox.subdirs().is_empty());
+        assert!(sandbox.exists("30000.cpp"));
+    });
+}
 
-    for i in 1001..=1010 {
-        create_test_file(root, &format!("{i}.cpp"));
-    }
+#[test]
+fn integration_reports_events_through_observer() {
+    on_each_fs(|sandbox| {
+        sandbox.create("old/1001.cpp");
+
+        let moves = plan_moves(&sandbox.scan(), 20);
+
+        let mut events = Vec::new();
+        let mut observer = |event: &Event| events.push(event.clone());
+        execute_moves_in(sandbox.fs(), &sandbox.root, &moves, &mut observer).unwrap();
+        cleanup_empty_dirs_in(sandbox.fs(), &sandbox.root, &mut observer).unwrap();
+
+        assert_eq!(
+            events,
+            vec![
+                Event::Moved {
+                    from: PathBuf::from("old/1001.cpp"),
+                    to: PathBuf::from("1001.cpp"),
+                },
+                Event::DirRemoved {
+                    path: PathBuf::from("old"),
+                },
+            ]
+        );
+    });
+}
 
-    let entries = ps_organizer::scanner::scan_directory(root);
-    let moves = ps_organizer::planner::plan_moves(&entries, 20);
+#[test]
+fn integration_refuses_to_overwrite_destination() {
+    on_each_fs(|sandbox| {
+        sandbox.write("1001.cpp", "kept");
+        sandbox.create("old/1001.cpp");
+
+        let moves = vec![MoveOperation::new(
+            PathBuf::from("old/1001.cpp"),
+            PathBuf::from("1001.cpp"),
+        )];
+        let result = sandbox.execute(&moves);
+
+        assert!(matches!(result, Err(Error::DestinationExists { .. })));
+        assert_eq!(sandbox.read("1001.cpp"), "kept");
+        assert!(sandbox.exists("old/1001.cpp"));
+    });
+}
 
-    assert!(moves.is_empty());
+#[cfg(unix)]
+#[test]
+fn integration_organizes_non_utf8_names() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    on_each_fs(|sandbox| {
+        let name = OsStr::from_bytes(b"\xb9\xae\xc1\xa6.cpp");
+        sandbox.create(Path::new("a").join(name));
+        sandbox.create(Path::new("b").join(name));
+
+        let entries = sandbox.scan();
+        assert_eq!(entries.len(), 2);
+
+        let moves = plan_moves(&entries, 20);
+        sandbox.execute(&moves).unwrap();
+
+        assert!(sandbox.exists(Path::new("etc").join(name)));
+        assert!(
+            sandbox.exists(
+                Path::new("etc").join(OsStr::from_bytes(b"\xb9\xae\xc1\xa6_conflict1.cpp"))
+            )
+        );
+    });
 }
 
 #[test]
-fn integration_nested_structure() {
-    let temp = TempDir::new().unwrap();
-    let root = temp.path();
+fn integration_find_and_path_follow_grouping() {
+    use ps_organizer::locate::{ProblemRange, find_problem_files};
+
+    on_each_fs(|sandbox| {
+        for i in 1001..=1050 {
+            sandbox.create(format!("{i}.cpp"));
+        }
+        sandbox.create("1010.in");
+
+        let mut organizer = sandbox.organizer().build().unwrap();
+        let scan = organizer.scan();
+        let plan = organizer.plan(&scan);
+        organizer.execute(&plan).unwrap();
+        let scan = organizer.scan();
+
+        let found = find_problem_files(&scan, &ProblemRange::single(1010));
+        let solution = found
+            .iter()
+            .find(|file| file.path.extension().is_some_and(|ext| ext == "cpp"))
+            .unwrap();
+        assert_ne!(solution.path, PathBuf::from("1010.cpp"));
+        assert!(found.iter().any(|file| file.path == Path::new("1010.in")));
+
+        // 이미 있는 풀이는 현재 경로를, 새 풀이는 같은 그룹 폴더를 가리킵니다.
+        assert_eq!(organizer.placement(&scan, 1010, "cpp"), solution.path);
+        let new_path = organizer.placement(&scan, 1011, "py");
+        assert_eq!(new_path.parent(), solution.path.parent());
+
+        // 범위의 새 풀이는 모두 만든다고 보고 한 번에 배치합니다.
+        let placements = organizer.placements(&scan, "py", 1049..=1051);
+        let neighbour = &find_problem_files(&scan, &ProblemRange::single(1050))[0];
+        let folder = neighbour.path.parent().unwrap();
+        assert_eq!(
+            placements,
+            vec![
+                (1049, folder.join("1049.py")),
+                (1050, folder.join("1050.py")),
+                (1051, folder.join("1051.py")),
+            ]
+        );
+
+        let range: ProblemRange = "1001..=1050".parse().unwrap();
+        assert_eq!(find_problem_files(&scan, &range).len(), 51);
+    });
+}
 
-    for i in 1000..=1100 {
-        create_test_file(root, &format!("{i}.cpp"));
-    }
-    for i in 30000..=30005 {
-        create_test_file(root, &format!("{i}.cpp"));
-    }
+#[test]
+fn integration_cached_scan_matches_full_scan() {
+    use ps_organizer::cache::ScanCache;
+
+    on_each_fs(|sandbox| {
+        for i in 1001..=1050 {
+            sandbox.create(format!("{i}.cpp"));
+        }
+        let cached = ScanOptions {
+            cache: true,
+            ..ScanOptions::default()
+        };
+        let sorted_paths = |options: &ScanOptions| {
+            let mut paths: Vec<_> = sandbox
+                .scan_with(options)
+                .into_iter()
+                .map(|entry| entry.current_path)
+                .collect();
+            paths.sort();
+            paths
+        };
+
+        let mut organizer = sandbox.organizer().cache(true).build().unwrap();
+        let scan = organizer.scan();
+        let plan = organizer.plan(&scan);
+        let _ = organizer.simulate(&plan);
+        // 스캔과 미리보기는 캐시를 쓰지 않고, 실행한 뒤 명시적으로 저장합니다.
+        assert!(!sandbox.fs().exists(&ScanCache::path(organizer.root())));
+        organizer.execute(&plan).unwrap();
+        organizer.save_cache(&scan).unwrap();
+        assert!(sandbox.fs().exists(&ScanCache::path(organizer.root())));
+
+        // 저장한 캐시는 실행 전 트리를 담지만, 바뀐 디렉토리는 다시 읽습니다.
+        sandbox.create("1051.cpp");
+        let full = sorted_paths(&ScanOptions::default());
+        assert_eq!(full.len(), 51);
+        assert_eq!(sorted_paths(&cached), full);
+    });
+}
 
-    let entries = ps_organizer::scanner::scan_directory(root);
-    let moves = ps_organizer::planner::plan_moves(&entries, 20);
+#[cfg(unix)]
+fn organize_with_symlinks(sandbox: &Sandbox, policy: SymlinkPolicy) -> ScanReport {
+    let mut organizer = sandbox.organizer().symlinks(policy).build().unwrap();
+    let scan = organizer.scan();
+    let plan = organizer.plan(&scan);
+    organizer.execute(&plan).unwrap();
+    scan
+}
 
-    ps_organizer::executor::execute_moves(root, &moves, false).unwrap();
+#[cfg(unix)]
+#[test]
+fn integration_skip_symlinks_leaves_links_untouched() {
+    on_each_fs(|sandbox| {
+        sandbox.write("data.txt", "solution");
+        sandbox
+            .fs()
+            .symlink(Path::new("data.txt"), &sandbox.path("1001.cpp"))
+            .unwrap();
+        sandbox.create("old/1002.cpp");
+
+        organize_with_symlinks(sandbox, SymlinkPolicy::Skip);
+
+        assert!(sandbox.is_file("1002.cpp"));
+        assert_eq!(sandbox.read_link("1001.cpp"), PathBuf::from("data.txt"));
+    });
+}
 
-    let subfolders: Vec<_> = fs::read_dir(root)
-        .unwrap()
-        .filter_map(Result::ok)
-        .filter(|e| e.path().is_dir())
-        .collect();
-    assert!(!subfolders.is_empty());
+#[cfg(unix)]
+#[test]
+fn integration_follow_symlinks_moves_real_files() {
+    on_each_fs(|sandbox| {
+        sandbox.write("real/1001.cpp", "first");
+        sandbox.write("old/1002.cpp", "second");
+        sandbox
+            .fs()
+            .symlink(Path::new("real"), &sandbox.path("linked"))
+            .unwrap();
+        sandbox
+            .fs()
+            .symlink(Path::new("../old/1002.cpp"), &sandbox.path("real/latest"))
+            .unwrap();
+
+        let mut organizer = sandbox
+            .organizer()
+            .symlinks(SymlinkPolicy::Follow)
+            .build()
+            .unwrap();
+        let scan = organizer.scan();
+        let plan = organizer.plan(&scan);
+        let simulation = organizer.simulate(&plan);
+        let report = organizer.execute(&plan).unwrap();
+
+        // 링크를 거쳐 닿은 파일도 실제 경로 기준으로 한 번만 옮깁니다. 옮긴 파일을
+        // 가리키던 링크는 새 위치로 고치고, 링크가 가리키는 폴더는 비어도 남겨 깨진
+        // 링크가 생기지 않습니다.
+        assert_eq!(scan.entries.len(), 2);
+        assert_eq!(sandbox.read("1001.cpp"), "first");
+        assert_eq!(sandbox.read("1002.cpp"), "second");
+        assert_eq!(sandbox.read_link("linked"), PathBuf::from("real"));
+        assert_eq!(
+            sandbox.read_link("real/latest"),
+            PathBuf::from("../1002.cpp")
+        );
+        assert_eq!(sandbox.read("real/latest"), "second");
+        assert!(sandbox.is_dir("linked"));
+        assert_eq!(report.removed_dirs, vec![PathBuf::from("old")]);
+        assert_eq!(simulation.removed_dirs, report.removed_dirs);
+    });
+}
+
+#[cfg(unix)]
+#[test]
+fn integration_move_link_keeps_relative_target() {
+    on_each_fs(|sandbox| {
+        sandbox.write("data/sol.txt", "solution");
+        sandbox.mkdir("old");
+        sandbox
+            .fs()
+            .symlink(Path::new("../data/sol.txt"), &sandbox.path("old/1001.cpp"))
+            .unwrap();
+        let absolute = sandbox
+            .fs()
+            .canonicalize(&sandbox.root)
+            .unwrap()
+            .join("data/sol.txt");
+        sandbox
+            .fs()
+            .symlink(&absolute, &sandbox.path("old/solution.cpp"))
+            .unwrap();
+
+        organize_with_symlinks(sandbox, SymlinkPolicy::MoveLink);
+
+        // 상대 경로 링크는 새 위치에 맞게 대상을 다시 계산하고, 절대 경로 링크는 그대로 옮깁니다.
+        assert_eq!(sandbox.read_link("1001.cpp"), PathBuf::from("data/sol.txt"));
+        assert_eq!(sandbox.read_link("etc/solution.cpp"), absolute);
+        assert_eq!(sandbox.read("1001.cpp"), "solution");
+        assert_eq!(sandbox.read("data/sol.txt"), "solution");
+        assert!(!sandbox.exists("old"));
+    });
+}
+
+#[test]
+fn integration_import_merges_into_grouped_tree() {
+    use ps_organizer::import::ImportOptions;
+
+    on_each_fs(|sandbox| {
+        for i in 1001..=1020 {
+            sandbox.write(format!("{i}.cpp"), &i.to_string());
+        }
+        let source = sandbox.outside("incoming");
+        sandbox.write_at(&source.join("1001.cpp"), "1001");
+        sandbox.write_at(&source.join("old/1002.cpp"), "changed");
+        sandbox.write_at(&source.join("1021.cpp"), "1021");
+
+        let mut organizer = sandbox.organizer().build().unwrap();
+        let scan = organizer.scan();
+        let incoming = organizer.scan_source(&source).unwrap();
+        let plan = organizer
+            .plan_import(&scan, &source, &incoming, &ImportOptions::default())
+            .unwrap();
+        assert_eq!(plan.duplicates.len(), 1);
+        assert_eq!(plan.renamed.len(), 1);
+        let report = organizer.execute_import(&plan).unwrap();
+
+        // 22개가 되어 임계값을 넘으므로 기존 파일까지 그룹 폴더로 옮겨집니다.
+        assert_eq!(report.imported.len(), 2);
+        assert_eq!(report.moved.len(), 20);
+        let scan = organizer.scan();
+        assert_eq!(scan.entries.len(), 22);
+        assert!(organizer.plan(&scan).moves.is_empty());
+        let path_of = |name: &str| {
+            scan.entries
+                .iter()
+                .find(|e| e.filename == name)
+                .map(|e| e.current_path.clone())
+                .unwrap()
+        };
+        assert_eq!(
+            path_of("1002_conflict1.cpp").parent(),
+            path_of("1002.cpp").parent()
+        );
+        assert_eq!(sandbox.read(path_of("1002_conflict1.cpp")), "changed");
+        assert!(sandbox.fs().exists(&source.join("old/1002.cpp")));
+    });
+}
+
+#[test]
+fn integration_export_is_reproducible_and_leaves_tree_alone() {
+    use ps_organizer::archive::ArchiveFormat;
+    use ps_organizer::export::ExportFilter;
+    use std::cell::RefCell;
+
+    let archives = RefCell::new(Vec::new());
+    on_each_fs(|sandbox| {
+        for i in 1001..=1021 {
+            sandbox.write(format!("old/{i}.cpp"), &i.to_string());
+        }
+        sandbox.write("1001.py", "print(1001)");
+        let before = sandbox.scan();
+
+        let mut organizer = sandbox.organizer().build().unwrap();
+        let scan = organizer.scan();
+        let planned = organizer.plan(&scan);
+        let filter = ExportFilter {
+            range: Some("1001..=1010".parse().unwrap()),
+            languages: vec!["cpp".to_string()],
+            ..ExportFilter::default()
+        };
+        let files = organizer.plan_export(&scan, &filter);
+        assert_eq!(files.len(), 10);
+        for op in &files {
+            let to = planned.moves.iter().find(|m| m.from == op.from).unwrap();
+            assert_eq!(op.to, to.to);
+        }
+        archives
+            .borrow_mut()
+            .push(organizer.export(&files, ArchiveFormat::TarGz).unwrap());
+
+        assert_eq!(sandbox.scan(), before);
+        assert!(!sandbox.exists("1000"));
+    });
+
+    // 수정 시각이 다른 두 파일 시스템에서도 같은 바이트가 나옵니다.
+    let archives = archives.into_inner();
+    assert_eq!(archives[0], archives[1]);
+}
+
+#[test]
+fn integration_flatten_undoes_grouping() {
+    use ps_organizer::import::ConflictPolicy;
+
+    on_each_fs(|sandbox| {
+        for i in 1001..=1030 {
+            sandbox.write(format!("{i}.cpp"), &i.to_string());
+        }
+        sandbox.write("notes.txt", "keep");
+        let mut organizer = sandbox.organizer().build().unwrap();
+        let scan = organizer.scan();
+        let plan = organizer.plan(&scan);
+        organizer.execute(&plan).unwrap();
+        assert!(!sandbox.subdirs().is_empty());
+        sandbox.write("extra/1001.cpp", "other");
+
+        let scan = organizer.scan();
+        let plan = organizer
+            .plan_flatten(&scan, Path::new(""), ConflictPolicy::Rename)
+            .unwrap();
+        let report = organizer.execute(&plan).unwrap();
+
+        assert_eq!(report.moved.len(), 31);
+        assert!(sandbox.subdirs().is_empty());
+        // 경로가 얕은 파일이 원래 이름을 가집니다.
+        assert_eq!(sandbox.read("1001.cpp"), "other");
+        assert_eq!(sandbox.read("1001_conflict1.cpp"), "1001");
+        assert_eq!(sandbox.read("notes.txt"), "keep");
+        let scan = organizer.scan();
+        assert_eq!(scan.entries.len(), 31);
+        assert!(
+            organizer
+                .plan_flatten(&scan, Path::new(""), ConflictPolicy::Rename)
+                .unwrap()
+                .moves
+                .is_empty()
+        );
+    });
+}
+
+/// 실패를 주입할 메모리 파일 시스템과 `/work` 루트.
+fn faulty_fs() -> (MemoryFs, PathBuf) {
+    let fs = MemoryFs::new();
+    let root = PathBuf::from("/work");
+    fs.create_dir_all(&root.join("old")).unwrap();
+    fs.write(&root.join("old/1001.cpp"), b"").unwrap();
+    (fs, root)
+}
 
-    assert!(root.join("30000.cpp").exists());
+#[test]
+fn integration_rename_permission_denied_stops_execution() {
+    let (fs, root) = faulty_fs();
+    fs.write(&root.join("old/1002.cpp"), b"").unwrap();
+    let moves = plan_moves(
+        &scan_directory_in(&fs, &root, &ScanOptions::default(), &mut NoopObserver).entries,
+        20,
+    );
+    fs.inject(
+        Op::Rename,
+        root.join("old/1002.cpp"),
+        io::ErrorKind::PermissionDenied,
+    );
+
+    let mut done = Vec::new();
+    let error = execute_moves_in(&fs, &root, &moves, &mut |event: &Event| {
+        if let Event::Moved { from, .. } = event {
+            done.push(from.clone());
+        }
+    })
+    .unwrap_err();
+
+    assert!(matches!(error, Error::PermissionDenied { .. }));
+    assert_eq!(error.exit_code(), 3);
+    assert_eq!(done, [PathBuf::from("old/1001.cpp")]);
+    assert!(fs.exists(&root.join("old/1002.cpp")));
+}
+
+#[test]
+fn integration_cross_device_move_is_reported() {
+    let (fs, root) = faulty_fs();
+    fs.set_device(root.join("old"), 2).unwrap();
+    let moves = plan_moves(
+        &scan_directory_in(&fs, &root, &ScanOptions::default(), &mut NoopObserver).entries,
+        20,
+    );
+
+    let simulation = ps_organizer::simulate::simulate_in(&fs, &root, &moves, None);
+    let predicted: Vec<u8> = simulation.failures.iter().map(Error::exit_code).collect();
+    assert_eq!(predicted, [6]);
+
+    let error = execute_moves_in(&fs, &root, &moves, &mut NoopObserver).unwrap_err();
+    assert!(matches!(error, Error::CrossDevice { .. }));
+    assert!(fs.exists(&root.join("old/1001.cpp")));
+}
+
+#[test]
+fn integration_unreadable_dir_is_reported_and_skipped() {
+    let (fs, root) = faulty_fs();
+    fs.write(&root.join("1002.cpp"), b"").unwrap();
+    fs.inject(
+        Op::ReadDir,
+        root.join("old"),
+        io::ErrorKind::PermissionDenied,
+    );
+
+    let report = scan_directory_in(&fs, &root, &ScanOptions::default(), &mut NoopObserver);
+
+    assert_eq!(report.entries.len(), 1);
+    assert_eq!(report.entries[0].current_path, PathBuf::from("1002.cpp"));
+    assert_eq!(report.errors.len(), 1);
+    assert!(matches!(report.errors[0], Error::PermissionDenied { .. }));
+}
+
+#[test]
+fn integration_failed_cleanup_keeps_moves() {
+    let (fs, root) = faulty_fs();
+    fs.inject(
+        Op::RemoveDir,
+        root.join("old"),
+        io::ErrorKind::PermissionDenied,
+    );
+
+    let mut organizer = Organizer::builder(&root).filesystem(&fs).build().unwrap();
+    let scan = organizer.scan();
+    let plan = organizer.plan(&scan);
+    let error = organizer.execute(&plan).unwrap_err();
+
+    assert!(matches!(error, Error::PermissionDenied { .. }));
+    assert!(fs.exists(&root.join("1001.cpp")));
+    assert!(fs.exists(&root.join("old")));
 }